@@ -0,0 +1,535 @@
+//! Helpers for `BatchWriteItem` and `BatchGetItem`.
+//!
+//! DynamoDB accepts up to 25 write requests in a `BatchWriteItem` call and up to 100 keys
+//! in a `BatchGetItem` call, and both may return part of the requests as unprocessed.
+//! [`BatchWrite`] and [`BatchGet`] split the requests into chunks of the right size and
+//! retry the unprocessed entries with exponential backoff and jitter until everything is
+//! processed or the [`RetryPolicy`] gives up.
+//!
+//! ```rust,no_run
+//! use dynamodel::{batch::{BatchGet, BatchWrite}, Dynamodel};
+//! # use std::collections::HashMap;
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! #[derive(Dynamodel, Debug, Clone, PartialEq)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Video {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     title: String,
+//! }
+//!
+//! #[derive(Dynamodel)]
+//! struct VideoKey {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//! }
+//!
+//! # async fn run(client: aws_sdk_dynamodb::Client, videos: Vec<Video>) -> Result<(), dynamodel::batch::BatchError> {
+//! BatchWrite::new("videos")
+//!     .put_all(videos)
+//!     .send(&client)
+//!     .await?;
+//!
+//! let videos: Vec<Video> = BatchGet::new("videos")
+//!     .keys((0..300).map(|i| VideoKey { id: i.to_string() }))
+//!     .send(&client)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::ConvertError;
+use aws_sdk_dynamodb::{
+    Client,
+    types::{AttributeValue, DeleteRequest, KeysAndAttributes, PutRequest, WriteRequest},
+};
+use std::{collections::HashMap, future::Future, time::Duration};
+use thiserror::Error;
+
+/// The maximum number of write requests in a `BatchWriteItem` call.
+pub const MAX_WRITE_REQUESTS: usize = 25;
+
+/// The maximum number of keys in a `BatchGetItem` call.
+pub const MAX_GET_KEYS: usize = 100;
+
+/// An error occurs when sending a batch.
+#[derive(Debug, Error)]
+pub enum BatchError {
+    /// The SDK returned an error.
+    #[error(transparent)]
+    Sdk(#[from] Box<aws_sdk_dynamodb::Error>),
+
+    /// An item in the response could not be converted into the requested type.
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+
+    /// Some write requests were still unprocessed when the retry policy gave up.
+    #[error("{} write requests remain unprocessed", .0.len())]
+    UnprocessedItems(Vec<WriteRequest>),
+
+    /// Some keys were still unprocessed when the retry policy gave up.
+    #[error("{} keys remain unprocessed", .0.len())]
+    UnprocessedKeys(Vec<HashMap<String, AttributeValue>>),
+}
+
+impl BatchError {
+    fn sdk(err: impl Into<aws_sdk_dynamodb::Error>) -> Self {
+        Self::Sdk(Box::new(err.into()))
+    }
+}
+
+/// How unprocessed entries are retried.
+///
+/// The delay before the n-th retry is a random duration between zero and
+/// `min(max_delay, base_delay * 2^(n - 1))`, the "full jitter" strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default policy: 10 attempts, 50ms base delay and 5s maximum delay.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many times a chunk is sent at most, including the first attempt.
+    pub fn with_max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the upper bound of the delay before the first retry.
+    pub fn with_base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+
+    /// Sets the upper bound of the delay before any retry.
+    pub fn with_max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        exp.mul_f64(fastrand::f64())
+    }
+
+    // Sends `pending` until nothing is left unprocessed and returns what all calls returned.
+    async fn run<T, O, F, Fut>(
+        &self,
+        mut pending: Vec<T>,
+        mut send: F,
+    ) -> Result<Vec<O>, (Vec<T>, Option<BatchError>)>
+    where
+        F: FnMut(Vec<T>) -> Fut,
+        Fut: Future<Output = Result<(Vec<O>, Vec<T>), (Vec<T>, BatchError)>>,
+    {
+        let mut outputs = vec![];
+        let mut attempts = 0;
+
+        while !pending.is_empty() {
+            if attempts >= self.max_attempts {
+                return Err((pending, None));
+            }
+            if attempts > 0 {
+                tokio::time::sleep(self.delay(attempts)).await;
+            }
+            attempts += 1;
+
+            let (mut out, unprocessed) = send(pending).await.map_err(|(p, e)| (p, Some(e)))?;
+            outputs.append(&mut out);
+            pending = unprocessed;
+        }
+
+        Ok(outputs)
+    }
+}
+
+/// A builder for `BatchWriteItem` calls on one table.
+///
+/// Items of different types can be put in the same batch as long as they can be
+/// converted into `HashMap<String, AttributeValue>`.
+#[derive(Debug, Clone)]
+pub struct BatchWrite {
+    table_name: String,
+    requests: Vec<WriteRequest>,
+    retry_policy: RetryPolicy,
+}
+
+impl BatchWrite {
+    /// Creates an empty batch for the table.
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            requests: vec![],
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Adds a put request of the item.
+    pub fn put<T>(mut self, item: T) -> Self
+    where
+        T: Into<HashMap<String, AttributeValue>>,
+    {
+        let request = PutRequest::builder()
+            .set_item(Some(item.into()))
+            .build()
+            .expect("item is set");
+        self.requests
+            .push(WriteRequest::builder().put_request(request).build());
+        self
+    }
+
+    /// Adds put requests of all the items.
+    pub fn put_all<I, T>(self, items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<HashMap<String, AttributeValue>>,
+    {
+        items.into_iter().fold(self, Self::put)
+    }
+
+    /// Adds a delete request of the key.
+    pub fn delete<K>(mut self, key: K) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        let request = DeleteRequest::builder()
+            .set_key(Some(key.into()))
+            .build()
+            .expect("key is set");
+        self.requests
+            .push(WriteRequest::builder().delete_request(request).build());
+        self
+    }
+
+    /// Adds delete requests of all the keys.
+    pub fn delete_all<I, K>(self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        keys.into_iter().fold(self, Self::delete)
+    }
+
+    /// Sets how unprocessed items are retried.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// The write requests in the batch.
+    pub fn requests(&self) -> &[WriteRequest] {
+        &self.requests
+    }
+
+    /// Sends the batch in chunks of 25 requests.
+    ///
+    /// If an error occurs, the requests of the failed chunk and all the following chunks
+    /// are not sent. [`BatchError::UnprocessedItems`] returns the requests which were not
+    /// written when the retry policy gave up.
+    pub async fn send(self, client: &Client) -> Result<(), BatchError> {
+        let Self {
+            table_name,
+            requests,
+            retry_policy,
+        } = self;
+
+        for chunk in chunks(requests, MAX_WRITE_REQUESTS) {
+            retry_policy
+                .run(chunk, |requests| {
+                    let request = client
+                        .batch_write_item()
+                        .request_items(&table_name, requests.clone());
+                    let table_name = &table_name;
+                    async move {
+                        let output = request
+                            .send()
+                            .await
+                            .map_err(|e| (requests, BatchError::sdk(e)))?;
+                        let unprocessed = output
+                            .unprocessed_items
+                            .and_then(|mut items| items.remove(table_name))
+                            .unwrap_or_default();
+                        Ok((Vec::<()>::new(), unprocessed))
+                    }
+                })
+                .await
+                .map_err(|(requests, err)| err.unwrap_or(BatchError::UnprocessedItems(requests)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder for `BatchGetItem` calls on one table.
+#[derive(Debug, Clone)]
+pub struct BatchGet {
+    table_name: String,
+    keys: Vec<HashMap<String, AttributeValue>>,
+    consistent_read: Option<bool>,
+    projection_expression: Option<String>,
+    expression_attribute_names: Option<HashMap<String, String>>,
+    retry_policy: RetryPolicy,
+}
+
+impl BatchGet {
+    /// Creates an empty batch for the table.
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            keys: vec![],
+            consistent_read: None,
+            projection_expression: None,
+            expression_attribute_names: None,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Adds a key to get.
+    pub fn key<K>(mut self, key: K) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Adds all the keys to get.
+    pub fn keys<I, K>(self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        keys.into_iter().fold(self, Self::key)
+    }
+
+    /// Uses strongly consistent reads.
+    pub fn consistent_read(self, consistent_read: bool) -> Self {
+        Self {
+            consistent_read: Some(consistent_read),
+            ..self
+        }
+    }
+
+    /// Sets the `ProjectionExpression` of the requests.
+    pub fn projection_expression(self, expression: impl Into<String>) -> Self {
+        Self {
+            projection_expression: Some(expression.into()),
+            ..self
+        }
+    }
+
+    /// Adds a placeholder for an attribute name in the `ProjectionExpression`.
+    pub fn expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .get_or_insert_with(HashMap::new)
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    /// Sets how unprocessed keys are retried.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// The keys in the batch.
+    pub fn key_list(&self) -> &[HashMap<String, AttributeValue>] {
+        &self.keys
+    }
+
+    /// Sends the batch in chunks of 100 keys and converts the found items into `T`.
+    ///
+    /// Like `BatchGetItem` itself, the items are returned in no particular order and keys
+    /// that don't exist are silently skipped. To load items of different types at once,
+    /// use an enum that derives [`Dynamodel`](crate::Dynamodel) with the `tag` attribute,
+    /// or [`BatchGet::send_raw`].
+    pub async fn send<T>(self, client: &Client) -> Result<Vec<T>, BatchError>
+    where
+        T: TryFrom<HashMap<String, AttributeValue>, Error = ConvertError>,
+    {
+        self.send_raw(client)
+            .await?
+            .into_iter()
+            .map(|item| T::try_from(item).map_err(BatchError::from))
+            .collect()
+    }
+
+    /// Sends the batch in chunks of 100 keys and returns the found items as they are.
+    pub async fn send_raw(
+        self,
+        client: &Client,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, BatchError> {
+        let Self {
+            table_name,
+            keys,
+            consistent_read,
+            projection_expression,
+            expression_attribute_names,
+            retry_policy,
+        } = self;
+
+        let mut items = Vec::with_capacity(keys.len());
+
+        for chunk in chunks(keys, MAX_GET_KEYS) {
+            let mut found = retry_policy
+                .run(chunk, |keys| {
+                    let keys_and_attributes = KeysAndAttributes::builder()
+                        .set_keys(Some(keys.clone()))
+                        .set_consistent_read(consistent_read)
+                        .set_projection_expression(projection_expression.clone())
+                        .set_expression_attribute_names(expression_attribute_names.clone())
+                        .build()
+                        .expect("keys are set");
+                    let request = client
+                        .batch_get_item()
+                        .request_items(&table_name, keys_and_attributes);
+                    let table_name = &table_name;
+                    async move {
+                        let output = request
+                            .send()
+                            .await
+                            .map_err(|e| (keys, BatchError::sdk(e)))?;
+                        let found = output
+                            .responses
+                            .and_then(|mut responses| responses.remove(table_name))
+                            .unwrap_or_default();
+                        let unprocessed = output
+                            .unprocessed_keys
+                            .and_then(|mut keys| keys.remove(table_name))
+                            .map(|keys| keys.keys)
+                            .unwrap_or_default();
+                        Ok((found, unprocessed))
+                    }
+                })
+                .await
+                .map_err(|(keys, err)| err.unwrap_or(BatchError::UnprocessedKeys(keys)))?;
+            items.append(&mut found);
+        }
+
+        Ok(items)
+    }
+}
+
+fn chunks<T>(values: Vec<T>, size: usize) -> impl Iterator<Item = Vec<T>> {
+    let mut values = values.into_iter().peekable();
+    std::iter::from_fn(move || {
+        values.peek()?;
+        Some(values.by_ref().take(size).collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn values_are_split_into_chunks() {
+        let sizes: Vec<usize> = chunks((0..60).collect(), MAX_WRITE_REQUESTS)
+            .map(|chunk: Vec<i32>| chunk.len())
+            .collect();
+        assert_eq!(sizes, vec![25, 25, 10]);
+
+        let sizes: Vec<usize> = chunks((0..200).collect(), MAX_GET_KEYS)
+            .map(|chunk: Vec<i32>| chunk.len())
+            .collect();
+        assert_eq!(sizes, vec![100, 100]);
+
+        assert_eq!(chunks(Vec::<i32>::new(), MAX_GET_KEYS).count(), 0);
+    }
+
+    #[test]
+    fn delay_grows_exponentially_up_to_max_delay() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(300));
+
+        for _ in 0..100 {
+            assert!(policy.delay(1) <= Duration::from_millis(100));
+            assert!(policy.delay(2) <= Duration::from_millis(200));
+            assert!(policy.delay(3) <= Duration::from_millis(300));
+            assert!(policy.delay(30) <= Duration::from_millis(300));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unprocessed_entries_are_retried() {
+        let calls = RefCell::new(vec![]);
+
+        let result = RetryPolicy::new()
+            .run(vec![1, 2, 3, 4], |pending| {
+                calls.borrow_mut().push(pending.clone());
+                // Process only the first entry on each call.
+                let (done, rest) = pending.split_at(1);
+                let result = Ok((done.to_vec(), rest.to_vec()));
+                async move { result }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            calls.into_inner(),
+            vec![vec![1, 2, 3, 4], vec![2, 3, 4], vec![3, 4], vec![4]],
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unprocessed_entries_are_returned_when_retry_policy_gives_up() {
+        let result = RetryPolicy::new()
+            .with_max_attempts(3)
+            .run(vec![1, 2], |pending| async move {
+                Ok((Vec::<i32>::new(), pending))
+            })
+            .await;
+
+        assert!(matches!(result, Err((pending, None)) if pending == vec![1, 2]));
+    }
+
+    #[test]
+    fn batch_write_accepts_different_item_types() {
+        let video: HashMap<String, AttributeValue> =
+            [("PK".to_string(), AttributeValue::S("video#1".into()))].into();
+        let stats: HashMap<String, AttributeValue> = [
+            ("PK".to_string(), AttributeValue::S("video#1".into())),
+            ("SK".to_string(), AttributeValue::S("stats".into())),
+        ]
+        .into();
+
+        let batch = BatchWrite::new("videos")
+            .put(video.clone())
+            .put_all(vec![stats])
+            .delete(video);
+
+        assert_eq!(batch.requests().len(), 3);
+        assert!(batch.requests()[0].put_request().is_some());
+        assert!(batch.requests()[2].delete_request().is_some());
+    }
+}
@@ -0,0 +1,312 @@
+//! Compressed attributes.
+//!
+//! Fields with the `compress` attribute of the [`Dynamodel`](crate::Dynamodel) macro are
+//! converted into an `AttributeValue` as usual, and then compressed into `AttributeValue::B` with
+//! a small header. The macro calls these functions, and they are public for hand-written
+//! conversions of the same attributes.
+//!
+//! ```rust
+//! # #[cfg(feature = "zstd")]
+//! # mod example {
+//! use dynamodel::Dynamodel;
+//!
+//! #[derive(Dynamodel)]
+//! struct Document {
+//!     id: String,
+//!     #[dynamodel(compress = "zstd")]
+//!     body: String,
+//! }
+//! # }
+//! ```
+//!
+//! The header is 5 bytes:
+//!
+//! | Bytes | Value |
+//! |---|---|
+//! | 0 - 2 | `DZ` and the version of the header, `1` |
+//! | 3 | The algorithm, `z` for zstd and `g` for gzip |
+//! | 4 | What is compressed, `S` for the string of `S`, `B` for the bytes of `B`, and `J` for the DynamoDB JSON of any other value |
+//!
+//! Values without the header are read as they are, so attributes written before adding the
+//! `compress` attribute stay readable and are compressed the next time they are written.
+//!
+//! A few hundred bytes can decompress into gigabytes, so decompressing stops at
+//! [`MAX_DECOMPRESSED_LEN`] bytes with [`CompressionError::TooLarge`].
+
+use super::ConvertError;
+use super::payload::{self, PayloadError};
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use std::io::{self, Read};
+use std::string::FromUtf8Error;
+use thiserror::Error;
+
+const MAGIC: &[u8] = b"DZ\x01";
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// The largest size of a decompressed value, 8 MiB.
+///
+/// DynamoDB items are up to 400 KB, so this is far more than any value compresses from unless
+/// it is mostly repetition.
+pub const MAX_DECOMPRESSED_LEN: usize = 8 * 1024 * 1024;
+
+/// An error occurs when compressing or decompressing an attribute.
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    /// The value is compressed with an algorithm whose feature is not enabled.
+    #[error("the `{0}` feature is needed to decompress the value")]
+    Disabled(&'static str),
+
+    /// The header has an unknown algorithm or kind of the value.
+    #[error("unknown header `{0:?}`")]
+    InvalidHeader(Vec<u8>),
+
+    /// The decompressed value is larger than [`MAX_DECOMPRESSED_LEN`].
+    #[error("the decompressed value is larger than {0} bytes")]
+    TooLarge(usize),
+
+    /// Compressing or decompressing failed, like with corrupted data.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// The compressed string is not UTF-8.
+    #[error(transparent)]
+    Utf8(#[from] FromUtf8Error),
+
+    /// The value can't be written as DynamoDB JSON, or the compressed DynamoDB JSON is invalid.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+}
+
+/// The compression algorithm of an attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Zstandard, with the `zstd` feature.
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// Gzip, with the `gzip` feature.
+    #[cfg(feature = "gzip")]
+    Gzip,
+}
+
+impl Compression {
+    fn id(self) -> u8 {
+        match self {
+            #[cfg(feature = "zstd")]
+            Self::Zstd => b'z',
+            #[cfg(feature = "gzip")]
+            Self::Gzip => b'g',
+        }
+    }
+
+    fn from_id(id: u8, header: &[u8]) -> Result<Self, CompressionError> {
+        match id {
+            #[cfg(feature = "zstd")]
+            b'z' => Ok(Self::Zstd),
+            #[cfg(not(feature = "zstd"))]
+            b'z' => Err(CompressionError::Disabled("zstd")),
+            #[cfg(feature = "gzip")]
+            b'g' => Ok(Self::Gzip),
+            #[cfg(not(feature = "gzip"))]
+            b'g' => Err(CompressionError::Disabled("gzip")),
+            _ => Err(CompressionError::InvalidHeader(header.to_vec())),
+        }
+    }
+
+    fn compress(self, out: Vec<u8>, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let mut out = out;
+                zstd::stream::copy_encode(bytes, &mut out, 0)?;
+                Ok(out)
+            }
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                use std::io::Write;
+
+                let mut encoder =
+                    flate2::write::GzEncoder::new(out, flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self {
+            #[cfg(feature = "zstd")]
+            Self::Zstd => read_limited(zstd::stream::read::Decoder::new(bytes)?),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => read_limited(flate2::read::GzDecoder::new(bytes)),
+        }
+    }
+}
+
+/// Compresses the value of the attribute into `AttributeValue::B` with the header.
+///
+/// The error is [`ConvertError::Compression`] with the name of the attribute.
+pub fn compress(
+    name: &str,
+    compression: Compression,
+    value: AttributeValue,
+) -> Result<AttributeValue, ConvertError> {
+    let (kind, bytes) = payload::to_bytes(value).map_err(|e| compression_err(name, e))?;
+
+    let mut header = Vec::with_capacity(HEADER_LEN + bytes.len() / 2);
+    header.extend_from_slice(MAGIC);
+    header.extend([compression.id(), kind]);
+
+    compression
+        .compress(header, &bytes)
+        .map(|bytes| AttributeValue::B(Blob::new(bytes)))
+        .map_err(|e| compression_err(name, e))
+}
+
+/// Decompresses the value of the attribute. Values without the header are returned as they are.
+///
+/// The error is [`ConvertError::Compression`] with the name of the attribute.
+pub fn decompress(name: &str, value: AttributeValue) -> Result<AttributeValue, ConvertError> {
+    match value {
+        AttributeValue::B(v) if has_header(v.as_ref()) => {
+            decompress_bytes(v.as_ref()).map_err(|e| compression_err(name, e))
+        }
+        value => Ok(value),
+    }
+}
+
+/// Whether the value is compressed by this module.
+pub fn is_compressed(value: &AttributeValue) -> bool {
+    match value {
+        AttributeValue::B(v) => has_header(v.as_ref()),
+        _ => false,
+    }
+}
+
+fn has_header(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN && bytes.starts_with(MAGIC)
+}
+
+fn decompress_bytes(bytes: &[u8]) -> Result<AttributeValue, CompressionError> {
+    let (header, compressed) = bytes.split_at(HEADER_LEN);
+    let compression = Compression::from_id(header[MAGIC.len()], header)?;
+    let bytes = compression.decompress(compressed)?;
+
+    payload::from_bytes::<CompressionError>(header[MAGIC.len() + 1], bytes)?
+        .ok_or_else(|| CompressionError::InvalidHeader(header.to_vec()))
+}
+
+// Reads one byte more than the limit to tell a value of exactly the limit from a larger one.
+fn read_limited(reader: impl Read) -> Result<Vec<u8>, CompressionError> {
+    let mut out = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_LEN as u64 + 1)
+        .read_to_end(&mut out)?;
+    if out.len() > MAX_DECOMPRESSED_LEN {
+        return Err(CompressionError::TooLarge(MAX_DECOMPRESSED_LEN));
+    }
+    Ok(out)
+}
+
+fn compression_err(name: &str, err: impl Into<CompressionError>) -> ConvertError {
+    ConvertError::Compression(name.into(), Box::new(err.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn algorithms() -> Vec<Compression> {
+        vec![
+            #[cfg(feature = "zstd")]
+            Compression::Zstd,
+            #[cfg(feature = "gzip")]
+            Compression::Gzip,
+        ]
+    }
+
+    #[test]
+    fn values_are_compressed_with_the_header() {
+        let body = "lorem ipsum ".repeat(100);
+
+        for compression in algorithms() {
+            let compressed =
+                compress("body", compression, AttributeValue::S(body.clone())).unwrap();
+            let bytes = compressed.as_b().unwrap().as_ref();
+            assert_eq!(bytes[..3], *b"DZ\x01");
+            assert_eq!(bytes[3], compression.id());
+            assert_eq!(bytes[4], b'S');
+            assert!(bytes.len() < body.len());
+
+            assert_eq!(
+                decompress("body", compressed).unwrap(),
+                AttributeValue::S(body.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn any_value_is_compressed() {
+        let values = [
+            AttributeValue::B(Blob::new(vec![0, 1, 2])),
+            AttributeValue::N("1.5".into()),
+            AttributeValue::L(vec![AttributeValue::M(
+                [("a".to_string(), AttributeValue::Ss(vec!["x".into()]))].into(),
+            )]),
+        ];
+
+        for compression in algorithms() {
+            for value in values.clone() {
+                let compressed = compress("v", compression, value.clone()).unwrap();
+                assert!(is_compressed(&compressed));
+                assert_eq!(decompress("v", compressed).unwrap(), value);
+            }
+        }
+    }
+
+    #[test]
+    fn values_without_the_header_are_read_as_they_are() {
+        for value in [
+            AttributeValue::S("legacy".into()),
+            AttributeValue::B(Blob::new(b"DZ".to_vec())),
+            AttributeValue::B(Blob::new(b"plain bytes".to_vec())),
+        ] {
+            assert!(!is_compressed(&value));
+            assert_eq!(decompress("v", value.clone()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn corrupted_values_are_errors() {
+        let value = AttributeValue::B(Blob::new(b"DZ\x01zS not zstd".to_vec()));
+        assert!(matches!(
+            decompress("body", value),
+            Err(ConvertError::Compression(name, _)) if name == "body"
+        ));
+
+        let value = AttributeValue::B(Blob::new(b"DZ\x01xS".to_vec()));
+        let err = decompress("body", value).unwrap_err();
+        assert!(err.to_string().contains("unknown header"));
+    }
+
+    #[test]
+    fn values_larger_than_the_limit_are_errors() {
+        for compression in algorithms() {
+            let value = AttributeValue::B(Blob::new(vec![0; MAX_DECOMPRESSED_LEN]));
+            let compressed = compress("body", compression, value.clone()).unwrap();
+            assert_eq!(decompress("body", compressed).unwrap(), value);
+
+            let value = AttributeValue::B(Blob::new(vec![0; MAX_DECOMPRESSED_LEN + 1]));
+            let compressed = compress("body", compression, value).unwrap();
+            assert!(compressed.as_b().unwrap().as_ref().len() < 64 * 1024);
+
+            let Err(ConvertError::Compression(name, err)) = decompress("body", compressed) else {
+                panic!("the value must be too large");
+            };
+            assert_eq!(name, "body");
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(CompressionError::TooLarge(MAX_DECOMPRESSED_LEN))
+            ));
+        }
+    }
+}
@@ -0,0 +1,739 @@
+//! Opaque pagination cursors.
+//!
+//! `Query` and `Scan` return the `LastEvaluatedKey` as a `HashMap<String, AttributeValue>`,
+//! which is not something you want to hand over to API clients as is.
+//! [`CursorCodec`] turns such a key into a compact, URL-safe string and back, so that you
+//! can give it to clients as a pagination token and use it as the `ExclusiveStartKey`
+//! of the next request.
+//!
+//! ```rust
+//! use dynamodel::{cursor::CursorCodec, KeySchema};
+//! # use std::collections::HashMap;
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! let codec = CursorCodec::new()
+//!     .with_secret("my-signing-secret")
+//!     .with_table_keys(KeySchema::new("PK").with_sort_key("SK"));
+//!
+//! let last_evaluated_key: HashMap<String, AttributeValue> = [
+//!     ("PK".to_string(), AttributeValue::S("7cf27a02".into())),
+//!     ("SK".to_string(), AttributeValue::S("VideoComment#2023-04-05T12:34:56".into())),
+//! ].into();
+//!
+//! let cursor = codec.encode(&last_evaluated_key).unwrap();
+//! let exclusive_start_key = codec.decode(&cursor).unwrap();
+//! assert_eq!(exclusive_start_key, last_evaluated_key);
+//! ```
+//!
+//! Every cursor carries a checksum, so a corrupted cursor is rejected instead of being
+//! sent to DynamoDB. When a secret is set with [`CursorCodec::with_secret`], the cursor is
+//! signed with HMAC-SHA256 instead and cursors tampered with by clients are rejected too.
+
+use super::KeySchema;
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use thiserror::Error;
+
+const VERSION: u8 = 1;
+const FLAG_SIGNED: u8 = 0b0000_0001;
+const CHECKSUM_LEN: usize = 4;
+const SIGNATURE_LEN: usize = 16;
+const HEADER_LEN: usize = 2;
+// DynamoDB supports up to 32 levels of nested attributes.
+const MAX_DEPTH: usize = 32;
+
+const TAG_S: u8 = 0;
+const TAG_N: u8 = 1;
+const TAG_B: u8 = 2;
+const TAG_BOOL: u8 = 3;
+const TAG_NULL: u8 = 4;
+const TAG_SS: u8 = 5;
+const TAG_NS: u8 = 6;
+const TAG_BS: u8 = 7;
+const TAG_L: u8 = 8;
+const TAG_M: u8 = 9;
+
+/// An error occurs when encoding or decoding a cursor.
+#[derive(Debug, Error)]
+pub enum CursorError {
+    /// The cursor is not a valid URL-safe base64 string.
+    #[error("cursor is not valid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+
+    /// The cursor is not in the expected binary format.
+    #[error("cursor is malformed")]
+    Malformed,
+
+    /// The cursor was made by an unknown version of the codec.
+    #[error("unsupported cursor version `{0}`")]
+    UnsupportedVersion(u8),
+
+    /// The checksum of an unsigned cursor does not match its contents.
+    #[error("cursor checksum does not match")]
+    ChecksumMismatch,
+
+    /// The signature of the cursor is missing or does not match its contents.
+    #[error("cursor signature is invalid")]
+    InvalidSignature,
+
+    /// The key holds an `AttributeValue` variant the codec doesn't know.
+    #[error("cannot encode `{0:?}` into a cursor")]
+    UnsupportedValue(AttributeValue),
+
+    /// The key holds an attribute that is not a key attribute of the table or index.
+    #[error("`{0}` is not a key attribute")]
+    UnexpectedAttribute(String),
+
+    /// The key lacks one of the key attributes of the table or index.
+    #[error("key attribute `{0}` is missing")]
+    MissingAttribute(String),
+
+    /// The key holds lists or maps nested deeper than DynamoDB supports.
+    #[error("key is nested deeper than {MAX_DEPTH} levels")]
+    TooDeep,
+}
+
+/// Encodes an exclusive start key into an unsigned cursor.
+///
+/// This is a shorthand for `CursorCodec::new().encode(key)`.
+pub fn encode(key: &HashMap<String, AttributeValue>) -> Result<String, CursorError> {
+    CursorCodec::new().encode(key)
+}
+
+/// Decodes an unsigned cursor into an exclusive start key.
+///
+/// This is a shorthand for `CursorCodec::new().decode(cursor)`.
+pub fn decode(cursor: &str) -> Result<HashMap<String, AttributeValue>, CursorError> {
+    CursorCodec::new().decode(cursor)
+}
+
+/// Converts exclusive start keys from and into opaque cursor strings.
+#[derive(Clone, Default)]
+pub struct CursorCodec {
+    secret: Option<Vec<u8>>,
+    table_keys: Option<KeySchema>,
+    index_keys: Option<KeySchema>,
+}
+
+impl std::fmt::Debug for CursorCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorCodec")
+            .field("secret", &self.secret.as_ref().map(|_| "***"))
+            .field("table_keys", &self.table_keys)
+            .field("index_keys", &self.index_keys)
+            .finish()
+    }
+}
+
+impl CursorCodec {
+    /// Creates a codec that makes unsigned cursors and doesn't validate key attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signs cursors with HMAC-SHA256 using this secret.
+    ///
+    /// A codec with a secret rejects unsigned cursors and cursors signed with another secret.
+    pub fn with_secret(self, secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: Some(secret.into()),
+            ..self
+        }
+    }
+
+    /// Requires keys to hold the key attributes of the table.
+    pub fn with_table_keys(self, schema: KeySchema) -> Self {
+        Self {
+            table_keys: Some(schema),
+            ..self
+        }
+    }
+
+    /// Requires keys to hold the key attributes of the index.
+    ///
+    /// The `LastEvaluatedKey` of a query on a secondary index holds both the table keys and
+    /// the index keys, so set both of them with [`CursorCodec::with_table_keys`].
+    pub fn with_index_keys(self, schema: KeySchema) -> Self {
+        Self {
+            index_keys: Some(schema),
+            ..self
+        }
+    }
+
+    /// Encodes an exclusive start key into a cursor.
+    ///
+    /// The same key always results in the same cursor.
+    pub fn encode(&self, key: &HashMap<String, AttributeValue>) -> Result<String, CursorError> {
+        self.validate(key)?;
+
+        let mut bytes = vec![VERSION, 0];
+        write_map(&mut bytes, key, 0)?;
+
+        match self.secret.as_deref() {
+            Some(secret) => {
+                bytes[1] |= FLAG_SIGNED;
+                let signature = sign(secret, &bytes);
+                bytes.extend_from_slice(&signature[..SIGNATURE_LEN]);
+            }
+            None => {
+                let checksum = checksum(&bytes);
+                bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+            }
+        }
+
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Decodes a cursor into an exclusive start key.
+    pub fn decode(&self, cursor: &str) -> Result<HashMap<String, AttributeValue>, CursorError> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor)?;
+
+        if bytes.len() < HEADER_LEN {
+            return Err(CursorError::Malformed);
+        }
+        if bytes[0] != VERSION {
+            return Err(CursorError::UnsupportedVersion(bytes[0]));
+        }
+
+        let signed = bytes[1] & FLAG_SIGNED != 0;
+        let body = match self.secret.as_deref() {
+            Some(secret) => {
+                if !signed || bytes.len() < HEADER_LEN + SIGNATURE_LEN {
+                    return Err(CursorError::InvalidSignature);
+                }
+                let (body, signature) = bytes.split_at(bytes.len() - SIGNATURE_LEN);
+                verify(secret, body, signature)?;
+                body
+            }
+            None => {
+                if signed {
+                    return Err(CursorError::InvalidSignature);
+                }
+                if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+                    return Err(CursorError::Malformed);
+                }
+                let (body, expected) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+                if checksum(body)[..CHECKSUM_LEN] != *expected {
+                    return Err(CursorError::ChecksumMismatch);
+                }
+                body
+            }
+        };
+
+        let mut reader = Reader::new(&body[HEADER_LEN..]);
+        let key = reader.read_map(0)?;
+        if !reader.is_empty() {
+            return Err(CursorError::Malformed);
+        }
+
+        self.validate(&key)?;
+        Ok(key)
+    }
+
+    fn validate(&self, key: &HashMap<String, AttributeValue>) -> Result<(), CursorError> {
+        let schemas: Vec<&KeySchema> = self.table_keys.iter().chain(&self.index_keys).collect();
+
+        if schemas.is_empty() {
+            return Ok(());
+        }
+
+        for name in schemas.iter().flat_map(|s| s.attribute_names()) {
+            if !key.contains_key(name) {
+                return Err(CursorError::MissingAttribute(name.into()));
+            }
+        }
+
+        let mut names: Vec<&String> = key.keys().collect();
+        names.sort();
+        for name in names {
+            if !schemas.iter().any(|s| s.contains(name)) {
+                return Err(CursorError::UnexpectedAttribute(name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn checksum(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes).to_vec()
+}
+
+fn mac(secret: &[u8]) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length")
+}
+
+fn sign(secret: &[u8], bytes: &[u8]) -> Vec<u8> {
+    let mut mac = mac(secret);
+    mac.update(bytes);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn verify(secret: &[u8], bytes: &[u8], signature: &[u8]) -> Result<(), CursorError> {
+    let mut mac = mac(secret);
+    mac.update(bytes);
+    mac.verify_truncated_left(signature)
+        .map_err(|_| CursorError::InvalidSignature)
+}
+
+fn write_len(bytes: &mut Vec<u8>, mut len: usize) {
+    // LEB128
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn write_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    write_len(bytes, value.len());
+    bytes.extend_from_slice(value);
+}
+
+// Nested values are checked against `MAX_DEPTH` like `Reader` does, so that every cursor this
+// codec makes can be decoded.
+fn write_map(
+    bytes: &mut Vec<u8>,
+    map: &HashMap<String, AttributeValue>,
+    depth: usize,
+) -> Result<(), CursorError> {
+    if depth > MAX_DEPTH {
+        return Err(CursorError::TooDeep);
+    }
+
+    // Sort the entries so that the same key always results in the same cursor.
+    let mut entries: Vec<(&String, &AttributeValue)> = map.iter().collect();
+    entries.sort_by_key(|(name, _)| *name);
+
+    write_len(bytes, entries.len());
+    for (name, value) in entries {
+        write_bytes(bytes, name.as_bytes());
+        write_value(bytes, value, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn write_value(
+    bytes: &mut Vec<u8>,
+    value: &AttributeValue,
+    depth: usize,
+) -> Result<(), CursorError> {
+    match value {
+        AttributeValue::S(v) => {
+            bytes.push(TAG_S);
+            write_bytes(bytes, v.as_bytes());
+        }
+        AttributeValue::N(v) => {
+            bytes.push(TAG_N);
+            write_bytes(bytes, v.as_bytes());
+        }
+        AttributeValue::B(v) => {
+            bytes.push(TAG_B);
+            write_bytes(bytes, v.as_ref());
+        }
+        AttributeValue::Bool(v) => {
+            bytes.push(TAG_BOOL);
+            bytes.push(*v as u8);
+        }
+        AttributeValue::Null(v) => {
+            bytes.push(TAG_NULL);
+            bytes.push(*v as u8);
+        }
+        AttributeValue::Ss(values) => {
+            bytes.push(TAG_SS);
+            write_len(bytes, values.len());
+            for v in values {
+                write_bytes(bytes, v.as_bytes());
+            }
+        }
+        AttributeValue::Ns(values) => {
+            bytes.push(TAG_NS);
+            write_len(bytes, values.len());
+            for v in values {
+                write_bytes(bytes, v.as_bytes());
+            }
+        }
+        AttributeValue::Bs(values) => {
+            bytes.push(TAG_BS);
+            write_len(bytes, values.len());
+            for v in values {
+                write_bytes(bytes, v.as_ref());
+            }
+        }
+        AttributeValue::L(values) => {
+            if depth > MAX_DEPTH {
+                return Err(CursorError::TooDeep);
+            }
+            bytes.push(TAG_L);
+            write_len(bytes, values.len());
+            for v in values {
+                write_value(bytes, v, depth + 1)?;
+            }
+        }
+        AttributeValue::M(map) => {
+            bytes.push(TAG_M);
+            write_map(bytes, map, depth)?;
+        }
+        _ => return Err(CursorError::UnsupportedValue(value.clone())),
+    }
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn read_u8(&mut self) -> Result<u8, CursorError> {
+        let (first, rest) = self.bytes.split_first().ok_or(CursorError::Malformed)?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    fn read_len(&mut self) -> Result<usize, CursorError> {
+        let mut len: usize = 0;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.read_u8()?;
+            len |= usize::from(byte & 0x7f)
+                .checked_shl(shift)
+                .ok_or(CursorError::Malformed)?;
+            if byte & 0x80 == 0 {
+                return Ok(len);
+            }
+        }
+        Err(CursorError::Malformed)
+    }
+
+    // A count of entries can't be larger than the remaining bytes, since every entry takes
+    // at least one byte. Checking it prevents huge allocations from a crafted cursor.
+    fn read_count(&mut self) -> Result<usize, CursorError> {
+        let count = self.read_len()?;
+        if count > self.bytes.len() {
+            return Err(CursorError::Malformed);
+        }
+        Ok(count)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], CursorError> {
+        let len = self.read_len()?;
+        if len > self.bytes.len() {
+            return Err(CursorError::Malformed);
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    fn read_string(&mut self) -> Result<String, CursorError> {
+        let bytes = self.read_bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| CursorError::Malformed)
+    }
+
+    fn read_bool(&mut self) -> Result<bool, CursorError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CursorError::Malformed),
+        }
+    }
+
+    fn read_map(&mut self, depth: usize) -> Result<HashMap<String, AttributeValue>, CursorError> {
+        if depth > MAX_DEPTH {
+            return Err(CursorError::TooDeep);
+        }
+
+        let count = self.read_count()?;
+        let mut map = HashMap::with_capacity(count);
+        for _ in 0..count {
+            let name = self.read_string()?;
+            let value = self.read_value(depth + 1)?;
+            map.insert(name, value);
+        }
+        Ok(map)
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<AttributeValue, CursorError> {
+        let value = match self.read_u8()? {
+            TAG_S => AttributeValue::S(self.read_string()?),
+            TAG_N => AttributeValue::N(self.read_string()?),
+            TAG_B => AttributeValue::B(Blob::new(self.read_bytes()?)),
+            TAG_BOOL => AttributeValue::Bool(self.read_bool()?),
+            TAG_NULL => AttributeValue::Null(self.read_bool()?),
+            TAG_SS => AttributeValue::Ss(self.read_list(Self::read_string)?),
+            TAG_NS => AttributeValue::Ns(self.read_list(Self::read_string)?),
+            TAG_BS => AttributeValue::Bs(self.read_list(|r| r.read_bytes().map(Blob::new))?),
+            TAG_L => {
+                if depth > MAX_DEPTH {
+                    return Err(CursorError::TooDeep);
+                }
+                AttributeValue::L(self.read_list(|r| r.read_value(depth + 1))?)
+            }
+            TAG_M => AttributeValue::M(self.read_map(depth)?),
+            _ => return Err(CursorError::Malformed),
+        };
+        Ok(value)
+    }
+
+    fn read_list<T, F>(&mut self, mut read: F) -> Result<Vec<T>, CursorError>
+    where
+        F: FnMut(&mut Self) -> Result<T, CursorError>,
+    {
+        let count = self.read_count()?;
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            values.push(read(self)?);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_key() -> HashMap<String, AttributeValue> {
+        [
+            ("PK".to_string(), AttributeValue::S("7cf27a02".into())),
+            ("SK".to_string(), AttributeValue::S("VideoStats".into())),
+        ]
+        .into()
+    }
+
+    fn all_variants() -> HashMap<String, AttributeValue> {
+        let nested: HashMap<String, AttributeValue> = [
+            ("n".to_string(), AttributeValue::N("-1.5e3".into())),
+            ("null".to_string(), AttributeValue::Null(true)),
+        ]
+        .into();
+
+        [
+            ("s".to_string(), AttributeValue::S("こんにちは".into())),
+            (
+                "n".to_string(),
+                AttributeValue::N("12345678901234567890".into()),
+            ),
+            (
+                "b".to_string(),
+                AttributeValue::B(Blob::new(vec![0, 1, 255])),
+            ),
+            ("bool".to_string(), AttributeValue::Bool(true)),
+            ("null".to_string(), AttributeValue::Null(true)),
+            (
+                "ss".to_string(),
+                AttributeValue::Ss(vec!["a".into(), "b".into()]),
+            ),
+            (
+                "ns".to_string(),
+                AttributeValue::Ns(vec!["1".into(), "2.5".into()]),
+            ),
+            (
+                "bs".to_string(),
+                AttributeValue::Bs(vec![Blob::new(vec![1]), Blob::new(vec![])]),
+            ),
+            (
+                "l".to_string(),
+                AttributeValue::L(vec![
+                    AttributeValue::S("x".into()),
+                    AttributeValue::Bool(false),
+                    AttributeValue::M(nested.clone()),
+                ]),
+            ),
+            ("m".to_string(), AttributeValue::M(nested)),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn all_variants_round_trip() {
+        let key = all_variants();
+        let cursor = encode(&key).unwrap();
+        assert_eq!(decode(&cursor).unwrap(), key);
+    }
+
+    #[test]
+    fn cursor_is_url_safe() {
+        let cursor = encode(&all_variants()).unwrap();
+        assert!(
+            cursor
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+    }
+
+    #[test]
+    fn same_key_results_in_same_cursor() {
+        let a = encode(&all_variants()).unwrap();
+        let b = encode(&all_variants()).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn corrupted_cursor_is_rejected() {
+        let mut bytes = URL_SAFE_NO_PAD
+            .decode(encode(&table_key()).unwrap())
+            .unwrap();
+        let last = bytes.len() - CHECKSUM_LEN - 1;
+        bytes[last] ^= 0x01;
+        let cursor = URL_SAFE_NO_PAD.encode(bytes);
+
+        assert!(matches!(
+            decode(&cursor),
+            Err(CursorError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn invalid_base64_is_rejected() {
+        assert!(matches!(decode("not base64!"), Err(CursorError::Base64(_))));
+    }
+
+    #[test]
+    fn truncated_cursor_is_rejected() {
+        assert!(matches!(decode(""), Err(CursorError::Malformed)));
+        assert!(matches!(decode("AQA"), Err(CursorError::Malformed)));
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let cursor = URL_SAFE_NO_PAD.encode([9, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(
+            decode(&cursor),
+            Err(CursorError::UnsupportedVersion(9))
+        ));
+    }
+
+    #[test]
+    fn signed_cursor_round_trips() {
+        let codec = CursorCodec::new().with_secret("secret");
+        let cursor = codec.encode(&table_key()).unwrap();
+        assert_eq!(codec.decode(&cursor).unwrap(), table_key());
+    }
+
+    #[test]
+    fn signed_cursor_is_rejected_with_another_secret() {
+        let cursor = CursorCodec::new()
+            .with_secret("secret")
+            .encode(&table_key())
+            .unwrap();
+
+        let codec = CursorCodec::new().with_secret("another secret");
+        assert!(matches!(
+            codec.decode(&cursor),
+            Err(CursorError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn tampered_signed_cursor_is_rejected() {
+        let codec = CursorCodec::new().with_secret("secret");
+        let cursor = codec.encode(&table_key()).unwrap();
+
+        let mut key = table_key();
+        key.insert("PK".into(), AttributeValue::S("someone else".into()));
+        let mut forged = URL_SAFE_NO_PAD.decode(encode(&key).unwrap()).unwrap();
+        forged.truncate(forged.len() - CHECKSUM_LEN);
+        forged[1] |= FLAG_SIGNED;
+        let signature = URL_SAFE_NO_PAD.decode(&cursor).unwrap();
+        forged.extend_from_slice(&signature[signature.len() - SIGNATURE_LEN..]);
+        let forged = URL_SAFE_NO_PAD.encode(forged);
+
+        assert!(matches!(
+            codec.decode(&forged),
+            Err(CursorError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn unsigned_cursor_is_rejected_by_signing_codec() {
+        let cursor = encode(&table_key()).unwrap();
+        let codec = CursorCodec::new().with_secret("secret");
+        assert!(matches!(
+            codec.decode(&cursor),
+            Err(CursorError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn key_attributes_are_validated() {
+        let codec = CursorCodec::new()
+            .with_table_keys(KeySchema::new("PK").with_sort_key("SK"))
+            .with_index_keys(KeySchema::new("GSI1PK"));
+
+        let mut key = table_key();
+        assert!(matches!(
+            codec.encode(&key),
+            Err(CursorError::MissingAttribute(name)) if name == "GSI1PK"
+        ));
+
+        key.insert("GSI1PK".into(), AttributeValue::S("author#1".into()));
+        let cursor = codec.encode(&key).unwrap();
+        assert_eq!(codec.decode(&cursor).unwrap(), key);
+
+        key.insert("Author".into(), AttributeValue::S("VideoGal12".into()));
+        assert!(matches!(
+            codec.encode(&key),
+            Err(CursorError::UnexpectedAttribute(name)) if name == "Author"
+        ));
+
+        let cursor = encode(&key).unwrap();
+        assert!(matches!(
+            codec.decode(&cursor),
+            Err(CursorError::UnexpectedAttribute(name)) if name == "Author"
+        ));
+    }
+
+    fn nested_key(depth: usize) -> HashMap<String, AttributeValue> {
+        let mut value = AttributeValue::Null(true);
+        for i in 0..depth {
+            value = if i % 2 == 0 {
+                AttributeValue::L(vec![value])
+            } else {
+                AttributeValue::M([("m".to_string(), value)].into())
+            };
+        }
+        [("deep".to_string(), value)].into()
+    }
+
+    #[test]
+    fn nested_key_round_trips_up_to_max_depth() {
+        let key = nested_key(MAX_DEPTH);
+        let cursor = encode(&key).unwrap();
+        assert_eq!(decode(&cursor).unwrap(), key);
+
+        assert!(matches!(
+            encode(&nested_key(MAX_DEPTH + 1)),
+            Err(CursorError::TooDeep)
+        ));
+    }
+
+    #[test]
+    fn deeply_nested_cursor_is_rejected() {
+        // A cursor made by hand, since `encode` refuses to make it.
+        let mut bytes = vec![VERSION, 0, 1];
+        write_bytes(&mut bytes, b"deep");
+        for _ in 0..=MAX_DEPTH {
+            bytes.extend([TAG_L, 1]);
+        }
+        bytes.extend([TAG_NULL, 1]);
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+        let cursor = URL_SAFE_NO_PAD.encode(bytes);
+
+        assert!(matches!(decode(&cursor), Err(CursorError::TooDeep)));
+    }
+}
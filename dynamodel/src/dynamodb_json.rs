@@ -0,0 +1,467 @@
+//! DynamoDB JSON, the typed JSON form of items.
+//!
+//! The AWS CLI, the console and S3 exports show items with the type of every attribute, like
+//! `{"name": {"S": "Kanji"}, "age": {"N": "23"}}`. This module converts
+//! `HashMap<String, AttributeValue>` and objects with the [`Dynamodel`](crate::Dynamodel) macro
+//! into that form and back.
+//!
+//! ```rust
+//! use dynamodel::{Dynamodel, dynamodb_json};
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct Person {
+//!     name: String,
+//!     age: u8,
+//! }
+//!
+//! let json = r#"{"name": {"S": "Kanji"}, "age": {"N": "23"}}"#;
+//!
+//! let person: Person = dynamodb_json::from_str(json).unwrap();
+//! assert_eq!(person, Person { name: "Kanji".into(), age: 23 });
+//!
+//! let json = dynamodb_json::to_string(person).unwrap();
+//! assert!(json.contains(r#""age":{"N":"23"}"#));
+//! ```
+//!
+//! Binary values are base64 strings, sets are arrays and `NULL` is `{"NULL": true}`.
+//! [`Reader`] reads newline-delimited files, like fixtures or the data files of S3 exports,
+//! one item at a time.
+
+use super::ConvertError;
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use thiserror::Error;
+
+/// An error occurs when reading or writing DynamoDB JSON.
+#[derive(Debug, Error)]
+pub enum DynamodbJsonError {
+    /// The input is not valid JSON.
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The input is JSON, but not DynamoDB JSON. `path` is the attribute, like `tags[2].name`.
+    #[error("invalid DynamoDB JSON at `{path}`: {reason}")]
+    Malformed { path: String, reason: String },
+
+    /// The item holds an `AttributeValue` variant this module doesn't know.
+    #[error("cannot write `{0:?}` as DynamoDB JSON")]
+    UnsupportedValue(AttributeValue),
+
+    /// The item can't be converted into the object.
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+
+    /// Reading or writing a file failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// An error on a line of a newline-delimited file.
+    #[error("line {line}: {source}")]
+    Line {
+        line: usize,
+        #[source]
+        source: Box<DynamodbJsonError>,
+    },
+}
+
+impl From<Infallible> for DynamodbJsonError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+fn malformed(path: &str, reason: impl Into<String>) -> DynamodbJsonError {
+    DynamodbJsonError::Malformed {
+        path: path.into(),
+        reason: reason.into(),
+    }
+}
+
+/// Converts an item into a DynamoDB JSON object.
+pub fn to_value(item: &HashMap<String, AttributeValue>) -> Result<Value, DynamodbJsonError> {
+    item.iter()
+        .map(|(name, value)| Ok((name.clone(), attribute_to_value(value)?)))
+        .collect::<Result<Map<_, _>, _>>()
+        .map(Value::Object)
+}
+
+/// Converts a DynamoDB JSON object into an item.
+pub fn from_value(value: Value) -> Result<HashMap<String, AttributeValue>, DynamodbJsonError> {
+    match value {
+        Value::Object(map) => map_from_value(map, ""),
+        _ => Err(malformed("", "expected an object of attributes")),
+    }
+}
+
+/// Converts an item or an object with the [`Dynamodel`](crate::Dynamodel) macro into a
+/// DynamoDB JSON string.
+pub fn to_string<T>(value: T) -> Result<String, DynamodbJsonError>
+where
+    T: Into<HashMap<String, AttributeValue>>,
+{
+    to_value(&value.into()).map(|v| v.to_string())
+}
+
+/// Parses a DynamoDB JSON string into an item or an object with the
+/// [`Dynamodel`](crate::Dynamodel) macro.
+pub fn from_str<T>(s: &str) -> Result<T, DynamodbJsonError>
+where
+    T: TryFrom<HashMap<String, AttributeValue>>,
+    DynamodbJsonError: From<T::Error>,
+{
+    let item = from_value(serde_json::from_str(s)?)?;
+    Ok(T::try_from(item)?)
+}
+
+/// Writes an item or an object as a line of a newline-delimited file.
+pub fn to_writer<W, T>(mut writer: W, value: T) -> Result<(), DynamodbJsonError>
+where
+    W: Write,
+    T: Into<HashMap<String, AttributeValue>>,
+{
+    serde_json::to_writer(&mut writer, &to_value(&value.into())?)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads items or objects from a newline-delimited DynamoDB JSON file. Blank lines are skipped.
+///
+/// ```rust
+/// use dynamodel::dynamodb_json::Reader;
+/// # use aws_sdk_dynamodb::types::AttributeValue;
+/// # use std::collections::HashMap;
+///
+/// let file = concat!(
+///     r#"{"Item": {"id": {"S": "a"}}}"#, "\n",
+///     r#"{"Item": {"id": {"S": "b"}}}"#, "\n",
+/// );
+///
+/// let items = Reader::export(file.as_bytes())
+///     .collect::<Result<Vec<HashMap<String, AttributeValue>>, _>>()
+///     .unwrap();
+/// assert_eq!(items[1]["id"], AttributeValue::S("b".into()));
+/// ```
+pub struct Reader<R, T = HashMap<String, AttributeValue>> {
+    lines: std::io::Lines<R>,
+    line: usize,
+    export: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T> Reader<R, T> {
+    /// Reads a file with an item on each line.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            export: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Reads a data file of an S3 export, where each line is an item under the `Item` key.
+    pub fn export(reader: R) -> Self {
+        Self {
+            export: true,
+            ..Self::new(reader)
+        }
+    }
+
+    fn read(&self, line: &str) -> Result<T, DynamodbJsonError>
+    where
+        T: TryFrom<HashMap<String, AttributeValue>>,
+        DynamodbJsonError: From<T::Error>,
+    {
+        let mut value: Value = serde_json::from_str(line)?;
+        if self.export {
+            value = match value.get_mut("Item") {
+                Some(item) => item.take(),
+                None => return Err(malformed("", "expected an `Item` key of an S3 export")),
+            };
+        }
+        Ok(T::try_from(from_value(value)?)?)
+    }
+}
+
+impl<R, T> Iterator for Reader<R, T>
+where
+    R: BufRead,
+    T: TryFrom<HashMap<String, AttributeValue>>,
+    DynamodbJsonError: From<T::Error>,
+{
+    type Item = Result<T, DynamodbJsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line += 1;
+            let result = match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => self.read(&line),
+                Err(err) => Err(err.into()),
+            };
+            return Some(result.map_err(|source| DynamodbJsonError::Line {
+                line: self.line,
+                source: Box::new(source),
+            }));
+        }
+    }
+}
+
+pub(crate) fn attribute_to_value(value: &AttributeValue) -> Result<Value, DynamodbJsonError> {
+    let (key, value) = match value {
+        AttributeValue::S(v) => ("S", Value::String(v.clone())),
+        AttributeValue::N(v) => ("N", Value::String(v.clone())),
+        AttributeValue::B(v) => ("B", Value::String(STANDARD.encode(v.as_ref()))),
+        AttributeValue::Bool(v) => ("BOOL", Value::Bool(*v)),
+        AttributeValue::Null(v) => ("NULL", Value::Bool(*v)),
+        AttributeValue::Ss(v) => ("SS", v.iter().cloned().map(Value::String).collect()),
+        AttributeValue::Ns(v) => ("NS", v.iter().cloned().map(Value::String).collect()),
+        AttributeValue::Bs(v) => (
+            "BS",
+            v.iter()
+                .map(|b| Value::String(STANDARD.encode(b.as_ref())))
+                .collect(),
+        ),
+        AttributeValue::L(v) => (
+            "L",
+            v.iter().map(attribute_to_value).collect::<Result<_, _>>()?,
+        ),
+        AttributeValue::M(v) => ("M", to_value(v)?),
+        _ => return Err(DynamodbJsonError::UnsupportedValue(value.clone())),
+    };
+    Ok(Value::Object(Map::from_iter([(key.to_string(), value)])))
+}
+
+fn map_from_value(
+    map: Map<String, Value>,
+    path: &str,
+) -> Result<HashMap<String, AttributeValue>, DynamodbJsonError> {
+    let mut item = HashMap::with_capacity(map.len());
+    for (name, value) in map {
+        let path = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{path}.{name}")
+        };
+        item.insert(name, attribute_from_value(value, &path)?);
+    }
+    Ok(item)
+}
+
+pub(crate) fn attribute_from_value(
+    value: Value,
+    path: &str,
+) -> Result<AttributeValue, DynamodbJsonError> {
+    let (key, value) = match value {
+        Value::Object(map) if map.len() == 1 => map.into_iter().next().expect("one entry"),
+        _ => {
+            return Err(malformed(
+                path,
+                "expected an object with a single type key, like `{\"S\": ...}`",
+            ));
+        }
+    };
+
+    match (key.as_str(), value) {
+        ("S", Value::String(v)) => Ok(AttributeValue::S(v)),
+        ("N", Value::String(v)) => Ok(AttributeValue::N(v)),
+        ("B", Value::String(v)) => decode(&v, path).map(AttributeValue::B),
+        ("BOOL", Value::Bool(v)) => Ok(AttributeValue::Bool(v)),
+        ("NULL", Value::Bool(v)) => Ok(AttributeValue::Null(v)),
+        ("SS", Value::Array(v)) => strings(v, path).map(AttributeValue::Ss),
+        ("NS", Value::Array(v)) => strings(v, path).map(AttributeValue::Ns),
+        ("BS", Value::Array(v)) => strings(v, path)?
+            .iter()
+            .map(|s| decode(s, path))
+            .collect::<Result<_, _>>()
+            .map(AttributeValue::Bs),
+        ("L", Value::Array(v)) => v
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| attribute_from_value(value, &format!("{path}[{i}]")))
+            .collect::<Result<_, _>>()
+            .map(AttributeValue::L),
+        ("M", Value::Object(v)) => map_from_value(v, path).map(AttributeValue::M),
+        (key @ ("S" | "N" | "B"), _) => Err(malformed(path, format!("`{key}` must be a string"))),
+        (key @ ("BOOL" | "NULL"), _) => Err(malformed(path, format!("`{key}` must be a boolean"))),
+        (key @ ("SS" | "NS" | "BS" | "L"), _) => {
+            Err(malformed(path, format!("`{key}` must be an array")))
+        }
+        ("M", _) => Err(malformed(path, "`M` must be an object")),
+        (key, _) => Err(malformed(path, format!("unknown type `{key}`"))),
+    }
+}
+
+fn strings(values: Vec<Value>, path: &str) -> Result<Vec<String>, DynamodbJsonError> {
+    values
+        .into_iter()
+        .map(|v| match v {
+            Value::String(s) => Ok(s),
+            _ => Err(malformed(path, "set members must be strings")),
+        })
+        .collect()
+}
+
+fn decode(value: &str, path: &str) -> Result<Blob, DynamodbJsonError> {
+    STANDARD
+        .decode(value)
+        .map(Blob::new)
+        .map_err(|e| malformed(path, format!("invalid base64: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item() -> HashMap<String, AttributeValue> {
+        [
+            ("s".to_string(), AttributeValue::S("text".into())),
+            ("n".to_string(), AttributeValue::N("-1.50".into())),
+            (
+                "b".to_string(),
+                AttributeValue::B(Blob::new(b"bytes".to_vec())),
+            ),
+            ("bool".to_string(), AttributeValue::Bool(false)),
+            ("null".to_string(), AttributeValue::Null(true)),
+            (
+                "ss".to_string(),
+                AttributeValue::Ss(vec!["a".into(), "b".into()]),
+            ),
+            (
+                "ns".to_string(),
+                AttributeValue::Ns(vec!["1".into(), "2".into()]),
+            ),
+            (
+                "bs".to_string(),
+                AttributeValue::Bs(vec![Blob::new(vec![0]), Blob::new(vec![255])]),
+            ),
+            (
+                "l".to_string(),
+                AttributeValue::L(vec![
+                    AttributeValue::S("x".into()),
+                    AttributeValue::M([("k".to_string(), AttributeValue::N("1".into()))].into()),
+                ]),
+            ),
+        ]
+        .into()
+    }
+
+    fn json() -> Value {
+        json!({
+            "s": { "S": "text" },
+            "n": { "N": "-1.50" },
+            "b": { "B": "Ynl0ZXM=" },
+            "bool": { "BOOL": false },
+            "null": { "NULL": true },
+            "ss": { "SS": ["a", "b"] },
+            "ns": { "NS": ["1", "2"] },
+            "bs": { "BS": ["AA==", "/w=="] },
+            "l": { "L": [{ "S": "x" }, { "M": { "k": { "N": "1" } } }] },
+        })
+    }
+
+    #[test]
+    fn all_variants_are_converted() {
+        assert_eq!(to_value(&item()).unwrap(), json());
+        assert_eq!(from_value(json()).unwrap(), item());
+    }
+
+    #[test]
+    fn strings_are_round_tripped() {
+        let s = to_string(item()).unwrap();
+        let converted: HashMap<String, AttributeValue> = from_str(&s).unwrap();
+        assert_eq!(converted, item());
+    }
+
+    #[test]
+    fn malformed_input_is_an_error_with_the_path() {
+        let cases = [
+            (json!([]), "", "expected an object of attributes"),
+            (
+                json!({ "a": "text" }),
+                "a",
+                "expected an object with a single type key",
+            ),
+            (
+                json!({ "a": { "S": "x", "N": "1" } }),
+                "a",
+                "expected an object",
+            ),
+            (json!({ "a": { "N": 1 } }), "a", "`N` must be a string"),
+            (json!({ "a": { "X": 1 } }), "a", "unknown type `X`"),
+            (json!({ "a": { "B": "!!" } }), "a", "invalid base64"),
+            (
+                json!({ "a": { "SS": [1] } }),
+                "a",
+                "set members must be strings",
+            ),
+            (
+                json!({ "a": { "M": { "b": { "L": [{ "S": "x" }, { "BOOL": "yes" }] } } } }),
+                "a.b[1]",
+                "`BOOL` must be a boolean",
+            ),
+        ];
+
+        for (value, expected_path, expected_reason) in cases {
+            match from_value(value) {
+                Err(DynamodbJsonError::Malformed { path, reason }) => {
+                    assert_eq!(path, expected_path);
+                    assert!(reason.starts_with(expected_reason), "{reason}");
+                }
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(matches!(
+            from_str::<HashMap<String, AttributeValue>>("{"),
+            Err(DynamodbJsonError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn newline_delimited_files_are_written_and_read() {
+        let mut file = vec![];
+        to_writer(&mut file, item()).unwrap();
+        file.extend_from_slice(b"\n");
+        to_writer(&mut file, HashMap::new()).unwrap();
+
+        let items: Vec<HashMap<String, AttributeValue>> = Reader::new(file.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, [item(), HashMap::new()]);
+    }
+
+    #[test]
+    fn reader_errors_have_the_line_number() {
+        let file = "{\"a\": {\"S\": \"x\"}}\n\n{\"a\": {\"S\": 1}}\n";
+        let mut reader: Reader<_> = Reader::new(file.as_bytes());
+
+        assert!(reader.next().unwrap().is_ok());
+        match reader.next().unwrap() {
+            Err(DynamodbJsonError::Line { line, source }) => {
+                assert_eq!(line, 3);
+                assert!(matches!(*source, DynamodbJsonError::Malformed { .. }));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn export_lines_need_the_item_key() {
+        let mut reader: Reader<_> = Reader::export("{\"a\": {\"S\": \"x\"}}".as_bytes());
+        assert!(matches!(
+            reader.next().unwrap(),
+            Err(DynamodbJsonError::Line { line: 1, .. })
+        ));
+    }
+}
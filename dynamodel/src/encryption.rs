@@ -0,0 +1,579 @@
+//! Client-side encryption and signing of attributes.
+//!
+//! Fields with the `encrypt` attribute of the [`Dynamodel`](crate::Dynamodel) macro are
+//! converted into an `AttributeValue` as usual, and then encrypted into `AttributeValue::B` with
+//! AES-256-GCM before they reach DynamoDB. The keys come from a [`KeyProvider`], which the
+//! `key_provider` container attribute names as a function returning one.
+//!
+//! With the `sign` container attribute, the item also gets [`SIGNATURE_ATTRIBUTE`], an HMAC-SHA256
+//! over the plaintext key attributes of a [`KeySchema`] and the encrypted attributes, so an
+//! encrypted value moved into another item, or removed from it, is detected on read.
+//!
+//! ```rust
+//! use dynamodel::{Dynamodel, KeySchema, encryption::{DataKey, StaticKeyProvider}};
+//! use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! const TABLE: KeySchema = KeySchema::new("id");
+//!
+//! fn keys() -> StaticKeyProvider {
+//!     StaticKeyProvider::new(DataKey::new("2024-01", [7; 32]))
+//! }
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! #[dynamodel(key_provider = "keys", sign = "TABLE")]
+//! struct Customer {
+//!     id: String,
+//!     #[dynamodel(encrypt)]
+//!     email: String,
+//! }
+//!
+//! let customer = Customer { id: "c1".into(), email: "kanji@example.com".into() };
+//!
+//! let item = customer.try_into_item().unwrap();
+//! assert!(matches!(item["email"], AttributeValue::B(_)));
+//!
+//! let customer = Customer::try_from(item).unwrap();
+//! assert_eq!(customer.email, "kanji@example.com");
+//! ```
+//!
+//! Conversions of objects with encrypted fields or a signature can fail, so they have
+//! `try_into_item` instead of `Into<HashMap<String, AttributeValue>>`, and can't have the `by_ref`
+//! attribute. A failure to encrypt is [`ConvertError::Encryption`], and a failure to decrypt or
+//! verify is [`ConvertError::Decryption`], both with the name of the attribute.
+//!
+//! Each value is encrypted with a key derived from the current [`DataKey`], and its id is stored
+//! in the header of the value, so values encrypted with older keys stay readable as long as the
+//! provider still has them. The name of the attribute is authenticated with the value, so an
+//! encrypted value can't be moved into another attribute either. Values without the header are
+//! errors, not plaintext.
+
+use super::payload::PayloadError;
+use super::{ConvertError, ItemMap, KeySchema, payload};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::string::FromUtf8Error;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The attribute of the item signature.
+pub const SIGNATURE_ATTRIBUTE: &str = "dynamodel_signature";
+
+const CIPHERTEXT_MAGIC: &[u8] = b"DE\x01";
+const SIGNATURE_MAGIC: &[u8] = b"DS\x01";
+const NONCE_LEN: usize = 12;
+const MAC_LEN: usize = 32;
+
+const ENCRYPTION_LABEL: &[u8] = b"dynamodel encryption";
+const SIGNATURE_LABEL: &[u8] = b"dynamodel signature";
+
+/// An error occurs when encrypting, decrypting, signing or verifying attributes.
+#[derive(Debug, Error)]
+pub enum EncryptionError {
+    /// The provider doesn't have the key of the id.
+    #[error("key `{0}` is not found")]
+    KeyNotFound(String),
+
+    /// Any other errors of a [`KeyProvider`], like a failed request to a key management service.
+    #[error(transparent)]
+    Provider(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The value doesn't have the header of this module.
+    #[error("the value is not encrypted")]
+    NotEncrypted,
+
+    /// The value has the header of this module, but it is truncated or unknown.
+    #[error("the header of the value is invalid")]
+    InvalidHeader,
+
+    /// The value can't be decrypted with the key, because the key is wrong or the value or its
+    /// attribute name was modified.
+    #[error("the value can't be decrypted with key `{0}`")]
+    Aead(String),
+
+    /// The item has no signature, or the signature doesn't match the item.
+    #[error("the signature is missing or doesn't match the item")]
+    SignatureMismatch,
+
+    /// The attribute can't be signed, because it is a missing key attribute or not `S`, `N` or `B`.
+    #[error("`{0}` attribute is missing or not `S`, `N` or `B`, so it can't be signed")]
+    UnsignableAttribute(String),
+
+    /// The decrypted string is not UTF-8.
+    #[error(transparent)]
+    Utf8(#[from] FromUtf8Error),
+
+    /// The value can't be written as DynamoDB JSON, or the decrypted DynamoDB JSON is invalid.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+}
+
+/// A 256-bit key and its id.
+///
+/// The keys to encrypt and sign are derived from it with HMAC-SHA256, so the same key can do
+/// both.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DataKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl DataKey {
+    /// Creates a key with the id stored in the header of the values it encrypts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id is longer than 255 bytes.
+    pub fn new(id: impl Into<String>, key: [u8; 32]) -> Self {
+        let id = id.into();
+        assert!(
+            id.len() <= u8::MAX as usize,
+            "key ids are at most 255 bytes"
+        );
+        Self { id, key }
+    }
+
+    /// The id of the key.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn derive(&self, label: &[u8]) -> [u8; 32] {
+        let mut mac = mac(&self.key);
+        mac.update(label);
+        mac.finalize().into_bytes().into()
+    }
+}
+
+impl fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataKey")
+            .field("id", &self.id)
+            .field("key", &"..")
+            .finish()
+    }
+}
+
+/// Provides the keys to encrypt, decrypt, sign and verify attributes.
+///
+/// Implement this for a key management service, usually caching its data keys, since every
+/// conversion asks for a key.
+pub trait KeyProvider {
+    /// The key to encrypt and sign new values.
+    fn current_key(&self) -> Result<DataKey, EncryptionError>;
+
+    /// The key of the id in the header of a value, to decrypt and verify it.
+    fn key(&self, id: &str) -> Result<DataKey, EncryptionError>;
+}
+
+impl<T: KeyProvider + ?Sized> KeyProvider for &T {
+    fn current_key(&self) -> Result<DataKey, EncryptionError> {
+        (**self).current_key()
+    }
+
+    fn key(&self, id: &str) -> Result<DataKey, EncryptionError> {
+        (**self).key(id)
+    }
+}
+
+impl<T: KeyProvider + ?Sized> KeyProvider for Arc<T> {
+    fn current_key(&self) -> Result<DataKey, EncryptionError> {
+        (**self).current_key()
+    }
+
+    fn key(&self, id: &str) -> Result<DataKey, EncryptionError> {
+        (**self).key(id)
+    }
+}
+
+/// A [`KeyProvider`] with fixed keys, for tests and local development.
+#[derive(Debug, Clone)]
+pub struct StaticKeyProvider {
+    keys: Vec<DataKey>,
+}
+
+impl StaticKeyProvider {
+    /// Creates a provider that encrypts with the key.
+    pub fn new(key: DataKey) -> Self {
+        Self { keys: vec![key] }
+    }
+
+    /// Adds an older key, which only decrypts and verifies values.
+    pub fn with_key(mut self, key: DataKey) -> Self {
+        self.keys.push(key);
+        self
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn current_key(&self) -> Result<DataKey, EncryptionError> {
+        Ok(self.keys[0].clone())
+    }
+
+    fn key(&self, id: &str) -> Result<DataKey, EncryptionError> {
+        self.keys
+            .iter()
+            .find(|key| key.id == id)
+            .cloned()
+            .ok_or_else(|| EncryptionError::KeyNotFound(id.into()))
+    }
+}
+
+/// Encrypts the value of the attribute into `AttributeValue::B` with the current key.
+///
+/// The error is [`ConvertError::Encryption`] with the name of the attribute.
+pub fn encrypt<P: KeyProvider + ?Sized>(
+    keys: &P,
+    name: &str,
+    value: AttributeValue,
+) -> Result<AttributeValue, ConvertError> {
+    encrypt_value(keys, name, value).map_err(|e| ConvertError::Encryption(name.into(), Box::new(e)))
+}
+
+/// Decrypts the value of the attribute.
+///
+/// The error is [`ConvertError::Decryption`] with the name of the attribute.
+pub fn decrypt<P: KeyProvider + ?Sized>(
+    keys: &P,
+    name: &str,
+    value: AttributeValue,
+) -> Result<AttributeValue, ConvertError> {
+    decrypt_value(keys, name, &value)
+        .map_err(|e| ConvertError::Decryption(name.into(), Box::new(e)))
+}
+
+/// Whether the value is encrypted by this module.
+pub fn is_encrypted(value: &AttributeValue) -> bool {
+    matches!(value, AttributeValue::B(v) if v.as_ref().starts_with(CIPHERTEXT_MAGIC))
+}
+
+/// Adds [`SIGNATURE_ATTRIBUTE`] to the item, signing the key attributes and the encrypted ones.
+///
+/// The error is [`ConvertError::Encryption`] with the name of the signature attribute.
+pub fn sign<P: KeyProvider + ?Sized, M: ItemMap>(
+    keys: &P,
+    schema: &KeySchema,
+    item: &mut M,
+    encrypted: &[&str],
+) -> Result<(), ConvertError> {
+    let signature = signature(keys, schema, item, encrypted)
+        .map_err(|e| ConvertError::Encryption(SIGNATURE_ATTRIBUTE.into(), Box::new(e)))?;
+    item.insert(SIGNATURE_ATTRIBUTE.into(), AttributeValue::B(signature));
+    Ok(())
+}
+
+/// Verifies [`SIGNATURE_ATTRIBUTE`] of the item.
+///
+/// The error is [`ConvertError::Decryption`] with the name of the signature attribute.
+pub fn verify<P: KeyProvider + ?Sized, M: ItemMap>(
+    keys: &P,
+    schema: &KeySchema,
+    item: &M,
+    encrypted: &[&str],
+) -> Result<(), ConvertError> {
+    verify_signature(keys, schema, item, encrypted)
+        .map_err(|e| ConvertError::Decryption(SIGNATURE_ATTRIBUTE.into(), Box::new(e)))
+}
+
+fn encrypt_value<P: KeyProvider + ?Sized>(
+    keys: &P,
+    name: &str,
+    value: AttributeValue,
+) -> Result<AttributeValue, EncryptionError> {
+    let key = keys.current_key()?;
+    let (kind, plaintext) = payload::to_bytes(value)?;
+
+    let mut bytes = header(CIPHERTEXT_MAGIC, &key);
+    bytes.push(kind);
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(&key)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &aad(name, &bytes),
+            },
+        )
+        .map_err(|_| EncryptionError::Aead(key.id.clone()))?;
+
+    bytes.extend_from_slice(&nonce);
+    bytes.extend(ciphertext);
+    Ok(AttributeValue::B(Blob::new(bytes)))
+}
+
+fn decrypt_value<P: KeyProvider + ?Sized>(
+    keys: &P,
+    name: &str,
+    value: &AttributeValue,
+) -> Result<AttributeValue, EncryptionError> {
+    let AttributeValue::B(bytes) = value else {
+        return Err(EncryptionError::NotEncrypted);
+    };
+    let bytes = bytes.as_ref();
+
+    let (id, rest) = parse_header(CIPHERTEXT_MAGIC, bytes)?;
+    let (&kind, rest) = rest.split_first().ok_or(EncryptionError::InvalidHeader)?;
+    if rest.len() < NONCE_LEN {
+        return Err(EncryptionError::InvalidHeader);
+    }
+    let header = &bytes[..bytes.len() - rest.len()];
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let key = keys.key(id)?;
+    let plaintext = cipher(&key)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &aad(name, header),
+            },
+        )
+        .map_err(|_| EncryptionError::Aead(key.id.clone()))?;
+
+    payload::from_bytes::<EncryptionError>(kind, plaintext)?.ok_or(EncryptionError::InvalidHeader)
+}
+
+fn signature<P: KeyProvider + ?Sized, M: ItemMap>(
+    keys: &P,
+    schema: &KeySchema,
+    item: &M,
+    encrypted: &[&str],
+) -> Result<Blob, EncryptionError> {
+    let key = keys.current_key()?;
+    let mac = signature_mac(&key, schema, item, encrypted)?;
+
+    let mut bytes = header(SIGNATURE_MAGIC, &key);
+    bytes.extend(mac.finalize().into_bytes());
+    Ok(Blob::new(bytes))
+}
+
+fn verify_signature<P: KeyProvider + ?Sized, M: ItemMap>(
+    keys: &P,
+    schema: &KeySchema,
+    item: &M,
+    encrypted: &[&str],
+) -> Result<(), EncryptionError> {
+    let Some(AttributeValue::B(bytes)) = item.get(SIGNATURE_ATTRIBUTE) else {
+        return Err(EncryptionError::SignatureMismatch);
+    };
+
+    let (id, signature) = parse_header(SIGNATURE_MAGIC, bytes.as_ref())
+        .map_err(|_| EncryptionError::SignatureMismatch)?;
+    if signature.len() != MAC_LEN {
+        return Err(EncryptionError::SignatureMismatch);
+    }
+
+    signature_mac(&keys.key(id)?, schema, item, encrypted)?
+        .verify_slice(signature)
+        .map_err(|_| EncryptionError::SignatureMismatch)
+}
+
+// The MAC over the key attributes, which must be set, and the encrypted attributes sorted by name,
+// which may be missing. Each attribute is its name, the type and the bytes of its value.
+fn signature_mac<M: ItemMap>(
+    key: &DataKey,
+    schema: &KeySchema,
+    item: &M,
+    encrypted: &[&str],
+) -> Result<Hmac<Sha256>, EncryptionError> {
+    let mut mac = mac(&key.derive(SIGNATURE_LABEL));
+
+    let mut encrypted = encrypted.to_vec();
+    encrypted.sort_unstable();
+
+    let keys = schema.attribute_names().map(|name| (name, true));
+    for (name, required) in keys.chain(encrypted.into_iter().map(|name| (name, false))) {
+        let (kind, bytes) = match item.get(name) {
+            Some(AttributeValue::S(v)) => (b'S', v.as_bytes()),
+            Some(AttributeValue::N(v)) => (b'N', v.as_bytes()),
+            Some(AttributeValue::B(v)) => (b'B', v.as_ref()),
+            None if !required => (b'-', &[][..]),
+            _ => return Err(EncryptionError::UnsignableAttribute(name.into())),
+        };
+
+        mac.update(&(name.len() as u64).to_be_bytes());
+        mac.update(name.as_bytes());
+        mac.update(&[kind]);
+        mac.update(&(bytes.len() as u64).to_be_bytes());
+        mac.update(bytes);
+    }
+
+    Ok(mac)
+}
+
+fn header(magic: &[u8], key: &DataKey) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(magic.len() + 1 + key.id.len());
+    bytes.extend_from_slice(magic);
+    bytes.push(key.id.len() as u8);
+    bytes.extend_from_slice(key.id.as_bytes());
+    bytes
+}
+
+// Returns the key id and the bytes after it.
+fn parse_header<'a>(magic: &[u8], bytes: &'a [u8]) -> Result<(&'a str, &'a [u8]), EncryptionError> {
+    let rest = bytes
+        .strip_prefix(magic)
+        .ok_or(EncryptionError::NotEncrypted)?;
+    let (&len, rest) = rest.split_first().ok_or(EncryptionError::InvalidHeader)?;
+    if rest.len() < len as usize {
+        return Err(EncryptionError::InvalidHeader);
+    }
+    let (id, rest) = rest.split_at(len as usize);
+    let id = std::str::from_utf8(id).map_err(|_| EncryptionError::InvalidHeader)?;
+    Ok((id, rest))
+}
+
+// The attribute name and the header are authenticated with the ciphertext.
+fn aad(name: &str, header: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(8 + name.len() + header.len());
+    aad.extend_from_slice(&(name.len() as u64).to_be_bytes());
+    aad.extend_from_slice(name.as_bytes());
+    aad.extend_from_slice(header);
+    aad
+}
+
+fn cipher(key: &DataKey) -> Aes256Gcm {
+    Aes256Gcm::new(&key.derive(ENCRYPTION_LABEL).into())
+}
+
+fn mac(key: &[u8]) -> Hmac<Sha256> {
+    <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const TABLE: KeySchema = KeySchema::new("PK").with_sort_key("SK");
+
+    fn keys() -> StaticKeyProvider {
+        StaticKeyProvider::new(DataKey::new("k2", [2; 32])).with_key(DataKey::new("k1", [1; 32]))
+    }
+
+    fn s(v: &str) -> AttributeValue {
+        AttributeValue::S(v.into())
+    }
+
+    fn item() -> HashMap<String, AttributeValue> {
+        let email = encrypt(&keys(), "email", s("kanji@example.com")).unwrap();
+        [
+            ("PK".to_string(), s("customer#1")),
+            ("SK".to_string(), AttributeValue::N("1".into())),
+            ("email".to_string(), email),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn values_are_encrypted() {
+        let values = [
+            s("kanji@example.com"),
+            AttributeValue::B(Blob::new(vec![0, 1, 2])),
+            AttributeValue::M([("a".to_string(), AttributeValue::Bool(true))].into()),
+        ];
+
+        for value in values {
+            let encrypted = encrypt(&keys(), "email", value.clone()).unwrap();
+            assert!(is_encrypted(&encrypted));
+
+            let bytes = encrypted.as_b().unwrap().as_ref();
+            assert_eq!(&bytes[..6], b"DE\x01\x02k2");
+
+            assert_eq!(decrypt(&keys(), "email", encrypted).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn values_are_encrypted_with_random_nonces() {
+        let a = encrypt(&keys(), "email", s("x")).unwrap();
+        let b = encrypt(&keys(), "email", s("x")).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn values_of_older_keys_are_decrypted() {
+        let old = StaticKeyProvider::new(DataKey::new("k1", [1; 32]));
+        let encrypted = encrypt(&old, "email", s("x")).unwrap();
+        assert_eq!(decrypt(&keys(), "email", encrypted).unwrap(), s("x"));
+    }
+
+    #[test]
+    fn decryption_errors_have_the_attribute_name() {
+        let encrypted = encrypt(&keys(), "email", s("x")).unwrap();
+
+        // Another attribute
+        let result = decrypt(&keys(), "phone", encrypted.clone());
+        assert!(matches!(result, Err(ConvertError::Decryption(name, _)) if name == "phone"));
+
+        // An unknown key
+        let other = StaticKeyProvider::new(DataKey::new("k3", [3; 32]));
+        let err = decrypt(&other, "email", encrypted.clone()).unwrap_err();
+        assert!(err.to_string().contains("key `k2` is not found"));
+
+        // A modified value
+        let mut bytes = encrypted.as_b().unwrap().clone().into_inner();
+        *bytes.last_mut().unwrap() ^= 1;
+        let result = decrypt(&keys(), "email", AttributeValue::B(Blob::new(bytes)));
+        assert!(matches!(result, Err(ConvertError::Decryption(name, _)) if name == "email"));
+
+        // A plaintext value
+        let result = decrypt(&keys(), "email", s("x"));
+        assert!(matches!(result, Err(ConvertError::Decryption(name, _)) if name == "email"));
+    }
+
+    #[test]
+    fn items_are_signed_and_verified() {
+        let mut item = item();
+        sign(&keys(), &TABLE, &mut item, &["email", "phone"]).unwrap();
+        assert!(item.contains_key(SIGNATURE_ATTRIBUTE));
+        verify(&keys(), &TABLE, &item, &["phone", "email"]).unwrap();
+
+        let verify_changed = |name: &str, value: Option<AttributeValue>| {
+            let mut item = item.clone();
+            match value {
+                Some(value) => item.insert(name.into(), value),
+                None => item.remove(name),
+            };
+            verify(&keys(), &TABLE, &item, &["email", "phone"])
+        };
+
+        for (name, value) in [
+            ("PK", Some(s("customer#2"))),
+            ("SK", Some(AttributeValue::N("2".into()))),
+            ("email", Some(self::item().remove("email").unwrap())),
+            ("email", None),
+            ("phone", Some(AttributeValue::B(Blob::new(vec![1])))),
+            (SIGNATURE_ATTRIBUTE, None),
+        ] {
+            assert!(
+                matches!(
+                    verify_changed(name, value),
+                    Err(ConvertError::Decryption(name, _)) if name == SIGNATURE_ATTRIBUTE
+                ),
+                "{name}"
+            );
+        }
+
+        // Other attributes are not signed.
+        assert!(verify_changed("name", Some(s("Kanji"))).is_ok());
+    }
+
+    #[test]
+    fn missing_key_attributes_are_errors() {
+        let mut item = item();
+        item.remove("SK");
+        let err = sign(&keys(), &TABLE, &mut item, &[]).unwrap_err();
+        assert!(matches!(err, ConvertError::Encryption(name, _) if name == SIGNATURE_ATTRIBUTE));
+    }
+
+    #[test]
+    fn data_keys_are_not_printed() {
+        let key = DataKey::new("k1", [1; 32]);
+        assert_eq!(format!("{key:?}"), r#"DataKey { id: "k1", key: ".." }"#);
+    }
+}
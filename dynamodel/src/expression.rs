@@ -0,0 +1,445 @@
+use super::{AttributeValueConvertible, ConvertError};
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+
+/// An update expression and a condition expression with their placeholders.
+///
+/// DynamoDB write requests take `UpdateExpression`, `ConditionExpression`,
+/// `ExpressionAttributeNames` and `ExpressionAttributeValues` separately, but the placeholders
+/// are shared by both expressions. This type keeps them together.
+///
+/// ```rust
+/// use dynamodel::Expression;
+/// # use aws_sdk_dynamodb::types::AttributeValue;
+///
+/// let expression = Expression::update("SET #status = :paid")
+///     .with_condition("#status = :pending")
+///     .name("#status", "Status")
+///     .value(":paid", "paid".to_string())
+///     .value(":pending", "pending".to_string());
+///
+/// assert_eq!(expression.update_expression(), Some("SET #status = :paid"));
+/// assert_eq!(expression.condition_expression(), Some("#status = :pending"));
+/// assert_eq!(
+///     expression.values().get(":paid"),
+///     Some(&AttributeValue::S("paid".into())),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Expression {
+    update: Option<String>,
+    condition: Option<String>,
+    names: HashMap<String, String>,
+    values: HashMap<String, AttributeValue>,
+}
+
+impl Expression {
+    /// Creates an expression that only has a condition expression.
+    pub fn condition(condition: impl Into<String>) -> Self {
+        Self {
+            condition: Some(condition.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates an expression that only has an update expression.
+    pub fn update(update: impl Into<String>) -> Self {
+        Self {
+            update: Some(update.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the condition expression.
+    ///
+    /// If a condition expression is already set, both of them must be met.
+    pub fn with_condition(mut self, condition: impl Into<String>) -> Self {
+        let condition = condition.into();
+        self.condition = Some(match self.condition.take() {
+            Some(current) => format!("({current}) AND ({condition})"),
+            None => condition,
+        });
+        self
+    }
+
+    /// Sets the update expression.
+    ///
+    /// If an update expression is already set, the new one is appended to it.
+    /// Both of them can have any clauses, like `SET a = :a` and `SET b = :b REMOVE c`,
+    /// and the result merges the clauses of the same action.
+    pub fn with_update(mut self, update: impl Into<String>) -> Self {
+        let update = update.into();
+        self.update = Some(match self.update.take() {
+            Some(current) => merge_update_expressions(&current, &update),
+            None => update,
+        });
+        self
+    }
+
+    /// Adds a placeholder for an attribute name, like `#name`.
+    pub fn name(mut self, placeholder: impl Into<String>, name: impl Into<String>) -> Self {
+        self.names.insert(placeholder.into(), name.into());
+        self
+    }
+
+    /// Adds a placeholder for a value, like `:value`.
+    ///
+    /// The value must convert infallibly. Use [`Expression::try_value`] for the types whose
+    /// [`FALLIBLE`](AttributeValueConvertible::FALLIBLE) is `true`.
+    pub fn value<V>(mut self, placeholder: impl Into<String>, value: V) -> Self
+    where
+        V: AttributeValueConvertible,
+    {
+        self.values
+            .insert(placeholder.into(), value.into_attribute_value());
+        self
+    }
+
+    /// Adds a placeholder for a value that can fail to convert, or returns the error.
+    pub fn try_value<V>(
+        mut self,
+        placeholder: impl Into<String>,
+        value: V,
+    ) -> Result<Self, ConvertError>
+    where
+        V: AttributeValueConvertible,
+    {
+        self.values
+            .insert(placeholder.into(), value.try_into_attribute_value()?);
+        Ok(self)
+    }
+
+    /// Merges the other expression into this one.
+    ///
+    /// The condition expressions are joined with `AND`, the update expressions are merged
+    /// like [`Expression::with_update`] and the placeholders of both are kept. A placeholder of
+    /// the other expression that this one uses for another name or value is renamed, like `:v`
+    /// into `:v_1`, so both expressions keep their meaning.
+    ///
+    /// ```rust
+    /// use dynamodel::Expression;
+    ///
+    /// let expression = Expression::update("SET #a = :v")
+    ///     .name("#a", "A")
+    ///     .value(":v", 1)
+    ///     .merge(Expression::update("SET #b = :v").name("#b", "B").value(":v", 2));
+    ///
+    /// assert_eq!(expression.update_expression(), Some("SET #a = :v, #b = :v_1"));
+    /// ```
+    pub fn merge(mut self, mut other: Expression) -> Self {
+        let names: Vec<String> = conflicts(&self.names, &other.names);
+        for placeholder in names {
+            let renamed = unused_placeholder(&placeholder, |p| {
+                self.names.contains_key(p) || other.names.contains_key(p)
+            });
+            if let Some(name) = other.names.remove(&placeholder) {
+                other.names.insert(renamed.clone(), name);
+                other.rename_placeholder(&placeholder, &renamed);
+            }
+        }
+
+        let values: Vec<String> = conflicts(&self.values, &other.values);
+        for placeholder in values {
+            let renamed = unused_placeholder(&placeholder, |p| {
+                self.values.contains_key(p) || other.values.contains_key(p)
+            });
+            if let Some(value) = other.values.remove(&placeholder) {
+                other.values.insert(renamed.clone(), value);
+                other.rename_placeholder(&placeholder, &renamed);
+            }
+        }
+
+        if let Some(condition) = other.condition {
+            self = self.with_condition(condition);
+        }
+        if let Some(update) = other.update {
+            self = self.with_update(update);
+        }
+        self.names.extend(other.names);
+        self.values.extend(other.values);
+        self
+    }
+
+    fn rename_placeholder(&mut self, from: &str, to: &str) {
+        for expression in [&mut self.update, &mut self.condition]
+            .into_iter()
+            .flatten()
+        {
+            *expression = rename_placeholder(expression, from, to);
+        }
+    }
+
+    /// The update expression.
+    pub fn update_expression(&self) -> Option<&str> {
+        self.update.as_deref()
+    }
+
+    /// The condition expression.
+    pub fn condition_expression(&self) -> Option<&str> {
+        self.condition.as_deref()
+    }
+
+    /// The placeholders for attribute names.
+    pub fn names(&self) -> &HashMap<String, String> {
+        &self.names
+    }
+
+    /// The placeholders for values.
+    pub fn values(&self) -> &HashMap<String, AttributeValue> {
+        &self.values
+    }
+
+    /// The placeholders for attribute names in the form the SDK builders accept.
+    pub fn attribute_names(&self) -> Option<HashMap<String, String>> {
+        Some(self.names.clone()).filter(|names| !names.is_empty())
+    }
+
+    /// The placeholders for values in the form the SDK builders accept.
+    pub fn attribute_values(&self) -> Option<HashMap<String, AttributeValue>> {
+        Some(self.values.clone()).filter(|values| !values.is_empty())
+    }
+}
+
+// The placeholders that both have, but for different names or values.
+fn conflicts<V: PartialEq>(a: &HashMap<String, V>, b: &HashMap<String, V>) -> Vec<String> {
+    b.iter()
+        .filter(|(placeholder, v)| a.get(*placeholder).is_some_and(|own| own != *v))
+        .map(|(placeholder, _)| placeholder.clone())
+        .collect()
+}
+
+fn unused_placeholder(placeholder: &str, is_used: impl Fn(&str) -> bool) -> String {
+    let mut n = 1;
+    while is_used(&format!("{placeholder}_{n}")) {
+        n += 1;
+    }
+    format!("{placeholder}_{n}")
+}
+
+// Replaces the placeholder, but not the longer ones starting with it, like `:v` in `:v2`.
+fn rename_placeholder(expression: &str, from: &str, to: &str) -> String {
+    let mut renamed = String::with_capacity(expression.len());
+    let mut rest = expression;
+
+    while let Some(start) = rest.find(from) {
+        let end = start + from.len();
+        let is_longer = rest
+            .as_bytes()
+            .get(end)
+            .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_');
+
+        renamed.push_str(&rest[..start]);
+        renamed.push_str(if is_longer { from } else { to });
+        rest = &rest[end..];
+    }
+
+    renamed.push_str(rest);
+    renamed
+}
+
+const ACTIONS: [&str; 4] = ["SET", "REMOVE", "ADD", "DELETE"];
+
+fn merge_update_expressions(a: &str, b: &str) -> String {
+    let mut clauses: Vec<(&str, Vec<&str>)> = vec![];
+
+    for (action, body) in split_clauses(a).into_iter().chain(split_clauses(b)) {
+        match clauses.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, bodies)) => bodies.push(body),
+            None => clauses.push((action, vec![body])),
+        }
+    }
+
+    clauses
+        .into_iter()
+        .map(|(action, bodies)| format!("{action} {}", bodies.join(", ")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Splits an update expression into its clauses, like `[("SET", "a = :a"), ("REMOVE", "b")]`.
+fn split_clauses(expression: &str) -> Vec<(&'static str, &str)> {
+    let mut starts: Vec<(usize, &'static str)> = vec![];
+    let mut depth = 0usize;
+    let bytes = expression.as_bytes();
+
+    for (i, c) in expression.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => {
+                let at_word_start = i == 0 || !is_word_byte(bytes[i - 1]);
+                if !at_word_start {
+                    continue;
+                }
+                for action in ACTIONS {
+                    let end = i + action.len();
+                    if expression.len() >= end
+                        && expression[i..end].eq_ignore_ascii_case(action)
+                        && bytes.get(end).is_none_or(|b| !is_word_byte(*b))
+                    {
+                        starts.push((i, action));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, (start, action))| {
+            let end = starts
+                .get(n + 1)
+                .map(|(i, _)| *i)
+                .unwrap_or(expression.len());
+            (*action, expression[start + action.len()..end].trim())
+        })
+        .collect()
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'#' | b':' | b'.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_are_joined_with_and() {
+        let expression = Expression::condition("attribute_exists(PK)")
+            .with_condition("#v = :v")
+            .name("#v", "Version")
+            .value(":v", 1u64);
+
+        assert_eq!(
+            expression.condition_expression(),
+            Some("(attribute_exists(PK)) AND (#v = :v)"),
+        );
+        assert_eq!(expression.names().get("#v").unwrap(), "Version");
+        assert_eq!(
+            expression.values().get(":v"),
+            Some(&AttributeValue::N("1".into())),
+        );
+    }
+
+    #[test]
+    fn update_expressions_are_merged_by_action() {
+        let expression = Expression::update("SET #a = :a REMOVE #b")
+            .with_update("set #c = if_not_exists(#c, :c) ADD #d :d")
+            .with_update("REMOVE #e");
+
+        assert_eq!(
+            expression.update_expression(),
+            Some("SET #a = :a, #c = if_not_exists(#c, :c) REMOVE #b, #e ADD #d :d"),
+        );
+    }
+
+    #[test]
+    fn attribute_names_that_look_like_actions_are_not_split() {
+        let expression =
+            Expression::update("SET #a = SETTINGS, ADDRESS = :v").with_update("SET b = :b");
+
+        assert_eq!(
+            expression.update_expression(),
+            Some("SET #a = SETTINGS, ADDRESS = :v, b = :b"),
+        );
+    }
+
+    #[test]
+    fn expressions_can_be_merged() {
+        let expression = Expression::update("SET #a = :a").name("#a", "A").merge(
+            Expression::condition("attribute_not_exists(PK)")
+                .with_update("SET #b = :b")
+                .name("#b", "B"),
+        );
+
+        assert_eq!(expression.update_expression(), Some("SET #a = :a, #b = :b"));
+        assert_eq!(
+            expression.condition_expression(),
+            Some("attribute_not_exists(PK)")
+        );
+        assert_eq!(expression.names().len(), 2);
+    }
+
+    #[test]
+    fn conflicting_placeholders_are_renamed_on_merge() {
+        let expression = Expression::update("SET #a = :v")
+            .with_condition("#a <> :v2")
+            .name("#a", "A")
+            .value(":v", 1)
+            .value(":v2", 0)
+            .merge(
+                Expression::update("SET #a = :v, #a2 = :v2")
+                    .with_condition("attribute_exists(#a) AND :v <> :v2")
+                    .name("#a", "B")
+                    .name("#a2", "A2")
+                    .value(":v", 2)
+                    .value(":v2", 0),
+            );
+
+        assert_eq!(
+            expression.update_expression(),
+            Some("SET #a = :v, #a_1 = :v_1, #a2 = :v2"),
+        );
+        assert_eq!(
+            expression.condition_expression(),
+            Some("(#a <> :v2) AND (attribute_exists(#a_1) AND :v_1 <> :v2)"),
+        );
+        assert_eq!(expression.names()["#a"], "A");
+        assert_eq!(expression.names()["#a_1"], "B");
+        assert_eq!(expression.values()[":v"], AttributeValue::N("1".into()));
+        assert_eq!(expression.values()[":v_1"], AttributeValue::N("2".into()));
+        assert_eq!(expression.values().len(), 3);
+    }
+
+    #[test]
+    fn renamed_placeholders_skip_used_ones() {
+        let expression = Expression::update("SET A = :v, B = :v_1")
+            .value(":v", 1)
+            .value(":v_1", 2)
+            .merge(Expression::update("SET C = :v").value(":v", 3));
+
+        assert_eq!(
+            expression.update_expression(),
+            Some("SET A = :v, B = :v_1, C = :v_2"),
+        );
+        assert_eq!(expression.values()[":v_2"], AttributeValue::N("3".into()));
+    }
+
+    #[test]
+    fn fallible_values_are_added_with_try_value() {
+        struct Score(i32);
+
+        impl AttributeValueConvertible for Score {
+            const FALLIBLE: bool = true;
+
+            fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+                match self.0 {
+                    0.. => Ok(AttributeValue::N(self.0.to_string())),
+                    _ => Err(ConvertError::InvalidNumber(self.0.to_string())),
+                }
+            }
+
+            fn try_from_attribute_value(_: &AttributeValue) -> Result<Self, ConvertError> {
+                unimplemented!()
+            }
+        }
+
+        let expression = Expression::update("SET #s = :s")
+            .try_value(":s", Score(1))
+            .unwrap();
+        assert_eq!(expression.values()[":s"], AttributeValue::N("1".into()));
+
+        let result = Expression::update("SET #s = :s").try_value(":s", Score(-1));
+        assert!(matches!(result, Err(ConvertError::InvalidNumber(v)) if v == "-1"));
+    }
+
+    #[test]
+    fn empty_placeholders_are_none() {
+        let expression = Expression::condition("attribute_exists(PK)");
+        assert_eq!(expression.attribute_names(), None);
+        assert_eq!(expression.attribute_values(), None);
+    }
+}
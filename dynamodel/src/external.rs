@@ -0,0 +1,260 @@
+//! `AttributeValueConvertible` implementations for the types of optional dependencies.
+
+use super::{AttributeValueConvertible, ConvertError, unmatch_err};
+use aws_sdk_dynamodb::types::AttributeValue;
+
+fn parse_err<T>(value: impl Into<String>) -> ConvertError {
+    ConvertError::ParseValue(std::any::type_name::<T>().into(), value.into())
+}
+
+/// A UUID is stored as a hyphenated string like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+///
+/// Use [`CompactUuid`] to store it as 16 bytes instead.
+#[cfg(feature = "uuid")]
+impl AttributeValueConvertible for uuid::Uuid {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::S(self.hyphenated().to_string())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let s = value.as_s().map_err(unmatch_err("S"))?;
+        Self::parse_str(s).map_err(|_| parse_err::<Self>(s))
+    }
+}
+
+/// A UUID stored in the compact form, the 16 bytes as `AttributeValue::B`.
+///
+/// ```rust
+/// use dynamodel::{AttributeValueConvertible, CompactUuid};
+/// use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+/// use uuid::Uuid;
+///
+/// let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+///
+/// let value = CompactUuid(id).into_attribute_value();
+/// assert_eq!(value, AttributeValue::B(Blob::new(id.as_bytes().to_vec())));
+///
+/// let converted = CompactUuid::try_from_attribute_value(&value).unwrap();
+/// assert_eq!(converted, CompactUuid(id));
+/// ```
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompactUuid(pub uuid::Uuid);
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for CompactUuid {
+    fn from(id: uuid::Uuid) -> Self {
+        Self(id)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<CompactUuid> for uuid::Uuid {
+    fn from(id: CompactUuid) -> Self {
+        id.0
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl std::ops::Deref for CompactUuid {
+    type Target = uuid::Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl AttributeValueConvertible for CompactUuid {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::B(self.0.as_bytes().to_vec().into())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let bytes = value.as_b().map_err(unmatch_err("B"))?.as_ref();
+        uuid::Uuid::from_slice(bytes)
+            .map(Self)
+            .map_err(|_| parse_err::<uuid::Uuid>(format!("{bytes:02x?}")))
+    }
+}
+
+/// A decimal is stored as `AttributeValue::N` with its scale, like `1.50`.
+///
+/// Reading a number with more digits than a decimal can hold is an error rather than rounding it.
+#[cfg(feature = "rust_decimal")]
+impl AttributeValueConvertible for rust_decimal::Decimal {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::N(self.to_string())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let n = value.as_n().map_err(unmatch_err("N"))?;
+        if n.contains(['e', 'E']) {
+            Self::from_scientific(n)
+        } else {
+            Self::from_str_exact(n)
+        }
+        .map_err(|_| parse_err::<Self>(n))
+    }
+}
+
+/// A big decimal is stored as `AttributeValue::N` through [`Number`](crate::Number), keeping its digits and scale.
+///
+/// DynamoDB holds up to 38 significant digits, so it is fallible, and
+/// [`try_into_attribute_value`](AttributeValueConvertible::try_into_attribute_value) fails on a
+/// decimal with more digits or out of its range.
+#[cfg(feature = "bigdecimal")]
+impl AttributeValueConvertible for bigdecimal::BigDecimal {
+    const FALLIBLE: bool = true;
+
+    fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+        self.to_string()
+            .parse::<super::Number>()?
+            .try_into_attribute_value()
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let n = value.as_n().map_err(unmatch_err("N"))?;
+        n.parse::<super::Number>()
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| parse_err::<Self>(n))
+    }
+}
+
+/// A ULID is stored as its 26-character string, which sorts in time order.
+#[cfg(feature = "ulid")]
+impl AttributeValueConvertible for ulid::Ulid {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::S(self.to_string())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let s = value.as_s().map_err(unmatch_err("S"))?;
+        Self::from_string(s).map_err(|_| parse_err::<Self>(s))
+    }
+}
+
+#[cfg(feature = "url")]
+impl AttributeValueConvertible for url::Url {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::S(self.into())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let s = value.as_s().map_err(unmatch_err("S"))?;
+        Self::parse(s).map_err(|_| parse_err::<Self>(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuids_are_stored_as_strings_or_bytes() {
+        use uuid::Uuid;
+
+        let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+        let value = id.into_attribute_value();
+        assert_eq!(
+            value,
+            AttributeValue::S("67e55044-10b1-426f-9247-bb680e5fe0c8".into())
+        );
+        assert_eq!(Uuid::try_from_attribute_value(&value).unwrap(), id);
+
+        let value = AttributeValue::S("67E5504410B1426F9247BB680E5FE0C8".into());
+        assert_eq!(Uuid::try_from_attribute_value(&value).unwrap(), id);
+
+        let result = Uuid::try_from_attribute_value(&AttributeValue::S("67e55044".into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "uuid::Uuid" && v == "67e55044")
+        );
+
+        let result = CompactUuid::try_from_attribute_value(&AttributeValue::B(vec![1, 2].into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "uuid::Uuid" && v == "[01, 02]")
+        );
+
+        let result = CompactUuid::try_from_attribute_value(&value);
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "B"));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimals_are_stored_as_exact_numbers() {
+        use rust_decimal::Decimal;
+
+        let value = AttributeValue::N("12345678901234567890.12345678".into());
+        let decimal = Decimal::try_from_attribute_value(&value).unwrap();
+        assert_eq!(decimal.to_string(), "12345678901234567890.12345678");
+        assert_eq!(decimal.into_attribute_value(), value);
+
+        let decimal = Decimal::try_from_attribute_value(&AttributeValue::N("1.5E+2".into()));
+        assert_eq!(decimal.unwrap(), Decimal::from(150));
+
+        let result = Decimal::try_from_attribute_value(&AttributeValue::N("9".repeat(38)));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, _)) if t == "rust_decimal::decimal::Decimal")
+        );
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn big_decimals_round_trip_through_numbers() {
+        use bigdecimal::BigDecimal;
+
+        let digits = "12345678901234567890123456789012345.678";
+        let value = AttributeValue::N(digits.into());
+        let decimal = BigDecimal::try_from_attribute_value(&value).unwrap();
+        assert_eq!(decimal.to_string(), digits);
+        assert_eq!(decimal.try_into_attribute_value().unwrap(), value);
+
+        let decimal = BigDecimal::try_from_attribute_value(&AttributeValue::N("1.5E+2".into()));
+        assert_eq!(decimal.unwrap(), BigDecimal::from(150));
+
+        let decimal: BigDecimal = "1".repeat(39).parse().unwrap();
+        let result = decimal.try_into_attribute_value();
+        assert!(matches!(result, Err(ConvertError::InvalidNumber(_))));
+
+        let result = BigDecimal::try_from_attribute_value(&AttributeValue::N("one".into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "bigdecimal::BigDecimal" && v == "one")
+        );
+
+        let result = BigDecimal::try_from_attribute_value(&AttributeValue::S("1".into()));
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "N"));
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn ulids_are_stored_as_strings() {
+        use ulid::Ulid;
+
+        let value = AttributeValue::S("01ARZ3NDEKTSV4RRFFQ69G5FAV".into());
+        let id = Ulid::try_from_attribute_value(&value).unwrap();
+        assert_eq!(id.into_attribute_value(), value);
+
+        let later = Ulid::from_parts(id.timestamp_ms() + 1, 0).into_attribute_value();
+        assert!(value.as_s().unwrap() < later.as_s().unwrap());
+
+        let result = Ulid::try_from_attribute_value(&AttributeValue::S("01HF7YAT0".into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "ulid::Ulid" && v == "01HF7YAT0")
+        );
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn urls_are_stored_as_strings() {
+        use url::Url;
+
+        let url = Url::parse("https://example.com/videos?id=7cf27a02").unwrap();
+        let value = url.clone().into_attribute_value();
+        assert_eq!(
+            value,
+            AttributeValue::S("https://example.com/videos?id=7cf27a02".into())
+        );
+        assert_eq!(Url::try_from_attribute_value(&value).unwrap(), url);
+
+        let result = Url::try_from_attribute_value(&AttributeValue::S("example.com".into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "url::Url" && v == "example.com")
+        );
+    }
+}
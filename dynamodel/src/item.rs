@@ -0,0 +1,134 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+
+/// Maps that can hold the attributes of an item.
+///
+/// Objects with the [`Dynamodel`](crate::Dynamodel) macro convert from and into any of these with
+/// `into_item_map`, `try_into_item_map` and `try_from_item_map`. The SDK uses
+/// `HashMap<String, AttributeValue>`, but a `HashMap` with another hasher or a `BTreeMap`, which
+/// keeps the attributes sorted, works the same way.
+///
+/// ```rust
+/// use dynamodel::Dynamodel;
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Dynamodel, Debug, PartialEq)]
+/// struct Person {
+///     name: String,
+///     age: u8,
+/// }
+///
+/// let person = Person { name: "Kanji".into(), age: 23 };
+///
+/// let item: BTreeMap<String, AttributeValue> = person.into_item_map();
+/// assert_eq!(item.keys().collect::<Vec<_>>(), ["age", "name"]);
+///
+/// let person = Person::try_from_item_map(item).unwrap();
+/// assert_eq!(person, Person { name: "Kanji".into(), age: 23 });
+/// ```
+///
+/// Functions of the `extra` and `try_from_item` attributes still take and return
+/// `HashMap<String, AttributeValue>`, so the items of objects with `try_from_item` fields are
+/// converted into one before reading them.
+pub trait ItemMap:
+    Sized + IntoIterator<Item = (String, AttributeValue)> + Extend<(String, AttributeValue)>
+{
+    /// Creates an empty map with space for at least `capacity` attributes, if the map can reserve it.
+    fn with_capacity(capacity: usize) -> Self;
+
+    fn get(&self, key: &str) -> Option<&AttributeValue>;
+
+    fn insert(&mut self, key: String, value: AttributeValue) -> Option<AttributeValue>;
+
+    fn remove(&mut self, key: &str) -> Option<AttributeValue>;
+
+    /// Converts from the map the SDK uses.
+    fn from_hash_map(map: HashMap<String, AttributeValue>) -> Self {
+        let mut item = Self::with_capacity(map.len());
+        item.extend(map);
+        item
+    }
+
+    /// Converts into the map the SDK uses.
+    fn into_hash_map(self) -> HashMap<String, AttributeValue> {
+        self.into_iter().collect()
+    }
+}
+
+impl<S: BuildHasher + Default> ItemMap for HashMap<String, AttributeValue, S> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn get(&self, key: &str) -> Option<&AttributeValue> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: AttributeValue) -> Option<AttributeValue> {
+        HashMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<AttributeValue> {
+        HashMap::remove(self, key)
+    }
+}
+
+impl ItemMap for BTreeMap<String, AttributeValue> {
+    fn with_capacity(_capacity: usize) -> Self {
+        Self::new()
+    }
+
+    fn get(&self, key: &str) -> Option<&AttributeValue> {
+        BTreeMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: AttributeValue) -> Option<AttributeValue> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<AttributeValue> {
+        BTreeMap::remove(self, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    type OtherHashMap = HashMap<String, AttributeValue, BuildHasherDefault<DefaultHasher>>;
+
+    fn item() -> HashMap<String, AttributeValue> {
+        [
+            ("id".to_string(), AttributeValue::S("7cf27a02".into())),
+            ("count".to_string(), AttributeValue::N("147".into())),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn maps_are_converted_from_and_into_hash_maps() {
+        let converted = OtherHashMap::from_hash_map(item());
+        assert_eq!(converted.len(), 2);
+        assert_eq!(converted.into_hash_map(), item());
+
+        let converted = BTreeMap::from_hash_map(item());
+        assert_eq!(converted.keys().collect::<Vec<_>>(), ["count", "id"]);
+        assert_eq!(converted.into_hash_map(), item());
+    }
+
+    #[test]
+    fn maps_insert_get_and_remove_attributes() {
+        let mut map = BTreeMap::with_capacity(2);
+        assert!(ItemMap::insert(&mut map, "id".into(), AttributeValue::Bool(true)).is_none());
+        assert_eq!(ItemMap::get(&map, "id"), Some(&AttributeValue::Bool(true)));
+        assert_eq!(
+            ItemMap::remove(&mut map, "id"),
+            Some(AttributeValue::Bool(true))
+        );
+        assert!(map.is_empty());
+    }
+}
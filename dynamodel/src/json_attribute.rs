@@ -0,0 +1,101 @@
+//! JSON strings in `S` attributes.
+//!
+//! Fields with the `json` attribute of the [`Dynamodel`](crate::Dynamodel) macro are serialized
+//! with serde into a JSON string and stored as `AttributeValue::S`, like legacy items often hold
+//! complex values. The macro calls these functions, and they are public for hand-written
+//! conversions of the same attributes.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//! use serde::{Deserialize, Serialize};
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//! # use std::collections::HashMap;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Dimensions {
+//!     width: u32,
+//!     height: u32,
+//! }
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct Image {
+//!     id: String,
+//!     #[dynamodel(json)]
+//!     dimensions: Dimensions,
+//! }
+//!
+//! let image = Image {
+//!     id: "7cf27a02".into(),
+//!     dimensions: Dimensions { width: 640, height: 480 },
+//! };
+//!
+//! let item: HashMap<String, AttributeValue> = image.try_into_item().unwrap();
+//! assert_eq!(
+//!     item["dimensions"],
+//!     AttributeValue::S(r#"{"width":640,"height":480}"#.into()),
+//! );
+//! ```
+
+use super::{ConvertError, unmatch_err};
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Serializes the value of the attribute into a JSON string.
+///
+/// The error is [`ConvertError::Json`] with the name of the attribute.
+pub fn to_attribute_value<T: Serialize + ?Sized>(
+    name: &str,
+    value: &T,
+) -> Result<AttributeValue, ConvertError> {
+    serde_json::to_string(value)
+        .map(AttributeValue::S)
+        .map_err(|e| json_err(name, e))
+}
+
+/// Deserializes the value of the attribute from a JSON string.
+///
+/// The error is [`ConvertError::AttributeValueUnmatched`] if the value is not `S`, and
+/// [`ConvertError::Json`] with the name of the attribute if the string is not valid JSON of `T`.
+pub fn from_attribute_value<T: DeserializeOwned>(
+    name: &str,
+    value: &AttributeValue,
+) -> Result<T, ConvertError> {
+    let json = value.as_s().map_err(unmatch_err("S"))?;
+    serde_json::from_str(json).map_err(|e| json_err(name, e))
+}
+
+fn json_err(name: &str, err: serde_json::Error) -> ConvertError {
+    ConvertError::Json(name.into(), Box::new(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn values_are_json_strings() {
+        let value = BTreeMap::from([("a", vec![1, 2])]);
+
+        let converted = to_attribute_value("tags", &value).unwrap();
+        assert_eq!(converted, AttributeValue::S(r#"{"a":[1,2]}"#.into()));
+
+        let parsed: BTreeMap<String, Vec<u8>> = from_attribute_value("tags", &converted).unwrap();
+        assert_eq!(parsed, BTreeMap::from([("a".to_string(), vec![1, 2])]));
+    }
+
+    #[test]
+    fn errors_have_the_attribute_name() {
+        let result = from_attribute_value::<Vec<u8>>("tags", &AttributeValue::S("[1,".into()));
+        assert!(matches!(result, Err(ConvertError::Json(name, _)) if name == "tags"));
+
+        let result = to_attribute_value("tags", &HashMap::from([((1, 2), 3)]));
+        assert!(matches!(result, Err(ConvertError::Json(name, _)) if name == "tags"));
+    }
+
+    #[test]
+    fn values_must_be_strings() {
+        let result = from_attribute_value::<u8>("count", &AttributeValue::N("1".into()));
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "S"));
+    }
+}
@@ -0,0 +1,102 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+
+/// The key attributes of a table or a secondary index.
+///
+/// ```rust
+/// use dynamodel::KeySchema;
+///
+/// const TABLE: KeySchema = KeySchema::new("PK").with_sort_key("SK");
+/// const GSI1: KeySchema = KeySchema::new("GSI1PK").with_sort_key("GSI1SK");
+///
+/// assert_eq!(TABLE.partition_key(), "PK");
+/// assert_eq!(GSI1.sort_key(), Some("GSI1SK"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySchema {
+    partition_key: &'static str,
+    sort_key: Option<&'static str>,
+}
+
+impl KeySchema {
+    /// Creates a key schema that only has a partition key.
+    pub const fn new(partition_key: &'static str) -> Self {
+        Self {
+            partition_key,
+            sort_key: None,
+        }
+    }
+
+    /// Adds a sort key to the key schema.
+    pub const fn with_sort_key(self, sort_key: &'static str) -> Self {
+        Self {
+            partition_key: self.partition_key,
+            sort_key: Some(sort_key),
+        }
+    }
+
+    /// The name of the partition key attribute.
+    pub const fn partition_key(&self) -> &'static str {
+        self.partition_key
+    }
+
+    /// The name of the sort key attribute, if any.
+    pub const fn sort_key(&self) -> Option<&'static str> {
+        self.sort_key
+    }
+
+    /// The names of the key attributes, the partition key first.
+    pub fn attribute_names(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.partition_key).chain(self.sort_key)
+    }
+
+    /// Returns true if `name` is one of the key attributes.
+    pub fn contains(&self, name: &str) -> bool {
+        self.attribute_names().any(|key| key == name)
+    }
+
+    /// Extracts the key attributes from an item.
+    ///
+    /// Returns `None` if the item lacks any of the key attributes.
+    pub fn key_of(
+        &self,
+        item: &HashMap<String, AttributeValue>,
+    ) -> Option<HashMap<String, AttributeValue>> {
+        self.attribute_names()
+            .map(|name| item.get(name).map(|v| (name.to_string(), v.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_attributes_can_be_extracted_from_an_item() {
+        let schema = KeySchema::new("PK").with_sort_key("SK");
+        let item: HashMap<String, AttributeValue> = [
+            ("PK".to_string(), AttributeValue::S("video#1".into())),
+            ("SK".to_string(), AttributeValue::S("stats".into())),
+            ("ViewCount".to_string(), AttributeValue::N("10".into())),
+        ]
+        .into();
+
+        let expected: HashMap<String, AttributeValue> = [
+            ("PK".to_string(), AttributeValue::S("video#1".into())),
+            ("SK".to_string(), AttributeValue::S("stats".into())),
+        ]
+        .into();
+
+        assert_eq!(schema.key_of(&item), Some(expected));
+    }
+
+    #[test]
+    fn key_of_returns_none_when_a_key_attribute_is_missing() {
+        let schema = KeySchema::new("PK").with_sort_key("SK");
+        let item: HashMap<String, AttributeValue> =
+            [("PK".to_string(), AttributeValue::S("video#1".into()))].into();
+
+        assert_eq!(schema.key_of(&item), None);
+    }
+}
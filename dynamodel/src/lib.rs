@@ -34,6 +34,13 @@
 //! | `u8, u16, u32, u64, u128, usize`<br>`i8, i16, i32, i64, i128, isize`<br>`f32, f64` | `AttributeValue::N("...")` |
 //! | `bool` | `AttributeValue::Bool(...)` |
 //! | `Vec` of any types that implement `AttributeValueConvertible` | `AttributeValue::L([...])` |
+//! | `AttributeValue` | As it is |
+//! | [`Number`], `rust_decimal::Decimal`, `bigdecimal::BigDecimal` (with their features) | `AttributeValue::N("...")` without losing precision |
+//! | `SystemTime`<br>`chrono::DateTime<Utc>`, `time::OffsetDateTime`, `jiff::Timestamp` (with their features) | `AttributeValue::S("2024-01-02T03:04:05.000000000Z")` |
+//! | `chrono::NaiveDate` (with the `chrono` feature) | `AttributeValue::S("2024-01-02")` |
+//! | `uuid::Uuid`, `ulid::Ulid`, `url::Url` (with their features) | `AttributeValue::S("...")` |
+//! | [`CompactUuid`] (with the `uuid` feature) | `AttributeValue::B(...)` with the 16 bytes of the UUID |
+//! | [`Serde<T>`](Serde) of any `Serialize` and `Deserialize` type (with the `serde` feature) | The value as serialized with the [`serde`](mod@serde) module, with the `fallible` field attribute and `try_into_item` instead of `From` |
 //! | Any types that implement `Dynamodel` macro | `AttributeValue::M({ ... })` |
 //!
 //! The last row of the above table shows that once you apply the [`Dynamodel`] macro to your object,
@@ -41,6 +48,15 @@
 //!
 //! **So, you can create nested structures of objects that apply the [`Dynamodel`] macro.**
 //!
+//! Numbers are written in plain notation, like `100` for `1E+2`, and integers are read from
+//! exponent forms too as long as they are integral. Reading a number out of the range of the
+//! field's type returns [`ConvertError::NumberOverflow`].
+//!
+//! DynamoDB can't store some values of `u128`, `i128`, `f32`, `f64` and `bigdecimal::BigDecimal`,
+//! like `NaN`, an infinity or an integer with 39 digits, so these types are fallible. Their fields
+//! need the `fallible` attribute, and `try_into_item` returns [`ConvertError::InvalidNumber`]
+//! for such a number.
+//!
 //! If you want to use additional types, you need to implement the `AttributeValueConvertible`
 //! trait for your type.
 //!
@@ -114,8 +130,102 @@
 //! | Field attribute | Argument | Return |
 //! |---|---|---|
 //! | `#[dynamodel(into = "...")]`| `field type` | `AttributeValue` |
+//! | `#[dynamodel(try_into = "...")]` | `field type` | `Result<AttributeValue, E>` |
 //! | `#[dynamodel(try_from = "...")]` | `&AttributeValue` | `Result<field type, ConvertError>` |
 //!
+//! Use `try_into` instead of `into` if the conversion can fail. Then the derive macro implements
+//! `TryFrom<YourStruct>` for the HashMap instead of `From`, and the error of the function is
+//! returned from `YourStruct::try_into_item()`. [`ConvertError`] must implement `From` for the
+//! error type.
+//!
+//! `try_into_item()` is available on every object with the `Dynamodel` macro. It also returns an
+//! error if [`AttributeValueConvertible::try_into_attribute_value`] of a field fails,
+//! like for a `NaN` float.
+//!
+//! A type whose [`FALLIBLE`](AttributeValueConvertible::FALLIBLE) is `true`, like an object
+//! without `From` or [`Serde<T>`](Serde), can't be converted infallibly. A field of such a type,
+//! including `Vec` and `Option` of it, makes the derived `From` of the outer object fail to
+//! compile, so add `#[dynamodel(fallible)]` to the field to convert it with
+//! `try_into_attribute_value` and make the outer object fallible too. Calling `into_attribute_value` on such an object,
+//! directly or through `Vec` or [`Expression::value`], fails to compile as well, so use
+//! `try_into_attribute_value` and [`Expression::try_value`] instead.
+//!
+//! ### Converting by reference
+//!
+//! The conversions above consume the object. Add the `by_ref` container attribute to convert it
+//! while keeping it, with `to_item(&self)` or `From<&YourStruct>` for the HashMap.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//! # use std::collections::HashMap;
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(by_ref)]
+//! struct Person {
+//!     name: String,
+//!     age: u8,
+//! }
+//!
+//! let person = Person { name: "Kanji".into(), age: 23 };
+//!
+//! let item = person.to_item();
+//! assert_eq!(item, HashMap::from(&person));
+//! assert_eq!(item["name"], AttributeValue::S("Kanji".into()));
+//! ```
+//!
+//! All field types must implement [`ToAttributeValue`], which the types in the table above and
+//! the objects with `by_ref` do, except the fallible ones. Fields with `into` are cloned before
+//! calling the function, and `try_into` fields can't be used with `by_ref`.
+//!
+//! ### Other item maps
+//!
+//! Besides the `HashMap` the SDK uses, objects convert from and into any [`ItemMap`], like a
+//! `HashMap` with a faster hasher or a `BTreeMap`, with `into_item_map`, `try_into_item_map`,
+//! `to_item_map` (with `by_ref`) and `try_from_item_map`.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//! use std::collections::BTreeMap;
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct Person {
+//!     name: String,
+//!     age: u8,
+//! }
+//!
+//! let item: BTreeMap<String, AttributeValue> =
+//!     Person { name: "Kanji".into(), age: 23 }.into_item_map();
+//!
+//! let person = Person::try_from_item_map(item).unwrap();
+//! assert_eq!(person, Person { name: "Kanji".into(), age: 23 });
+//! ```
+//!
+//! ### Crate paths
+//!
+//! The derived code uses the SDK types re-exported as [`aws_sdk_dynamodb`], so depending on
+//! `aws-sdk-dynamodb` yourself is optional. If dynamodel is renamed in your `Cargo.toml` or
+//! re-exported by another crate, tell the macro where it is with the `crate` container attribute.
+//!
+//! ```rust
+//! mod facade {
+//!     pub use dynamodel;
+//! }
+//!
+//! use dynamodel::aws_sdk_dynamodb::types::AttributeValue;
+//! use std::collections::HashMap;
+//!
+//! #[derive(facade::dynamodel::Dynamodel)]
+//! #[dynamodel(crate = "facade::dynamodel")]
+//! struct Person {
+//!     name: String,
+//! }
+//!
+//! let item: HashMap<String, AttributeValue> = Person { name: "Kanji".into() }.into();
+//! assert_eq!(item["name"], AttributeValue::S("Kanji".into()));
+//! ```
+//!
 //! ## Example
 //!
 //! ### Single-table design
@@ -212,6 +322,224 @@
 //! assert_eq!(converted, comment);
 //! ```
 //!
+//! ### Optimistic locking
+//!
+//! Add the `version` attribute to an integer field to implement the [`Versioned`] trait.
+//! [`Versioned::increment_version`] increments the field and returns a condition expression which
+//! requires the stored item to still have the previous version, or not to exist if the version was `0`.
+//! A version at the maximum of its type is a [`ConvertError::NumberOverflow`] instead of wrapping
+//! around.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//!
+//! #[derive(Dynamodel, Clone)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Video {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     title: String,
+//!     #[dynamodel(version)]
+//!     version: u64,
+//! }
+//! ```
+//!
+//! With the `table` feature, `Table::put_versioned` saves the object this way and returns
+//! `TableError::VersionConflict` if another writer has updated the item.
+//!
+//! ### Date and time
+//!
+//! Time types are stored as RFC 3339 strings in UTC by default. Fractional seconds are always written
+//! with 9 digits, so the strings sort in time order and can be used as sort keys. Years before 0000
+//! or after 9999, which RFC 3339 can't hold, are written with a sign like `+10000-01-01T...` and
+//! read back, but they don't sort with the others.
+//!
+//! Use the `format` attribute to store a field in another way. It is one of `rfc3339`,
+//! `epoch_seconds`, `epoch_millis` or a strftime pattern like `%Y-%m-%d`, which requires the `chrono`
+//! feature. [`TimestampFormat`] describes the formats.
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono")]
+//! # mod example {
+//! use dynamodel::Dynamodel;
+//! use chrono::{DateTime, NaiveDate, Utc};
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Event {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     started_at: DateTime<Utc>,
+//!     #[dynamodel(format = "epoch_millis")]
+//!     finished_at: Option<DateTime<Utc>>,
+//!     #[dynamodel(format = "%Y/%m/%d")]
+//!     day: NaiveDate,
+//! }
+//! # }
+//! ```
+//!
+//! ### Timestamps
+//!
+//! Add the `created_at` and `updated_at` attributes to time fields to fill them in on write.
+//! The `created_at` field is set to the current time if it is `None`, and the `updated_at` field is
+//! always set to the current time. [`Timestamped::timestamp_update`] returns an update expression
+//! that does the same with `if_not_exists` for `created_at`, so `UpdateItem` requests keep the
+//! original creation time.
+//!
+//! The fields are stored as RFC 3339 strings by default, and the `format` attribute is available
+//! as well.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//! use std::time::SystemTime;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Video {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(created_at)]
+//!     created_at: Option<SystemTime>,
+//!     #[dynamodel(updated_at, format = "epoch_seconds")]
+//!     updated_at: SystemTime,
+//! }
+//! ```
+//!
+//! `SystemTime` is supported by default, and `chrono::DateTime<Utc>`, `chrono::NaiveDate`,
+//! `time::OffsetDateTime` and `jiff::Timestamp` with the `chrono`, `time` and `jiff` features.
+//!
+//! ### Time to live
+//!
+//! Add the `ttl` attribute to a time field to store it as epoch seconds, which DynamoDB TTL requires.
+//! `None` is not stored, so the item never expires. [`TimeToLive::TTL_ATTRIBUTE`] is the name of the
+//! attribute to enable TTL on.
+//!
+//! ```rust
+//! use dynamodel::Dynamodel;
+//! use std::time::SystemTime;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Session {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(ttl)]
+//!     expires_at: Option<SystemTime>,
+//! }
+//! ```
+//!
+//! ### JSON attributes
+//!
+//! Add the `json` attribute to a field of any `Serialize` and `Deserialize` type to store it as a
+//! JSON string in `AttributeValue::S`, which requires the `json` feature. Serializing can fail,
+//! like for a map with keys that are not strings, so the struct has `try_into_item` instead of
+//! `Into<HashMap<String, AttributeValue>>` and can't have the `by_ref` attribute. The error and
+//! an invalid JSON string are [`ConvertError::Json`] with the name of the attribute.
+//!
+//! ```rust
+//! # #[cfg(feature = "json")]
+//! # mod example {
+//! use dynamodel::Dynamodel;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Document {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(json)]
+//!     metadata: HashMap<String, Vec<String>>,
+//! }
+//! # }
+//! ```
+//!
+//! ### Compressed attributes
+//!
+//! Add the `compress` attribute to a field to compress its `AttributeValue` into
+//! `AttributeValue::B` with a small header, for large attributes close to the item size limit. The
+//! algorithm is `zstd` or `gzip`, which require the features of the same names. Values without the
+//! header are read as they are, so existing items stay readable while you migrate them, and the
+//! [`compression`] module describes the header. With the `json` attribute, the JSON string is
+//! compressed. Compressing can fail, so the struct has `try_into_item` instead of
+//! `Into<HashMap<String, AttributeValue>>`, and it can't have the `by_ref` attribute.
+//! Reading a value that decompresses into more than [`compression::MAX_DECOMPRESSED_LEN`], 8 MiB, is an error.
+//!
+//! ```rust
+//! # #[cfg(feature = "zstd")]
+//! # mod example {
+//! use dynamodel::Dynamodel;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Document {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(compress = "zstd")]
+//!     body: String,
+//! }
+//! # }
+//! ```
+//!
+//! ### Encrypted attributes
+//!
+//! Add the `encrypt` attribute to a field to encrypt its `AttributeValue` into `AttributeValue::B`
+//! with AES-256-GCM on the client, and the `key_provider` attribute to the struct with a function
+//! returning a [`KeyProvider`](encryption::KeyProvider), like the local
+//! [`StaticKeyProvider`](encryption::StaticKeyProvider). The `sign` attribute with a [`KeySchema`]
+//! adds an HMAC signature of the key attributes and the encrypted ones to the item, so an encrypted
+//! value copied from another item is rejected. These conversions can fail, so the struct has
+//! `try_into_item` instead of `Into<HashMap<String, AttributeValue>>`, and a value that can't be
+//! decrypted or verified is a [`ConvertError::Decryption`]. It requires the `encryption` feature,
+//! and the [`encryption`] module describes the format.
+//!
+//! ```rust
+//! # #[cfg(feature = "encryption")]
+//! # mod example {
+//! use dynamodel::{Dynamodel, KeySchema, encryption::{DataKey, StaticKeyProvider}};
+//!
+//! const TABLE: KeySchema = KeySchema::new("PK");
+//!
+//! fn keys() -> StaticKeyProvider {
+//!     StaticKeyProvider::new(DataKey::new("2024-06", [7; 32]))
+//! }
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(key_provider = "keys", sign = "TABLE", rename_all = "PascalCase")]
+//! struct Customer {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(encrypt)]
+//!     email: String,
+//! }
+//! # }
+//! ```
+//!
+//! ## Cargo features
+//!
+//! | Feature | Description |
+//! |---|---|
+//! | `arbitrary_precision` | The `arbitrary_precision` feature of `serde_json`, so that [`plain_json`] keeps the digits of every number. It changes `serde_json` for the whole dependency graph. Enables `json`. |
+//! | `batch` | The [`batch`] module with `BatchWriteItem` and `BatchGetItem` helpers that chunk requests and retry unprocessed entries. |
+//! | `bigdecimal` | `AttributeValueConvertible` for `bigdecimal::BigDecimal`. |
+//! | `chrono` | [`Timestamp`] support for `chrono::DateTime<Utc>` and `chrono::NaiveDate`, and strftime patterns in the `format` attribute. |
+//! | `cursor` | The [`cursor`] module to turn a `LastEvaluatedKey` into an opaque pagination token and back. |
+//! | `encryption` | The [`encryption`] module with client-side encryption and signing for the `encrypt` field attribute. |
+//! | `json` | The [`dynamodb_json`] and [`plain_json`] modules to read and write items as DynamoDB JSON and plain JSON, and the `json` field attribute. |
+//! | `gzip` | Gzip for the `compress` field attribute and the [`compression`] module. |
+//! | `jiff` | [`Timestamp`] support for `jiff::Timestamp`. |
+//! | `rust_decimal` | `AttributeValueConvertible` for `rust_decimal::Decimal`. |
+//! | `serde` | The [`serde`](mod@serde) module to serialize any type into an `AttributeValue`, and [`Serde`] to use one as a field. |
+//! | `streams` | The [`stream`] module with `StreamRecord` to load the images of DynamoDB Streams and Lambda event records. |
+//! | `streams-sdk` | `StreamRecord` from the items of the `aws-sdk-dynamodbstreams` client. Enables `streams`. |
+//! | `table` | The [`table`] module with a `Table` trait over the item operations and its SDK implementation. |
+//! | `testing` | The [`testing`] module with `MemoryTable`, an in-memory `Table` for tests. Enables `table`. |
+//! | `time` | [`Timestamp`] support for `time::OffsetDateTime`. |
+//! | `transaction` | The [`transaction`] module with typed `TransactWriteItems` and `TransactGetItems` builders. |
+//! | `ulid` | `AttributeValueConvertible` for `ulid::Ulid`. |
+//! | `url` | `AttributeValueConvertible` for `url::Url`. |
+//! | `uuid` | `AttributeValueConvertible` for `uuid::Uuid`, and [`CompactUuid`] to store it as bytes. |
+//! | `zstd` | Zstandard for the `compress` field attribute and the [`compression`] module. |
+//!
 //! ## More features
 //!
 //! For more features, refer to [this wiki](https://github.com/kaicoh/dynamodel/wiki).
@@ -221,11 +549,70 @@
 /// For details, refer to [the wiki](https://github.com/kaicoh/dynamodel/wiki).
 pub use dynamodel_derive::Dynamodel;
 
+/// The AWS SDK for DynamoDB, re-exported so that the derived code and your code can use its types
+/// without depending on a matching version of it.
+pub use aws_sdk_dynamodb;
+
+#[cfg(feature = "batch")]
+pub mod batch;
+#[cfg(any(feature = "zstd", feature = "gzip"))]
+pub mod compression;
+#[cfg(feature = "cursor")]
+pub mod cursor;
+#[cfg(feature = "json")]
+pub mod dynamodb_json;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+mod expression;
+#[cfg(any(
+    feature = "bigdecimal",
+    feature = "rust_decimal",
+    feature = "ulid",
+    feature = "url",
+    feature = "uuid"
+))]
+mod external;
+mod item;
+#[cfg(feature = "json")]
+pub mod json_attribute;
+mod key;
+mod number;
+#[cfg(any(feature = "zstd", feature = "gzip", feature = "encryption"))]
+mod payload;
+#[cfg(feature = "json")]
+pub mod plain_json;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "streams")]
+pub mod stream;
+#[cfg(feature = "table")]
+pub mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timestamp;
+#[cfg(feature = "transaction")]
+pub mod transaction;
+mod version;
+
+pub use expression::Expression;
+#[cfg(feature = "uuid")]
+pub use external::CompactUuid;
+pub use item::ItemMap;
+pub use key::KeySchema;
+pub use number::Number;
+#[cfg(any(feature = "zstd", feature = "gzip", feature = "encryption"))]
+pub use payload::PayloadError;
+#[cfg(feature = "serde")]
+pub use serde::Serde;
+pub use timestamp::{TimeToLive, Timestamp, TimestampFormat, Timestamped};
+pub use version::Versioned;
+
 use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
 use std::num::{ParseFloatError, ParseIntError};
 use thiserror::Error;
 
-/// An error occurs when converting from a `HashMap<String, AttributeValue>` to your object.
+/// An error occurs when converting between a `HashMap<String, AttributeValue>` and your object.
 #[derive(Debug, Error)]
 pub enum ConvertError {
     /// There is no key-value pair for this field in the HashMap.
@@ -244,6 +631,41 @@ pub enum ConvertError {
     #[error("{0}")]
     ParseFloat(#[from] ParseFloatError),
 
+    /// The value in the HashMap is not a valid representation of the type, like a malformed timestamp.
+    #[error("cannot parse `{1}` as `{0}`")]
+    ParseValue(String, String),
+
+    /// The number in the HashMap is out of the range of the type.
+    #[error("`{1}` is out of the range of `{0}`")]
+    NumberOverflow(String, String),
+
+    /// The number can't be stored in DynamoDB, like `NaN`, an infinity, or a number with more than
+    /// 38 significant digits.
+    #[error("`{0}` is not a number DynamoDB can store")]
+    InvalidNumber(String),
+
+    /// The JSON string of a `json` field is invalid, or the value can't be serialized into JSON.
+    /// It has the name of the attribute and the error of `serde_json`.
+    #[error("cannot convert `{0}` attribute from or into JSON: {1}")]
+    Json(String, Box<dyn std::error::Error + Send + Sync>),
+
+    /// The value of a `compress` field can't be compressed or decompressed, like corrupted data.
+    /// It has the name of the attribute and the error of the `compression` module.
+    #[error("cannot compress or decompress `{0}` attribute: {1}")]
+    Compression(String, Box<dyn std::error::Error + Send + Sync>),
+
+    /// The value of an `encrypt` field can't be encrypted, or the item can't be signed, like when
+    /// the key provider fails. It has the name of the attribute and the error of the `encryption`
+    /// module.
+    #[error("cannot encrypt or sign `{0}` attribute: {1}")]
+    Encryption(String, Box<dyn std::error::Error + Send + Sync>),
+
+    /// The value of an `encrypt` field can't be decrypted, or the signature of the item doesn't
+    /// match, like with a wrong key or a modified value. It has the name of the attribute and the
+    /// error of the `encryption` module.
+    #[error("cannot decrypt or verify `{0}` attribute: {1}")]
+    Decryption(String, Box<dyn std::error::Error + Send + Sync>),
+
     /// There are no vairants for the enum in the HashMap.
     #[error("not found any variant in hashmap")]
     VariantNotFound,
@@ -263,8 +685,59 @@ fn unmatch_err(expected: &str) -> impl Fn(&AttributeValue) -> ConvertError + '_
 /// Types that implement this trait on objects with the [`Dynamodel`] macro can be
 /// implicitly converted from and into [`AttributeValue`].
 pub trait AttributeValueConvertible: Sized {
-    fn into_attribute_value(self) -> AttributeValue;
+    /// Whether converting into an `AttributeValue` can fail, so that the type must be converted
+    /// with [`try_into_attribute_value`](Self::try_into_attribute_value).
+    ///
+    /// Fallible types implement `try_into_attribute_value` instead of
+    /// [`into_attribute_value`](Self::into_attribute_value), so calling `into_attribute_value`
+    /// on them fails to compile, even through `Vec` or [`Expression::value`]. Objects with the
+    /// [`Dynamodel`] macro set this if they implement `TryFrom` instead of `From`, and the derived
+    /// `From` of an object with a field of such a type fails to compile too.
+    const FALLIBLE: bool = false;
+
+    /// Converts into an `AttributeValue`.
+    ///
+    /// Infallible types must implement this. The default implementation fails to compile when it
+    /// is used, because the type is fallible or doesn't implement it.
+    fn into_attribute_value(self) -> AttributeValue {
+        const {
+            assert!(
+                !Self::FALLIBLE,
+                "the type can fail to convert into an `AttributeValue`; use `try_into_attribute_value`"
+            );
+            panic!("infallible types must implement `into_attribute_value`")
+        }
+    }
+
     fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError>;
+
+    /// Converts into an `AttributeValue`, or returns an error if DynamoDB can't store the value,
+    /// like a `NaN` float.
+    ///
+    /// The default implementation always succeeds with [`into_attribute_value`](Self::into_attribute_value),
+    /// so fallible types must implement this.
+    fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+        Ok(self.into_attribute_value())
+    }
+
+    /// Converts from the map of an `AttributeValue::M`.
+    ///
+    /// Objects with the [`Dynamodel`] macro implement this without copying the map, and their
+    /// [`try_from_attribute_value`](Self::try_from_attribute_value) calls it. The default
+    /// implementation wraps a copy of the map in `AttributeValue::M`.
+    fn try_from_item(item: &HashMap<String, AttributeValue>) -> Result<Self, ConvertError> {
+        Self::try_from_attribute_value(&AttributeValue::M(item.clone()))
+    }
+
+    /// Converts from an owned `AttributeValue`, moving its strings and maps into `Self` where
+    /// possible.
+    ///
+    /// `TryFrom<HashMap<String, AttributeValue>>` of objects with the [`Dynamodel`] macro takes
+    /// each value out of the item and calls this. The default implementation calls
+    /// [`try_from_attribute_value`](Self::try_from_attribute_value).
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        Self::try_from_attribute_value(&value)
+    }
 }
 
 impl AttributeValueConvertible for String {
@@ -277,6 +750,12 @@ impl AttributeValueConvertible for String {
             .map(|v| v.to_string())
             .map_err(unmatch_err("S"))
     }
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        match value {
+            AttributeValue::S(v) => Ok(v),
+            value => Self::try_from_attribute_value(&value),
+        }
+    }
 }
 
 impl AttributeValueConvertible for bool {
@@ -288,8 +767,38 @@ impl AttributeValueConvertible for bool {
     }
 }
 
+impl AttributeValueConvertible for AttributeValue {
+    fn into_attribute_value(self) -> AttributeValue {
+        self
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        Ok(value.clone())
+    }
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        Ok(value)
+    }
+}
+
+// The numbers that DynamoDB may not store, like `NaN` or an integer with 39 digits, are fallible
+// and implement only `try_into_attribute_value`.
 macro_rules! impl_to_nums {
-    ($($ty:ty),*) => {
+    (fallible; $parse:expr; $($ty:ty),*) => {
+        $(
+            impl AttributeValueConvertible for $ty {
+                const FALLIBLE: bool = true;
+
+                fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+                    value.as_n()
+                        .map_err(unmatch_err("N"))
+                        .and_then(|v| $parse(v))
+                }
+                fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+                    number::canonical_number(&self.to_string()).map(AttributeValue::N)
+                }
+            }
+         )*
+    };
+    ($parse:expr; $($ty:ty),*) => {
         $(
             impl AttributeValueConvertible for $ty {
                 fn into_attribute_value(self) -> AttributeValue {
@@ -298,20 +807,40 @@ macro_rules! impl_to_nums {
                 fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
                     value.as_n()
                         .map_err(unmatch_err("N"))
-                        .and_then(|v| v.parse::<$ty>().map_err(|e| e.into()))
+                        .and_then(|v| $parse(v))
                 }
             }
          )*
-    }
+    };
+}
+
+impl_to_nums! {
+    number::parse_int;
+    u8, u16, u32, u64, usize,
+    i8, i16, i32, i64, isize
 }
 
 impl_to_nums! {
-    u8, u16, u32, u64, u128, usize,
-    i8, i16, i32, i64, i128, isize,
-    f32, f64
+    fallible;
+    number::parse_int;
+    u128, i128
+}
+
+impl_to_nums! {
+    fallible;
+    |v| number::parse_float(v, |v: &f32| v.is_finite());
+    f32
+}
+
+impl_to_nums! {
+    fallible;
+    |v| number::parse_float(v, |v: &f64| v.is_finite());
+    f64
 }
 
 impl<T: AttributeValueConvertible> AttributeValueConvertible for Vec<T> {
+    const FALLIBLE: bool = T::FALLIBLE;
+
     fn into_attribute_value(self) -> AttributeValue {
         AttributeValue::L(
             self.into_iter()
@@ -320,12 +849,111 @@ impl<T: AttributeValueConvertible> AttributeValueConvertible for Vec<T> {
         )
     }
     fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
-        let mut values: Vec<T> = vec![];
-        for v in value.as_l().map_err(unmatch_err("L"))?.iter() {
-            let v: T = AttributeValueConvertible::try_from_attribute_value(v)?;
-            values.push(v);
+        let values = value.as_l().map_err(unmatch_err("L"))?;
+        try_map_vec(
+            values.iter(),
+            AttributeValueConvertible::try_from_attribute_value,
+        )
+    }
+    fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+        try_map_vec(
+            self.into_iter(),
+            AttributeValueConvertible::try_into_attribute_value,
+        )
+        .map(AttributeValue::L)
+    }
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        match value {
+            AttributeValue::L(values) => try_map_vec(
+                values.into_iter(),
+                AttributeValueConvertible::try_from_attribute_value_owned,
+            ),
+            value => Self::try_from_attribute_value(&value),
         }
-        Ok(values)
+    }
+}
+
+// The derived infallible conversions call this instead of `into_attribute_value`, so that a field
+// of a fallible type is a compile error instead of a panic.
+#[doc(hidden)]
+pub fn into_infallible_attribute_value<T: AttributeValueConvertible>(value: T) -> AttributeValue {
+    const {
+        assert!(
+            !T::FALLIBLE,
+            "the field type can fail to convert into an `AttributeValue`; add `#[dynamodel(fallible)]` to the field"
+        )
+    };
+    value.into_attribute_value()
+}
+
+// Maps the values into a vector allocated once up front. Collecting into a `Result` can't use the
+// length of the source, so the vector would grow as it goes.
+fn try_map_vec<I, T, F>(values: I, mut f: F) -> Result<Vec<T>, ConvertError>
+where
+    I: ExactSizeIterator,
+    F: FnMut(I::Item) -> Result<T, ConvertError>,
+{
+    let mut mapped = Vec::with_capacity(values.len());
+    for value in values {
+        mapped.push(f(value)?);
+    }
+    Ok(mapped)
+}
+
+/// Converts a borrowed value into an [`AttributeValue`], leaving the value in place.
+///
+/// Objects with the `by_ref` container attribute of the [`Dynamodel`] macro implement this, and
+/// all of their fields must implement it too. The values of the other types are cloned into the
+/// `AttributeValue`. Fallible types, like floats, don't implement it.
+pub trait ToAttributeValue {
+    fn to_attribute_value(&self) -> AttributeValue;
+}
+
+macro_rules! impl_to_attribute_value {
+    ($($(#[$meta:meta])* $ty:ty),*) => {
+        $(
+            $(#[$meta])*
+            impl ToAttributeValue for $ty {
+                fn to_attribute_value(&self) -> AttributeValue {
+                    self.clone().into_attribute_value()
+                }
+            }
+        )*
+    };
+}
+
+impl_to_attribute_value! {
+    String, bool, AttributeValue, Number,
+    u8, u16, u32, u64, usize,
+    i8, i16, i32, i64, isize,
+    std::time::SystemTime,
+    #[cfg(feature = "chrono")]
+    chrono::DateTime<chrono::Utc>,
+    #[cfg(feature = "chrono")]
+    chrono::NaiveDate,
+    #[cfg(feature = "time")]
+    time::OffsetDateTime,
+    #[cfg(feature = "jiff")]
+    jiff::Timestamp,
+    #[cfg(feature = "rust_decimal")]
+    rust_decimal::Decimal,
+    #[cfg(feature = "ulid")]
+    ulid::Ulid,
+    #[cfg(feature = "url")]
+    url::Url,
+    #[cfg(feature = "uuid")]
+    uuid::Uuid,
+    #[cfg(feature = "uuid")]
+    CompactUuid
+}
+
+impl<T: ToAttributeValue> ToAttributeValue for Vec<T> {
+    fn to_attribute_value(&self) -> AttributeValue {
+        AttributeValue::L(
+            self.iter()
+                .map(ToAttributeValue::to_attribute_value)
+                .collect(),
+        )
     }
 }
 
@@ -350,6 +978,30 @@ mod tests {
         assert_eq!(result.unwrap(), "Hello".to_string());
     }
 
+    #[test]
+    fn owned_values_are_moved_out() {
+        let value = AttributeValue::S("Hello".into());
+        let result = String::try_from_attribute_value_owned(value);
+        assert_eq!(result.unwrap(), "Hello".to_string());
+
+        let result = String::try_from_attribute_value_owned(AttributeValue::Bool(true));
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "S"));
+
+        let value = AttributeValue::L(vec![
+            AttributeValue::S("Hello".into()),
+            AttributeValue::S("World".into()),
+        ]);
+        let result = Vec::<String>::try_from_attribute_value_owned(value);
+        assert_eq!(
+            result.unwrap(),
+            vec!["Hello".to_string(), "World".to_string()]
+        );
+
+        let value = AttributeValue::L(vec![AttributeValue::N("1".into())]);
+        let result = Vec::<String>::try_from_attribute_value_owned(value);
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "S"));
+    }
+
     #[test]
     fn boolean_can_be_converted_into_attribute_value() {
         let value = true;
@@ -376,6 +1028,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn string_vector_can_be_converted_by_reference() {
+        let value = vec!["Hello".to_string(), "World".to_string()];
+        assert_eq!(
+            value.to_attribute_value(),
+            AttributeValue::L(vec![
+                AttributeValue::S("Hello".into()),
+                AttributeValue::S("World".into())
+            ]),
+        );
+        assert_eq!(value.len(), 2);
+    }
+
     #[test]
     fn string_vector_can_be_converted_from_attribute_value() {
         let expected = vec!["Hello".to_string(), "World".to_string()];
@@ -412,14 +1077,19 @@ mod tests {
         assert_eq!(result.unwrap(), expected);
     }
 
+    // The fallible numbers are converted with `try_into_attribute_value`.
+    fn try_into<T: AttributeValueConvertible>(value: T) -> AttributeValue {
+        value.try_into_attribute_value().unwrap()
+    }
+
     macro_rules! test_int {
-        ($($ty:ty),*) => {
+        ([$into:path] $($ty:ty),*) => {
             $(
                 paste::item! {
                     #[test]
                     fn [<$ty _can_be_converted_into_attribute_value>]() {
                         let value: $ty = 10;
-                        assert_eq!(value.into_attribute_value(), AttributeValue::N("10".into()));
+                        assert_eq!($into(value), AttributeValue::N("10".into()));
                     }
 
                     #[test]
@@ -434,7 +1104,7 @@ mod tests {
                     fn [<$ty _vector_can_be_converted_into_attribute_value>]() {
                         let value: Vec<$ty> = vec![10, 20];
                         assert_eq!(
-                            value.into_attribute_value(),
+                            $into(value),
                             AttributeValue::L(vec![AttributeValue::N("10".into()), AttributeValue::N("20".into())]),
                         );
                     }
@@ -451,16 +1121,17 @@ mod tests {
         }
     }
 
-    test_int! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
+    test_int! { [AttributeValueConvertible::into_attribute_value] u8, u16, u32, u64, usize, i8, i16, i32, i64, isize }
+    test_int! { [try_into] u128, i128 }
 
     macro_rules! test_float {
-        ($($ty:ty),*) => {
+        ([$into:path] $($ty:ty),*) => {
             $(
                 paste::item! {
                     #[test]
                     fn [<$ty _can_be_converted_into_attribute_value>]() {
                         let value: $ty = 1.2;
-                        assert_eq!(value.into_attribute_value(), AttributeValue::N("1.2".into()));
+                        assert_eq!($into(value), AttributeValue::N("1.2".into()));
                     }
 
                     #[test]
@@ -475,7 +1146,7 @@ mod tests {
                     fn [<$ty _vector_can_be_converted_into_attribute_value>]() {
                         let value: Vec<$ty> = vec![1.2, 3.45];
                         assert_eq!(
-                            value.into_attribute_value(),
+                            $into(value),
                             AttributeValue::L(vec![AttributeValue::N("1.2".into()), AttributeValue::N("3.45".into())]),
                         );
                     }
@@ -492,5 +1163,5 @@ mod tests {
         }
     }
 
-    test_float! { f32, f64 }
+    test_float! { [try_into] f32, f64 }
 }
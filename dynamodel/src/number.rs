@@ -0,0 +1,404 @@
+use super::{AttributeValueConvertible, ConvertError, unmatch_err};
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::fmt;
+use std::num::{IntErrorKind, ParseFloatError, ParseIntError};
+use std::str::FromStr;
+
+// DynamoDB numbers have up to 38 significant digits, and their magnitude is from 1E-130
+// up to but not including 1E+126.
+const MAX_DIGITS: usize = 38;
+const MIN_EXP: i64 = -130;
+const MAX_EXP: i64 = 125;
+
+/// A DynamoDB number that keeps its original string.
+///
+/// `AttributeValue::N` has up to 38 digits of precision, which neither the integers nor the
+/// floats of Rust can always hold. This type stores the numeric string as it is, so the value
+/// round-trips exactly and can be parsed later into whatever type fits.
+///
+/// Two numbers are equal only if their strings are equal, so `1.0` and `1` are different.
+///
+/// ```rust
+/// use dynamodel::{AttributeValueConvertible, Number};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+///
+/// let value = AttributeValue::N("123456789012345678901234567890123456.78".into());
+/// let number = Number::try_from_attribute_value(&value).unwrap();
+/// assert_eq!(number.as_str(), "123456789012345678901234567890123456.78");
+/// assert_eq!(number.into_attribute_value(), value);
+///
+/// let number: Number = "42".parse().unwrap();
+/// assert_eq!(number.parse::<u8>().unwrap(), 42);
+///
+/// assert!("1.2.3".parse::<Number>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Number(String);
+
+impl Number {
+    /// The numeric string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Parses the number into another type, like an integer or a decimal.
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.0.parse()
+    }
+}
+
+// Whether the string is a number: an optional sign, digits with an optional decimal point,
+// and an optional exponent.
+fn is_number(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e.strip_prefix(['+', '-']).unwrap_or(e))),
+        None => (s, None),
+    };
+    let (int, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+    !(int.is_empty() && fraction.is_empty())
+        && digits(int)
+        && digits(fraction)
+        && exponent.is_none_or(|e| !e.is_empty() && digits(e))
+}
+
+// A number split into its significant digits and the position of the decimal point.
+// The value is `0.{digits} * 10^point`, and the digits have no leading or trailing zeros.
+struct Parts {
+    negative: bool,
+    digits: String,
+    point: i64,
+}
+
+impl Parts {
+    fn parse(s: &str) -> Option<Self> {
+        if !is_number(s) {
+            return None;
+        }
+
+        let negative = s.starts_with('-');
+        let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+        let (mantissa, exp) = match unsigned.split_once(['e', 'E']) {
+            Some((m, e)) => (m, e.parse::<i64>().ok()?),
+            None => (unsigned, 0),
+        };
+        let (int, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        let all = format!("{int}{fraction}");
+        let digits = all.trim_start_matches('0');
+        let leading_zeros = (all.len() - digits.len()) as i64;
+        let digits = digits.trim_end_matches('0');
+
+        Some(Self {
+            negative: negative && !digits.is_empty(),
+            digits: digits.into(),
+            point: (int.len() as i64)
+                .checked_add(exp)?
+                .checked_sub(leading_zeros)?,
+        })
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // Whether DynamoDB can store the number.
+    fn is_storable(&self) -> bool {
+        self.is_zero()
+            || (self.digits.len() <= MAX_DIGITS
+                && self
+                    .point
+                    .checked_sub(1)
+                    .is_some_and(|exp| (MIN_EXP..=MAX_EXP).contains(&exp)))
+    }
+
+    // Whether the number has no fractional part.
+    fn is_integral(&self) -> bool {
+        self.point >= self.digits.len() as i64
+    }
+
+    // The number in plain notation without an exponent, like `-0.0015` or `1200`.
+    fn to_plain(&self) -> String {
+        if self.is_zero() {
+            return "0".into();
+        }
+
+        let sign = if self.negative { "-" } else { "" };
+        let len = self.digits.len() as i64;
+        let digits = self.digits.as_str();
+
+        if self.point <= 0 {
+            let zeros = "0".repeat(self.point.unsigned_abs() as usize);
+            format!("{sign}0.{zeros}{digits}")
+        } else if self.point >= len {
+            let zeros = "0".repeat((self.point - len) as usize);
+            format!("{sign}{digits}{zeros}")
+        } else {
+            let (int, fraction) = digits.split_at(self.point as usize);
+            format!("{sign}{int}.{fraction}")
+        }
+    }
+}
+
+/// Checks that DynamoDB can store the number and returns it in the canonical form:
+/// plain notation without an exponent, a plus sign, or leading and trailing zeros.
+pub(crate) fn canonical_number(s: &str) -> Result<String, ConvertError> {
+    Parts::parse(s)
+        .filter(Parts::is_storable)
+        .map(|parts| parts.to_plain())
+        .ok_or_else(|| ConvertError::InvalidNumber(s.into()))
+}
+
+fn overflow_err<T>(s: &str) -> ConvertError {
+    ConvertError::NumberOverflow(std::any::type_name::<T>().into(), s.into())
+}
+
+/// Parses an integer, accepting exponent forms like `1E+2` if the value is integral.
+pub(crate) fn parse_int<T>(s: &str) -> Result<T, ConvertError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let err = match s.parse::<T>() {
+        Ok(v) => return Ok(v),
+        Err(e) => e,
+    };
+    if matches!(
+        err.kind(),
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+    ) {
+        return Err(overflow_err::<T>(s));
+    }
+
+    let Some(parts) = Parts::parse(s) else {
+        return Err(err.into());
+    };
+    if !parts.is_integral() {
+        return Err(ConvertError::ParseValue(
+            std::any::type_name::<T>().into(),
+            s.into(),
+        ));
+    }
+    // No integer type has more than 39 digits.
+    if parts.point > 40 {
+        return Err(overflow_err::<T>(s));
+    }
+    parts.to_plain().parse().map_err(|_| overflow_err::<T>(s))
+}
+
+/// Parses a float, reporting numbers too large for the type as an overflow instead of an infinity.
+pub(crate) fn parse_float<T>(s: &str, is_finite: impl Fn(&T) -> bool) -> Result<T, ConvertError>
+where
+    T: FromStr<Err = ParseFloatError>,
+{
+    let v = s.parse::<T>()?;
+    if !is_number(s) {
+        // Like `NaN` or `inf`, which aren't DynamoDB numbers.
+        Err(ConvertError::ParseValue(
+            std::any::type_name::<T>().into(),
+            s.into(),
+        ))
+    } else if !is_finite(&v) {
+        Err(overflow_err::<T>(s))
+    } else {
+        Ok(v)
+    }
+}
+
+impl FromStr for Number {
+    type Err = ConvertError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_number(s) {
+            Ok(Self(s.into()))
+        } else {
+            Err(ConvertError::ParseValue(
+                std::any::type_name::<Self>().into(),
+                s.into(),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Number> for String {
+    fn from(number: Number) -> Self {
+        number.0
+    }
+}
+
+macro_rules! impl_from_nums {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(v: $ty) -> Self {
+                    Self(v.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_nums! {
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize
+}
+
+impl AttributeValueConvertible for Number {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::N(self.0)
+    }
+    fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+        canonical_number(&self.0)?;
+        Ok(self.into_attribute_value())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        value.as_n().map_err(unmatch_err("N"))?.parse()
+    }
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        match value {
+            AttributeValue::N(v) if is_number(&v) => Ok(Self(v)),
+            value => Self::try_from_attribute_value(&value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_strings_are_numbers() {
+        for valid in [
+            "0", "-1", "+1", "1.", ".5", "1.5e10", "1E+2", "-1.5e-3", "007",
+        ] {
+            assert!(is_number(valid), "{valid} should be a number");
+        }
+
+        for invalid in [
+            "", "-", ".", "1.2.3", "1e", "e5", "1e+", "0x10", "NaN", "1 ",
+        ] {
+            assert!(!is_number(invalid), "{invalid} should not be a number");
+        }
+    }
+
+    #[test]
+    fn numbers_are_written_in_the_canonical_form() {
+        let cases = [
+            ("0", "0"),
+            ("-0.000", "0"),
+            ("+007.50", "7.5"),
+            ("1E+2", "100"),
+            ("1.5e-3", "0.0015"),
+            ("-12.345E1", "-123.45"),
+            ("0.00012e4", "1.2"),
+            ("1E+125", &format!("1{}", "0".repeat(125))),
+            ("1E-130", &format!("0.{}1", "0".repeat(129))),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(canonical_number(s).unwrap(), expected, "{s}");
+        }
+
+        for invalid in [
+            "NaN",
+            "inf",
+            "1E+126",
+            "1E-131",
+            &"1".repeat(39),
+            "1e99999999999999999999",
+            "0.0001e-9223372036854775808",
+            ".5e-9223372036854775808",
+        ] {
+            assert!(
+                matches!(canonical_number(invalid), Err(ConvertError::InvalidNumber(v)) if v == invalid)
+            );
+        }
+        assert_eq!(canonical_number(&"1".repeat(38)).unwrap(), "1".repeat(38));
+    }
+
+    #[test]
+    fn integers_are_parsed_from_exponent_forms() {
+        assert_eq!(parse_int::<u8>("1E+2").unwrap(), 100);
+        assert_eq!(parse_int::<i64>("-1.5e1").unwrap(), -15);
+        assert_eq!(parse_int::<i32>("0e5").unwrap(), 0);
+
+        let result = parse_int::<u8>("1E+3");
+        assert!(
+            matches!(result, Err(ConvertError::NumberOverflow(t, v)) if t == "u8" && v == "1E+3")
+        );
+        let result = parse_int::<u8>("256");
+        assert!(matches!(result, Err(ConvertError::NumberOverflow(t, _)) if t == "u8"));
+        let result = parse_int::<u32>("-1");
+        assert!(matches!(result, Err(ConvertError::NumberOverflow(t, _)) if t == "u32"));
+        let result = parse_int::<i128>("1E+100");
+        assert!(matches!(result, Err(ConvertError::NumberOverflow(t, _)) if t == "i128"));
+
+        let result = parse_int::<u8>("1.5");
+        assert!(matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "u8" && v == "1.5"));
+        let result = parse_int::<u8>("foo");
+        assert!(matches!(result, Err(ConvertError::ParseInt(_))));
+    }
+
+    #[test]
+    fn floats_too_large_for_the_type_are_overflows() {
+        assert_eq!(
+            parse_float::<f32>("1E+2", |v| v.is_finite()).unwrap(),
+            100.0
+        );
+
+        let result = parse_float::<f32>("1E+39", |v| v.is_finite());
+        assert!(matches!(result, Err(ConvertError::NumberOverflow(t, _)) if t == "f32"));
+
+        let result = parse_float::<f64>("NaN", |v| v.is_finite());
+        assert!(matches!(result, Err(ConvertError::ParseValue(t, _)) if t == "f64"));
+        let result = parse_float::<f64>("foo", |v| v.is_finite());
+        assert!(matches!(result, Err(ConvertError::ParseFloat(_))));
+    }
+
+    #[test]
+    fn numbers_keep_the_original_string() {
+        let value = AttributeValue::N("1.50".into());
+        let number = Number::try_from_attribute_value(&value).unwrap();
+        assert_eq!(number.to_string(), "1.50");
+        assert_ne!(number, "1.5".parse().unwrap());
+        assert_eq!(number.into_attribute_value(), value);
+
+        let big = format!("1{}", "0".repeat(40));
+        let number: Number = big.parse().unwrap();
+        assert!(number.parse::<i128>().is_err());
+        assert_eq!(String::from(number), big);
+
+        assert_eq!(Number::from(-3i8).as_str(), "-3");
+
+        let number: Number = "1E+2".parse().unwrap();
+        assert_eq!(
+            number.try_into_attribute_value().unwrap(),
+            AttributeValue::N("1E+2".into())
+        );
+        let number: Number = "1E+200".parse().unwrap();
+        assert!(number.try_into_attribute_value().is_err());
+    }
+
+    #[test]
+    fn invalid_numbers_are_errors() {
+        let result = Number::try_from_attribute_value(&AttributeValue::S("1".into()));
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "N"));
+
+        let result = Number::try_from_attribute_value(&AttributeValue::N("one".into()));
+        assert!(
+            matches!(result, Err(ConvertError::ParseValue(t, v)) if t == "dynamodel::number::Number" && v == "one")
+        );
+
+        let result = Number::try_from_attribute_value_owned(AttributeValue::N("one".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "one"));
+
+        let result = Number::try_from_attribute_value_owned(AttributeValue::N("1.50".into()));
+        assert_eq!(result.unwrap().as_str(), "1.50");
+    }
+}
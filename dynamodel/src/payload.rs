@@ -0,0 +1,446 @@
+// The bytes of an attribute value that the `compression` and `encryption` modules transform.
+//
+// Strings and bytes are stored as they are, and any other value as its DynamoDB JSON. The kind is
+// a byte in the headers of both modules. The JSON is written and read here instead of with
+// `serde_json`, so these modules don't need the `json` feature.
+
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::string::FromUtf8Error;
+use thiserror::Error;
+
+// DynamoDB nests lists and maps up to 32 levels, so deeper JSON is never written by this module.
+const MAX_DEPTH: usize = 32;
+
+/// An error occurs when writing a compressed or encrypted value as DynamoDB JSON or reading it
+/// back.
+#[derive(Debug, Error)]
+pub enum PayloadError {
+    /// The value holds an `AttributeValue` variant this crate doesn't know.
+    #[error("cannot write `{0:?}` as DynamoDB JSON")]
+    UnsupportedValue(AttributeValue),
+
+    /// The bytes are not the DynamoDB JSON of an attribute value.
+    #[error("invalid DynamoDB JSON at byte {position}: {reason}")]
+    Malformed { position: usize, reason: String },
+}
+
+pub(crate) fn to_bytes(value: AttributeValue) -> Result<(u8, Vec<u8>), PayloadError> {
+    match value {
+        AttributeValue::S(v) => Ok((b'S', v.into_bytes())),
+        AttributeValue::B(v) => Ok((b'B', v.into_inner())),
+        value => {
+            let mut json = String::new();
+            write_attribute(&mut json, &value)?;
+            Ok((b'J', json.into_bytes()))
+        }
+    }
+}
+
+// Returns `None` if the kind is unknown.
+pub(crate) fn from_bytes<E>(kind: u8, bytes: Vec<u8>) -> Result<Option<AttributeValue>, E>
+where
+    E: From<FromUtf8Error> + From<PayloadError>,
+{
+    match kind {
+        b'S' => Ok(Some(AttributeValue::S(String::from_utf8(bytes)?))),
+        b'B' => Ok(Some(AttributeValue::B(Blob::new(bytes)))),
+        b'J' => {
+            let json = String::from_utf8(bytes)?;
+            let mut parser = Parser {
+                json: &json,
+                position: 0,
+            };
+            let value = parser.attribute(0)?;
+            parser.end()?;
+            Ok(Some(value))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn write_attribute(out: &mut String, value: &AttributeValue) -> Result<(), PayloadError> {
+    let key = match value {
+        AttributeValue::S(_) => "S",
+        AttributeValue::N(_) => "N",
+        AttributeValue::B(_) => "B",
+        AttributeValue::Bool(_) => "BOOL",
+        AttributeValue::Null(_) => "NULL",
+        AttributeValue::Ss(_) => "SS",
+        AttributeValue::Ns(_) => "NS",
+        AttributeValue::Bs(_) => "BS",
+        AttributeValue::L(_) => "L",
+        AttributeValue::M(_) => "M",
+        _ => return Err(PayloadError::UnsupportedValue(value.clone())),
+    };
+    out.push_str("{\"");
+    out.push_str(key);
+    out.push_str("\":");
+
+    match value {
+        AttributeValue::S(v) | AttributeValue::N(v) => write_string(out, v),
+        AttributeValue::B(v) => write_string(out, &STANDARD.encode(v.as_ref())),
+        AttributeValue::Bool(v) | AttributeValue::Null(v) => out.push_str(&v.to_string()),
+        AttributeValue::Ss(v) | AttributeValue::Ns(v) => write_array(out, v, |out, v| {
+            write_string(out, v);
+            Ok(())
+        })?,
+        AttributeValue::Bs(v) => write_array(out, v, |out, v| {
+            write_string(out, &STANDARD.encode(v.as_ref()));
+            Ok(())
+        })?,
+        AttributeValue::L(v) => write_array(out, v, write_attribute)?,
+        AttributeValue::M(v) => write_map(out, v)?,
+        _ => unreachable!("unsupported values are returned above"),
+    }
+
+    out.push('}');
+    Ok(())
+}
+
+fn write_array<T>(
+    out: &mut String,
+    values: &[T],
+    mut write: impl FnMut(&mut String, &T) -> Result<(), PayloadError>,
+) -> Result<(), PayloadError> {
+    out.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write(out, value)?;
+    }
+    out.push(']');
+    Ok(())
+}
+
+// The names are sorted, so the same map is always the same bytes.
+fn write_map(out: &mut String, map: &HashMap<String, AttributeValue>) -> Result<(), PayloadError> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by_key(|(name, _)| *name);
+
+    out.push('{');
+    for (i, (name, value)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(out, name);
+        out.push(':');
+        write_attribute(out, value)?;
+    }
+    out.push('}');
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32).expect("writing into a string"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Reads the DynamoDB JSON of an attribute value: objects, arrays, strings and booleans.
+struct Parser<'a> {
+    json: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn malformed(&self, reason: impl Into<String>) -> PayloadError {
+        PayloadError::Malformed {
+            position: self.position,
+            reason: reason.into(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.json[self.position..];
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.json.as_bytes().get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), PayloadError> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.malformed(format!("expected `{}`", byte as char)))
+        }
+    }
+
+    // Whether the next byte is `byte`, consuming it if so.
+    fn next_is(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn end(&mut self) -> Result<(), PayloadError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.malformed("trailing characters")),
+        }
+    }
+
+    fn attribute(&mut self, depth: usize) -> Result<AttributeValue, PayloadError> {
+        if depth > MAX_DEPTH {
+            return Err(self.malformed(format!("nested deeper than {MAX_DEPTH} levels")));
+        }
+
+        self.expect(b'{')?;
+        let key = self.string()?;
+        self.expect(b':')?;
+
+        let value = match key.as_str() {
+            "S" => AttributeValue::S(self.string()?),
+            "N" => AttributeValue::N(self.string()?),
+            "B" => AttributeValue::B(self.binary()?),
+            "BOOL" => AttributeValue::Bool(self.boolean()?),
+            "NULL" => AttributeValue::Null(self.boolean()?),
+            "SS" => AttributeValue::Ss(self.array(Self::string)?),
+            "NS" => AttributeValue::Ns(self.array(Self::string)?),
+            "BS" => AttributeValue::Bs(self.array(Self::binary)?),
+            "L" => AttributeValue::L(self.array(|p| p.attribute(depth + 1))?),
+            "M" => AttributeValue::M(self.map(depth + 1)?),
+            key => return Err(self.malformed(format!("unknown type `{key}`"))),
+        };
+
+        self.expect(b'}')?;
+        Ok(value)
+    }
+
+    fn array<T>(
+        &mut self,
+        mut member: impl FnMut(&mut Self) -> Result<T, PayloadError>,
+    ) -> Result<Vec<T>, PayloadError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.next_is(b']') {
+            return Ok(values);
+        }
+        loop {
+            values.push(member(self)?);
+            if self.next_is(b']') {
+                return Ok(values);
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn map(&mut self, depth: usize) -> Result<HashMap<String, AttributeValue>, PayloadError> {
+        self.expect(b'{')?;
+        let mut map = HashMap::new();
+        if self.next_is(b'}') {
+            return Ok(map);
+        }
+        loop {
+            let name = self.string()?;
+            self.expect(b':')?;
+            map.insert(name, self.attribute(depth)?);
+            if self.next_is(b'}') {
+                return Ok(map);
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn boolean(&mut self) -> Result<bool, PayloadError> {
+        self.skip_whitespace();
+        let rest = &self.json[self.position..];
+        for (word, value) in [("true", true), ("false", false)] {
+            if rest.starts_with(word) {
+                self.position += word.len();
+                return Ok(value);
+            }
+        }
+        Err(self.malformed("expected a boolean"))
+    }
+
+    fn binary(&mut self) -> Result<Blob, PayloadError> {
+        let position = self.position;
+        let s = self.string()?;
+        STANDARD.decode(s).map(Blob::new).map_err(|e| {
+            self.position = position;
+            self.malformed(format!("invalid base64: {e}"))
+        })
+    }
+
+    fn string(&mut self) -> Result<String, PayloadError> {
+        self.expect(b'"')?;
+        let mut s = String::new();
+        loop {
+            let rest = &self.json[self.position..];
+            let Some(end) = rest.find(['"', '\\']) else {
+                return Err(self.malformed("unterminated string"));
+            };
+            s.push_str(&rest[..end]);
+            self.position += end + 1;
+
+            if rest.as_bytes()[end] == b'"' {
+                return Ok(s);
+            }
+            let escape = self.json.as_bytes().get(self.position).copied();
+            self.position += 1;
+            let escaped = match escape {
+                Some(b'"') => '"',
+                Some(b'\\') => '\\',
+                Some(b'/') => '/',
+                Some(b'b') => '\u{8}',
+                Some(b'f') => '\u{c}',
+                Some(b'n') => '\n',
+                Some(b'r') => '\r',
+                Some(b't') => '\t',
+                Some(b'u') => self.unicode_escape()?,
+                _ => return Err(self.malformed("invalid escape")),
+            };
+            s.push(escaped);
+        }
+    }
+
+    // Reads the hex digits after `\u`, with the low surrogate of a pair.
+    fn unicode_escape(&mut self) -> Result<char, PayloadError> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.json[self.position..].starts_with("\\u") {
+                return Err(self.malformed("unpaired surrogate"));
+            }
+            self.position += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.malformed("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.malformed("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, PayloadError> {
+        let digits = self
+            .json
+            .get(self.position..self.position + 4)
+            .filter(|s| s.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.malformed("invalid unicode escape"))?;
+        self.position += 4;
+        Ok(u32::from_str_radix(digits, 16).expect("hex digits"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Error = Box<dyn std::error::Error>;
+
+    fn round_trip(value: AttributeValue) -> AttributeValue {
+        let (kind, bytes) = to_bytes(value).unwrap();
+        from_bytes::<Error>(kind, bytes).unwrap().unwrap()
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let values = [
+            AttributeValue::N("-1.5E+3".into()),
+            AttributeValue::Bool(false),
+            AttributeValue::Null(true),
+            AttributeValue::Ss(vec!["a".into(), "b".into()]),
+            AttributeValue::Ns(vec![]),
+            AttributeValue::Bs(vec![Blob::new(vec![0, 255])]),
+            AttributeValue::L(vec![
+                AttributeValue::S("quote \" backslash \\ tab \t bell \u{7} é 🦀".into()),
+                AttributeValue::B(Blob::new(b"bytes".to_vec())),
+                AttributeValue::L(vec![]),
+            ]),
+            AttributeValue::M(
+                [
+                    ("b".to_string(), AttributeValue::M(HashMap::new())),
+                    ("a\n".to_string(), AttributeValue::Bool(true)),
+                ]
+                .into(),
+            ),
+        ];
+        for value in values {
+            assert_eq!(round_trip(value.clone()), value);
+        }
+    }
+
+    #[test]
+    fn maps_are_written_with_sorted_names() {
+        let value = AttributeValue::M(
+            [
+                ("b".to_string(), AttributeValue::N("1".into())),
+                ("a".to_string(), AttributeValue::Null(true)),
+            ]
+            .into(),
+        );
+        let (kind, bytes) = to_bytes(value).unwrap();
+        assert_eq!(kind, b'J');
+        assert_eq!(bytes, br#"{"M":{"a":{"NULL":true},"b":{"N":"1"}}}"#);
+    }
+
+    #[test]
+    fn json_with_whitespace_and_escapes_is_read() {
+        let json = r#" { "L" : [ {"S":"é🦀\/"} , {"BOOL":true} ] } "#;
+        let value = from_bytes::<Error>(b'J', json.into()).unwrap();
+        assert_eq!(
+            value,
+            Some(AttributeValue::L(vec![
+                AttributeValue::S("é🦀/".into()),
+                AttributeValue::Bool(true),
+            ]))
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        let deep = format!(
+            "{}{{\"NULL\":true}}{}",
+            r#"{"L":["#.repeat(40),
+            "]}".repeat(40)
+        );
+        for json in [
+            "",
+            "[]",
+            r#"{"N":1}"#,
+            r#"{"X":"1"}"#,
+            r#"{"S":"a"} x"#,
+            r#"{"S":"a"#,
+            r#"{"S":"\x"}"#,
+            r#"{"S":"\ud83e"}"#,
+            r#"{"B":"!"}"#,
+            r#"{"BOOL":yes}"#,
+            r#"{"L":[{"S":"a"},]}"#,
+            deep.as_str(),
+        ] {
+            let result = from_bytes::<Error>(b'J', json.into());
+            let err = result.unwrap_err();
+            assert!(
+                matches!(err.downcast_ref(), Some(PayloadError::Malformed { .. })),
+                "{json}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_kinds_are_none() {
+        assert!(matches!(from_bytes::<Error>(b'X', vec![]), Ok(None)));
+    }
+}
@@ -0,0 +1,368 @@
+//! Plain JSON, the untyped JSON view of items.
+//!
+//! APIs and admin tools usually want an item like `{"name": "Kanji", "age": 23}` rather than
+//! its DynamoDB JSON, like `unmarshall` of the JavaScript SDK gives. [`PlainJsonCodec`] converts
+//! attribute values from and into [`serde_json::Value`], and every object with the
+//! [`Dynamodel`](crate::Dynamodel) macro gets `to_plain_json` and `from_plain_json` from the
+//! [`PlainJson`] trait.
+//!
+//! ```rust
+//! use dynamodel::{Dynamodel, plain_json::PlainJson};
+//! use serde_json::json;
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct Person {
+//!     name: String,
+//!     age: u8,
+//! }
+//!
+//! let person = Person { name: "Kanji".into(), age: 23 };
+//! assert_eq!(person.to_plain_json().unwrap(), json!({ "name": "Kanji", "age": 23 }));
+//!
+//! let person = Person::from_plain_json(json!({ "name": "Kanji", "age": 23 })).unwrap();
+//! assert_eq!(person, Person { name: "Kanji".into(), age: 23 });
+//! ```
+//!
+//! | `AttributeValue` | JSON |
+//! |---|---|
+//! | `S` | string |
+//! | `N` | number, with the digits of the `N` string as they are |
+//! | `Bool` | boolean |
+//! | `Null` | `null` |
+//! | `B` | base64 string |
+//! | `L` | array |
+//! | `M` | object |
+//! | `Ss`, `Ns`, `Bs` | array, or an error, as the [`SetPolicy`] says |
+//!
+//! JSON has no binary or set types, so strings are always read as `S` and arrays as `L`.
+//!
+//! A `Value` holds a number as an `f64` or a 64-bit integer, so an `N` value that would be rounded,
+//! like one with 20 significant digits, is [`PlainJsonError::InexactNumber`]. The
+//! `arbitrary_precision` feature enables the feature of `serde_json` with the same name, so that
+//! numbers keep their digits in a `Value`. It changes `serde_json` for every crate that uses it,
+//! so it is not enabled by `json`.
+
+use super::ConvertError;
+use super::number::canonical_number;
+use aws_sdk_dynamodb::types::AttributeValue;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An error occurs when converting from or into plain JSON.
+#[derive(Debug, Error)]
+pub enum PlainJsonError {
+    /// Items are JSON objects, but the value is not.
+    #[error("expect a JSON object, but got `{0}`")]
+    NotAnObject(Value),
+
+    /// The `N` value is not a number JSON can hold.
+    #[error("`{0}` is not a JSON number")]
+    InvalidNumber(String),
+
+    /// The `N` value would be rounded in a JSON number without the `arbitrary_precision` feature.
+    #[error("`{0}` can't be held exactly without the `arbitrary_precision` feature")]
+    InexactNumber(String),
+
+    /// The value is a set and the [`SetPolicy`] is `Reject`.
+    #[error("sets are not allowed in plain JSON")]
+    SetNotAllowed,
+
+    /// The item holds an `AttributeValue` variant this module doesn't know.
+    #[error("cannot convert `{0:?}` into plain JSON")]
+    UnsupportedValue(AttributeValue),
+
+    /// The item can't be converted into the object.
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+}
+
+impl From<Infallible> for PlainJsonError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+/// How sets (`Ss`, `Ns` and `Bs`) are written in plain JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SetPolicy {
+    /// Arrays in the order of the set.
+    #[default]
+    Array,
+    /// Arrays sorted by the JSON text of their members, so the same set always gives the same JSON.
+    SortedArray,
+    /// Sets are [`PlainJsonError::SetNotAllowed`].
+    Reject,
+}
+
+/// Converts attribute values from and into plain JSON.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainJsonCodec {
+    set_policy: SetPolicy,
+}
+
+impl PlainJsonCodec {
+    /// Creates a codec that writes sets as arrays.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes sets as this policy says.
+    pub fn with_set_policy(self, set_policy: SetPolicy) -> Self {
+        Self { set_policy }
+    }
+
+    /// Converts an attribute value into a JSON value.
+    pub fn to_value(&self, value: &AttributeValue) -> Result<Value, PlainJsonError> {
+        match value {
+            AttributeValue::S(v) => Ok(Value::String(v.clone())),
+            AttributeValue::N(v) => number(v).map(Value::Number),
+            AttributeValue::Bool(v) => Ok(Value::Bool(*v)),
+            AttributeValue::Null(_) => Ok(Value::Null),
+            AttributeValue::B(v) => Ok(Value::String(STANDARD.encode(v.as_ref()))),
+            AttributeValue::L(v) => v
+                .iter()
+                .map(|v| self.to_value(v))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            AttributeValue::M(v) => self.item_to_value(v),
+            AttributeValue::Ss(v) => self.set(v.iter().cloned().map(Value::String).collect()),
+            AttributeValue::Ns(v) => {
+                let members = v.iter().map(|n| number(n).map(Value::Number));
+                self.set(members.collect::<Result<_, _>>()?)
+            }
+            AttributeValue::Bs(v) => self.set(
+                v.iter()
+                    .map(|b| Value::String(STANDARD.encode(b.as_ref())))
+                    .collect(),
+            ),
+            _ => Err(PlainJsonError::UnsupportedValue(value.clone())),
+        }
+    }
+
+    /// Converts an item into a JSON object.
+    pub fn item_to_value(
+        &self,
+        item: &HashMap<String, AttributeValue>,
+    ) -> Result<Value, PlainJsonError> {
+        item.iter()
+            .map(|(name, value)| Ok((name.clone(), self.to_value(value)?)))
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object)
+    }
+
+    /// Converts a JSON value into an attribute value.
+    pub fn from_value(&self, value: Value) -> AttributeValue {
+        match value {
+            Value::String(v) => AttributeValue::S(v),
+            Value::Number(v) => AttributeValue::N(v.to_string()),
+            Value::Bool(v) => AttributeValue::Bool(v),
+            Value::Null => AttributeValue::Null(true),
+            Value::Array(v) => {
+                AttributeValue::L(v.into_iter().map(|v| self.from_value(v)).collect())
+            }
+            Value::Object(v) => AttributeValue::M(self.map_from_value(v)),
+        }
+    }
+
+    /// Converts a JSON object into an item.
+    pub fn item_from_value(
+        &self,
+        value: Value,
+    ) -> Result<HashMap<String, AttributeValue>, PlainJsonError> {
+        match value {
+            Value::Object(map) => Ok(self.map_from_value(map)),
+            value => Err(PlainJsonError::NotAnObject(value)),
+        }
+    }
+
+    fn map_from_value(&self, map: Map<String, Value>) -> HashMap<String, AttributeValue> {
+        map.into_iter()
+            .map(|(name, value)| (name, self.from_value(value)))
+            .collect()
+    }
+
+    fn set(&self, mut members: Vec<Value>) -> Result<Value, PlainJsonError> {
+        match self.set_policy {
+            SetPolicy::Array => {}
+            SetPolicy::SortedArray => members.sort_by_cached_key(Value::to_string),
+            SetPolicy::Reject => return Err(PlainJsonError::SetNotAllowed),
+        }
+        Ok(Value::Array(members))
+    }
+}
+
+fn number(value: &str) -> Result<Number, PlainJsonError> {
+    let number =
+        Number::from_str(value).map_err(|_| PlainJsonError::InvalidNumber(value.into()))?;
+    if cfg!(feature = "arbitrary_precision") {
+        return Ok(number);
+    }
+    // Without `arbitrary_precision`, the number is an `f64` or an integer that may be rounded.
+    let exact = canonical_number(value)
+        .ok()
+        .is_some_and(|v| canonical_number(&number.to_string()).is_ok_and(|n| n == v));
+    if exact {
+        Ok(number)
+    } else {
+        Err(PlainJsonError::InexactNumber(value.into()))
+    }
+}
+
+/// Conversions of items and objects with the [`Dynamodel`](crate::Dynamodel) macro from and
+/// into plain JSON with the default [`PlainJsonCodec`].
+pub trait PlainJson: Sized {
+    fn to_plain_json(self) -> Result<Value, PlainJsonError>;
+
+    fn from_plain_json(value: Value) -> Result<Self, PlainJsonError>;
+}
+
+impl<T> PlainJson for T
+where
+    T: Into<HashMap<String, AttributeValue>> + TryFrom<HashMap<String, AttributeValue>>,
+    PlainJsonError: From<T::Error>,
+{
+    fn to_plain_json(self) -> Result<Value, PlainJsonError> {
+        PlainJsonCodec::new().item_to_value(&self.into())
+    }
+
+    fn from_plain_json(value: Value) -> Result<Self, PlainJsonError> {
+        let item = PlainJsonCodec::new().item_from_value(value)?;
+        Ok(T::try_from(item)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_dynamodb::primitives::Blob;
+    use serde_json::json;
+
+    #[test]
+    fn values_are_converted_into_json() {
+        let item: HashMap<String, AttributeValue> = [
+            ("s".to_string(), AttributeValue::S("text".into())),
+            ("n".to_string(), AttributeValue::N("1.50".into())),
+            ("bool".to_string(), AttributeValue::Bool(true)),
+            ("null".to_string(), AttributeValue::Null(true)),
+            (
+                "b".to_string(),
+                AttributeValue::B(Blob::new(b"bytes".to_vec())),
+            ),
+            (
+                "l".to_string(),
+                AttributeValue::L(vec![AttributeValue::M(
+                    [("k".to_string(), AttributeValue::N("-2".into()))].into(),
+                )]),
+            ),
+        ]
+        .into();
+
+        let value = PlainJsonCodec::new().item_to_value(&item).unwrap();
+        let expected: Value = serde_json::from_str(
+            r#"{"s": "text", "n": 1.50, "bool": true, "null": null, "b": "Ynl0ZXM=", "l": [{"k": -2}]}"#,
+        )
+        .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn numbers_are_converted() {
+        let codec = PlainJsonCodec::new();
+        for n in ["0", "-42", "0.1", "1.5E+3", "18446744073709551615"] {
+            let value = codec.to_value(&AttributeValue::N(n.into())).unwrap();
+            assert_eq!(value, serde_json::from_str::<Value>(n).unwrap());
+        }
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn numbers_keep_their_digits() {
+        let codec = PlainJsonCodec::new();
+        let n = "123456789012345678901234567890.123456789";
+
+        let value = codec.to_value(&AttributeValue::N(n.into())).unwrap();
+        assert_eq!(value.to_string(), n);
+        assert_eq!(codec.from_value(value), AttributeValue::N(n.into()));
+
+        let value: Value = serde_json::from_str(n).unwrap();
+        assert_eq!(codec.from_value(value), AttributeValue::N(n.into()));
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    #[test]
+    fn numbers_that_would_be_rounded_are_errors() {
+        for n in [
+            "123456789012345678901234567890.123456789",
+            "0.30000000000000001",
+        ] {
+            assert!(matches!(
+                PlainJsonCodec::new().to_value(&AttributeValue::N(n.into())),
+                Err(PlainJsonError::InexactNumber(v)) if v == n
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_numbers_are_errors() {
+        assert!(matches!(
+            PlainJsonCodec::new().to_value(&AttributeValue::N("NaN".into())),
+            Err(PlainJsonError::InvalidNumber(n)) if n == "NaN"
+        ));
+    }
+
+    #[test]
+    fn sets_follow_the_policy() {
+        let set = AttributeValue::Ns(vec!["3".into(), "1".into(), "2".into()]);
+
+        let value = PlainJsonCodec::new().to_value(&set).unwrap();
+        assert_eq!(value, json!([3, 1, 2]));
+
+        let value = PlainJsonCodec::new()
+            .with_set_policy(SetPolicy::SortedArray)
+            .to_value(&set)
+            .unwrap();
+        assert_eq!(value, json!([1, 2, 3]));
+
+        let result = PlainJsonCodec::new()
+            .with_set_policy(SetPolicy::Reject)
+            .to_value(&AttributeValue::Ss(vec!["a".into()]));
+        assert!(matches!(result, Err(PlainJsonError::SetNotAllowed)));
+    }
+
+    #[test]
+    fn json_is_converted_into_values() {
+        let codec = PlainJsonCodec::new();
+        let item = codec
+            .item_from_value(json!({ "a": [null, false, "x", { "b": 1 }] }))
+            .unwrap();
+
+        assert_eq!(
+            item["a"],
+            AttributeValue::L(vec![
+                AttributeValue::Null(true),
+                AttributeValue::Bool(false),
+                AttributeValue::S("x".into()),
+                AttributeValue::M([("b".to_string(), AttributeValue::N("1".into()))].into()),
+            ])
+        );
+
+        assert!(matches!(
+            codec.item_from_value(json!([])),
+            Err(PlainJsonError::NotAnObject(_))
+        ));
+    }
+
+    #[test]
+    fn items_implement_plain_json() {
+        let item: HashMap<String, AttributeValue> =
+            [("a".to_string(), AttributeValue::S("x".into()))].into();
+
+        let value = item.clone().to_plain_json().unwrap();
+        assert_eq!(value, json!({ "a": "x" }));
+        assert_eq!(HashMap::from_plain_json(value).unwrap(), item);
+    }
+}
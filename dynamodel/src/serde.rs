@@ -0,0 +1,924 @@
+//! A serde data format for `AttributeValue`.
+//!
+//! Types of other crates can't have the [`Dynamodel`](crate::Dynamodel) macro, but many of them
+//! implement `Serialize` and `Deserialize`. [`to_attribute_value`] and [`from_attribute_value`]
+//! convert them from and into an `AttributeValue`, and wrapping a field in [`Serde`] with the
+//! `fallible` attribute lets any of them be a field of your object.
+//!
+//! ```rust
+//! use dynamodel::{Dynamodel, Serde};
+//! use serde::{Deserialize, Serialize};
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//! # use std::collections::HashMap;
+//!
+//! // A type from another crate.
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Settings {
+//!     theme: String,
+//!     font_size: u8,
+//! }
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct User {
+//!     id: String,
+//!     #[dynamodel(fallible)]
+//!     settings: Serde<Settings>,
+//! }
+//!
+//! let user = User {
+//!     id: "7cf27a02".into(),
+//!     settings: Serde(Settings { theme: "dark".into(), font_size: 14 }),
+//! };
+//!
+//! let item: HashMap<String, AttributeValue> = user.try_into_item().unwrap();
+//! assert_eq!(
+//!     item["settings"],
+//!     AttributeValue::M(HashMap::from([
+//!         ("theme".to_string(), AttributeValue::S("dark".into())),
+//!         ("font_size".to_string(), AttributeValue::N("14".into())),
+//!     ])),
+//! );
+//! ```
+//!
+//! | Rust | `AttributeValue` |
+//! |---|---|
+//! | `bool` | `Bool` |
+//! | integers and floats | `N` |
+//! | `char`, `String` | `S` |
+//! | bytes, like with `serde_bytes` | `B` |
+//! | `None`, `()` and unit structs | `Null` |
+//! | sequences and tuples | `L` |
+//! | structs and maps with string or integer keys | `M` |
+//! | unit variants | `S` with the name of the variant |
+//! | other variants | `M` with the name of the variant as the only key |
+//!
+//! `Some(value)` and newtype structs are the inner value. Sets are read as sequences.
+
+use super::{AttributeValueConvertible, ConvertError};
+use ::serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use ::serde::ser::{self, Serialize};
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use thiserror::Error;
+
+/// An error occurs when serializing into or deserializing from an `AttributeValue`.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct Error(String);
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<ConvertError> for Error {
+    fn from(err: ConvertError) -> Self {
+        Self(err.to_string())
+    }
+}
+
+impl From<Error> for ConvertError {
+    fn from(err: Error) -> Self {
+        ConvertError::Other(Box::new(err))
+    }
+}
+
+/// Serializes a value into an `AttributeValue`.
+pub fn to_attribute_value<T: Serialize + ?Sized>(value: &T) -> Result<AttributeValue, Error> {
+    value.serialize(Serializer)
+}
+
+/// Deserializes a value from an `AttributeValue`.
+pub fn from_attribute_value<T: DeserializeOwned>(value: AttributeValue) -> Result<T, Error> {
+    T::deserialize(Deserializer(value))
+}
+
+/// A field of any type that implements `Serialize` and `Deserialize`, converted with this
+/// module.
+///
+/// Serializing fails if the value can't be an `AttributeValue`, like a map with keys that are
+/// not strings, so a `Serde` field needs the `fallible` attribute, and the object implements
+/// `TryFrom` instead of `From` for the HashMap and returns the error from `try_into_item`. It
+/// can't have the `by_ref` attribute.
+/// `Serde` implements only `try_into_attribute_value`, so `into_attribute_value` fails to compile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Serde<T>(pub T);
+
+impl<T> Serde<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Serde<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for Serde<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Serde<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> AttributeValueConvertible for Serde<T> {
+    const FALLIBLE: bool = true;
+
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        Self::try_from_attribute_value_owned(value.clone())
+    }
+
+    fn try_into_attribute_value(self) -> Result<AttributeValue, ConvertError> {
+        to_attribute_value(&self.0).map_err(ConvertError::from)
+    }
+
+    fn try_from_attribute_value_owned(value: AttributeValue) -> Result<Self, ConvertError> {
+        from_attribute_value(value)
+            .map(Self)
+            .map_err(ConvertError::from)
+    }
+}
+
+/// The serializer into an `AttributeValue`.
+pub struct Serializer;
+
+fn number<T: AttributeValueConvertible>(value: T) -> Result<AttributeValue, Error> {
+    value.try_into_attribute_value().map_err(Error::from)
+}
+
+fn variant(name: &'static str, value: AttributeValue) -> AttributeValue {
+    AttributeValue::M(HashMap::from([(name.to_string(), value)]))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = AttributeValue;
+    type Error = Error;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeList;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeMap;
+
+    fn serialize_bool(self, v: bool) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<AttributeValue, Error> {
+        number(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::B(Blob::new(v)))
+    }
+
+    fn serialize_none(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<AttributeValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, Error> {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList, Error> {
+        Ok(SerializeList {
+            values: Vec::with_capacity(len.unwrap_or_default()),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, Error> {
+        Ok(SerializeList {
+            values: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: HashMap::with_capacity(len.unwrap_or_default()),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: HashMap::with_capacity(len),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+/// Serializes sequences, tuples and tuple variants into `AttributeValue::L`.
+pub struct SerializeList {
+    values: Vec<AttributeValue>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeList {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<AttributeValue, Error> {
+        let list = AttributeValue::L(self.values);
+        Ok(match self.variant {
+            Some(name) => variant(name, list),
+            None => list,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeList {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+/// Serializes maps, structs and struct variants into `AttributeValue::M`.
+pub struct SerializeMap {
+    map: HashMap<String, AttributeValue>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeMap {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.map.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<AttributeValue, Error> {
+        let map = AttributeValue::M(self.map);
+        Ok(match self.variant {
+            Some(name) => variant(name, map),
+            None => map,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = match key.serialize(Serializer)? {
+            AttributeValue::S(key) | AttributeValue::N(key) => Some(key),
+            key => {
+                return Err(Error(format!(
+                    "map keys must be strings, but got `{key:?}`"
+                )));
+            }
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("map value without a key".into()))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeMap {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        self.finish()
+    }
+}
+
+/// The deserializer from an `AttributeValue`.
+pub struct Deserializer(pub AttributeValue);
+
+impl Deserializer {
+    fn number<T: AttributeValueConvertible>(self) -> Result<T, Error> {
+        T::try_from_attribute_value_owned(self.0).map_err(Error::from)
+    }
+}
+
+macro_rules! deserialize_numbers {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.number::<$ty>()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            AttributeValue::S(v) => visitor.visit_string(v),
+            AttributeValue::N(v) => {
+                if let Ok(n) = v.parse::<u64>() {
+                    visitor.visit_u64(n)
+                } else if let Ok(n) = v.parse::<i64>() {
+                    visitor.visit_i64(n)
+                } else if let Ok(n) = v.parse::<f64>() {
+                    visitor.visit_f64(n)
+                } else {
+                    Err(Error(format!("`{v}` is not a number")))
+                }
+            }
+            AttributeValue::Bool(v) => visitor.visit_bool(v),
+            AttributeValue::Null(_) => visitor.visit_unit(),
+            AttributeValue::B(v) => visitor.visit_byte_buf(v.into_inner()),
+            AttributeValue::L(v) => visit_seq(v, visitor),
+            AttributeValue::Ss(v) => visit_seq(v.into_iter().map(AttributeValue::S), visitor),
+            AttributeValue::Ns(v) => visit_seq(v.into_iter().map(AttributeValue::N), visitor),
+            AttributeValue::Bs(v) => visit_seq(v.into_iter().map(AttributeValue::B), visitor),
+            AttributeValue::M(v) => visitor.visit_map(MapDeserializer {
+                entries: v.into_iter(),
+                value: None,
+            }),
+            value => Err(Error(format!("cannot deserialize `{value:?}`"))),
+        }
+    }
+
+    deserialize_numbers! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            AttributeValue::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            AttributeValue::S(variant) => {
+                visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(variant))
+            }
+            AttributeValue::M(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("one entry");
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            value => Err(Error(format!(
+                "expect `S` or `M` with a single key for an enum, but got `{value:?}`"
+            ))),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier
+    }
+}
+
+fn visit_seq<'de, I, V>(values: I, visitor: V) -> Result<V::Value, Error>
+where
+    I: IntoIterator<Item = AttributeValue>,
+    V: Visitor<'de>,
+{
+    let mut seq = SeqDeserializer(values.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    match seq.0.next() {
+        None => Ok(value),
+        Some(_) => Err(Error("the list has more elements than expected".into())),
+    }
+}
+
+struct SeqDeserializer<I>(I);
+
+impl<'de, I: Iterator<Item = AttributeValue>> SeqAccess<'de> for SeqDeserializer<I> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(Deserializer(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint().1
+    }
+}
+
+struct MapDeserializer {
+    entries: std::collections::hash_map::IntoIter<String, AttributeValue>,
+    value: Option<AttributeValue>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(KeyDeserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("map key without a value".into()))?;
+        seed.deserialize(Deserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+// Map keys are always strings, but the keys of integers are read as `N`, like they are written.
+struct KeyDeserializer(String);
+
+macro_rules! deserialize_number_keys {
+    ($($method:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                Deserializer(AttributeValue::N(self.0)).$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_number_keys! {
+        deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64, deserialize_i128,
+        deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64, deserialize_u128,
+        deserialize_f32, deserialize_f64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(self.0))
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool char str string bytes byte_buf option unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: AttributeValue,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = Deserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer), Error> {
+        let variant =
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+        Ok((variant, Deserializer(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Deserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0 {
+            AttributeValue::Null(_) => Ok(()),
+            value => Err(Error(format!(
+                "expect `Null` for a unit variant, but got `{value:?}`"
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Settings {
+        theme: String,
+        font_size: u8,
+        ratio: f64,
+        nickname: Option<String>,
+        tags: Vec<String>,
+        mode: Mode,
+        limits: BTreeMap<u32, bool>,
+        point: (i64, i64),
+        id: Id,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Light,
+        Custom(String),
+        Scaled { factor: u16 },
+        Pair(u8, u8),
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Id(u128);
+
+    fn settings() -> Settings {
+        Settings {
+            theme: "dark".into(),
+            font_size: 14,
+            ratio: 1.5,
+            nickname: None,
+            tags: vec!["a".into(), "b".into()],
+            mode: Mode::Scaled { factor: 2 },
+            limits: [(1, true)].into(),
+            point: (-1, 2),
+            id: Id(u128::from(u64::MAX) + 1),
+        }
+    }
+
+    fn map(entries: &[(&str, AttributeValue)]) -> AttributeValue {
+        AttributeValue::M(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    fn n(v: &str) -> AttributeValue {
+        AttributeValue::N(v.into())
+    }
+
+    #[test]
+    fn structs_are_serialized_into_maps() {
+        let value = to_attribute_value(&settings()).unwrap();
+
+        let expected = map(&[
+            ("theme", AttributeValue::S("dark".into())),
+            ("font_size", n("14")),
+            ("ratio", n("1.5")),
+            ("nickname", AttributeValue::Null(true)),
+            (
+                "tags",
+                AttributeValue::L(vec![
+                    AttributeValue::S("a".into()),
+                    AttributeValue::S("b".into()),
+                ]),
+            ),
+            ("mode", map(&[("Scaled", map(&[("factor", n("2"))]))])),
+            ("limits", map(&[("1", AttributeValue::Bool(true))])),
+            ("point", AttributeValue::L(vec![n("-1"), n("2")])),
+            ("id", n("18446744073709551616")),
+        ]);
+        assert_eq!(value, expected);
+
+        let converted: Settings = from_attribute_value(value).unwrap();
+        assert_eq!(converted, settings());
+    }
+
+    #[test]
+    fn variants_are_converted() {
+        for (mode, expected) in [
+            (Mode::Light, AttributeValue::S("Light".into())),
+            (
+                Mode::Custom("x".into()),
+                map(&[("Custom", AttributeValue::S("x".into()))]),
+            ),
+            (
+                Mode::Pair(1, 2),
+                map(&[("Pair", AttributeValue::L(vec![n("1"), n("2")]))]),
+            ),
+        ] {
+            let value = to_attribute_value(&mode).unwrap();
+            assert_eq!(value, expected);
+            assert_eq!(from_attribute_value::<Mode>(value).unwrap(), mode);
+        }
+    }
+
+    #[test]
+    fn values_are_read_leniently() {
+        let value: u32 = from_attribute_value(n("1E+2")).unwrap();
+        assert_eq!(value, 100);
+
+        let value: Vec<String> =
+            from_attribute_value(AttributeValue::Ss(vec!["a".into()])).unwrap();
+        assert_eq!(value, ["a"]);
+
+        let value: Option<u8> = from_attribute_value(AttributeValue::Null(true)).unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        assert!(to_attribute_value(&f64::NAN).is_err());
+        assert!(to_attribute_value(&HashMap::from([((1, 2), 3)])).is_err());
+        assert!(from_attribute_value::<u8>(n("256")).is_err());
+        assert!(from_attribute_value::<Mode>(AttributeValue::Bool(true)).is_err());
+        assert!(from_attribute_value::<(u8, u8)>(AttributeValue::L(vec![n("1"); 3])).is_err());
+    }
+
+    #[test]
+    fn serde_fields_are_attribute_values() {
+        let value = Serde(settings()).try_into_attribute_value().unwrap();
+        assert_eq!(value, to_attribute_value(&settings()).unwrap());
+
+        let result = Serde(HashMap::from([((1, 2), 3)])).try_into_attribute_value();
+        assert!(matches!(result, Err(ConvertError::Other(_))));
+
+        let converted = Serde::<Settings>::try_from_attribute_value(&value).unwrap();
+        assert_eq!(converted.into_inner(), settings());
+
+        let err = Serde::<Settings>::try_from_attribute_value(&n("1")).unwrap_err();
+        assert!(matches!(err, ConvertError::Other(_)));
+    }
+}
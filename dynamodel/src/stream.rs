@@ -0,0 +1,263 @@
+//! Typed records of DynamoDB Streams.
+//!
+//! Stream consumers don't get `aws_sdk_dynamodb` attribute values. The Lambda events of
+//! `aws_lambda_events` hold `serde_dynamo` items, and the `aws-sdk-dynamodbstreams` client
+//! has its own `AttributeValue`. [`StreamRecord`] takes any of them that converts into a
+//! [`serde_dynamo::Item`] and loads the images into objects with the
+//! [`Dynamodel`](crate::Dynamodel) macro.
+//!
+//! ```rust
+//! use dynamodel::{Dynamodel, stream::{EventName, StreamRecord}};
+//! use serde_dynamo::{AttributeValue, Item};
+//! use std::collections::HashMap;
+//!
+//! #[derive(Dynamodel, Debug, PartialEq)]
+//! struct Video {
+//!     id: String,
+//!     views: u64,
+//! }
+//!
+//! // The `Keys`, `OldImage` and `NewImage` of a record in a Lambda event.
+//! let keys = Item::from(HashMap::from([
+//!     ("id".to_string(), AttributeValue::S("7cf27a02".into())),
+//! ]));
+//! let new_image = Item::from(HashMap::from([
+//!     ("id".to_string(), AttributeValue::S("7cf27a02".into())),
+//!     ("views".to_string(), AttributeValue::N("42".into())),
+//! ]));
+//!
+//! let record: StreamRecord<Video> =
+//!     StreamRecord::try_from_images("INSERT", keys, None, Some(new_image)).unwrap();
+//!
+//! assert_eq!(record.event_name, EventName::Insert);
+//! assert_eq!(record.old, None);
+//! assert_eq!(record.new, Some(Video { id: "7cf27a02".into(), views: 42 }));
+//! ```
+//!
+//! For the `aws-sdk-dynamodbstreams` client, enable the `streams-sdk` feature, which converts
+//! its attribute values into `serde_dynamo` ones, and pass the `keys`, `old_image` and
+//! `new_image` maps of the record.
+
+use super::ConvertError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde_dynamo::Item;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The type of the change in a stream record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventName {
+    /// A new item was added to the table.
+    Insert,
+    /// One or more attributes of an item were modified.
+    Modify,
+    /// An item was deleted from the table.
+    Remove,
+}
+
+impl EventName {
+    /// The name as it is in stream records, like `"INSERT"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "INSERT",
+            Self::Modify => "MODIFY",
+            Self::Remove => "REMOVE",
+        }
+    }
+}
+
+impl fmt::Display for EventName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventName {
+    type Err = ConvertError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "INSERT" => Ok(Self::Insert),
+            "MODIFY" => Ok(Self::Modify),
+            "REMOVE" => Ok(Self::Remove),
+            _ => Err(ConvertError::ParseValue("EventName".into(), s.into())),
+        }
+    }
+}
+
+/// A change to an item with its images loaded into `T`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamRecord<T> {
+    /// The type of the change.
+    pub event_name: EventName,
+    /// The key attributes of the changed item.
+    pub keys: HashMap<String, AttributeValue>,
+    /// The item before the change, if the stream view type includes old images.
+    pub old: Option<T>,
+    /// The item after the change, if the stream view type includes new images.
+    pub new: Option<T>,
+}
+
+impl<T> StreamRecord<T>
+where
+    T: TryFrom<HashMap<String, AttributeValue>, Error = ConvertError>,
+{
+    /// Loads the images of a stream record.
+    ///
+    /// Missing and empty images are `None`, since Lambda events give empty maps for the images
+    /// the event or the stream view type doesn't have.
+    pub fn try_from_images<I: Into<Item>>(
+        event_name: &str,
+        keys: I,
+        old_image: Option<I>,
+        new_image: Option<I>,
+    ) -> Result<Self, ConvertError> {
+        let image = |image: Option<I>| {
+            image
+                .map(into_item)
+                .filter(|item| !item.is_empty())
+                .map(T::try_from)
+                .transpose()
+        };
+
+        Ok(Self {
+            event_name: event_name.parse()?,
+            keys: into_item(keys),
+            old: image(old_image)?,
+            new: image(new_image)?,
+        })
+    }
+}
+
+/// Converts an item of a stream record into the `HashMap` of `aws_sdk_dynamodb`.
+pub fn into_item<I: Into<Item>>(item: I) -> HashMap<String, AttributeValue> {
+    item.into().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_dynamo::AttributeValue as StreamValue;
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(u64);
+
+    impl TryFrom<HashMap<String, AttributeValue>> for Counter {
+        type Error = ConvertError;
+
+        fn try_from(item: HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+            let count = item
+                .get("count")
+                .ok_or(ConvertError::FieldNotSet("count".into()))?;
+            Ok(Self(count.as_n().unwrap().parse()?))
+        }
+    }
+
+    fn item(count: &str) -> HashMap<String, StreamValue> {
+        [
+            ("id".to_string(), StreamValue::S("c1".into())),
+            ("count".to_string(), StreamValue::N(count.into())),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn event_names_are_parsed() {
+        for name in [EventName::Insert, EventName::Modify, EventName::Remove] {
+            assert_eq!(name.as_str().parse::<EventName>().unwrap(), name);
+        }
+        assert!(matches!(
+            "UPSERT".parse::<EventName>(),
+            Err(ConvertError::ParseValue(ty, value)) if ty == "EventName" && value == "UPSERT"
+        ));
+    }
+
+    #[test]
+    fn images_are_loaded() {
+        let keys: HashMap<String, StreamValue> =
+            [("id".to_string(), StreamValue::S("c1".into()))].into();
+
+        let record: StreamRecord<Counter> =
+            StreamRecord::try_from_images("MODIFY", keys, Some(item("1")), Some(item("2")))
+                .unwrap();
+
+        assert_eq!(record.event_name, EventName::Modify);
+        assert_eq!(
+            record.keys,
+            [("id".to_string(), AttributeValue::S("c1".into()))].into()
+        );
+        assert_eq!(record.old, Some(Counter(1)));
+        assert_eq!(record.new, Some(Counter(2)));
+    }
+
+    #[test]
+    fn empty_images_are_none() {
+        let record: StreamRecord<Counter> = StreamRecord::try_from_images(
+            "REMOVE",
+            item("1"),
+            Some(item("1")),
+            Some(HashMap::new()),
+        )
+        .unwrap();
+
+        assert_eq!(record.old, Some(Counter(1)));
+        assert_eq!(record.new, None);
+    }
+
+    #[cfg(feature = "streams-sdk")]
+    #[test]
+    fn images_of_the_streams_sdk_are_loaded() {
+        use aws_sdk_dynamodbstreams::types::{AttributeValue as SdkValue, StreamRecord as Record};
+
+        let image = |count: &str| {
+            HashMap::from([
+                ("id".to_string(), SdkValue::S("c1".into())),
+                ("count".to_string(), SdkValue::N(count.into())),
+            ])
+        };
+        let record = Record::builder()
+            .keys("id", SdkValue::S("c1".into()))
+            .set_old_image(Some(image("1")))
+            .set_new_image(Some(image("2")))
+            .build();
+
+        let record: StreamRecord<Counter> = StreamRecord::try_from_images(
+            "MODIFY",
+            record.keys.unwrap_or_default(),
+            record.old_image,
+            record.new_image,
+        )
+        .unwrap();
+
+        assert_eq!(
+            record.keys,
+            [("id".to_string(), AttributeValue::S("c1".into()))].into()
+        );
+        assert_eq!(record.old, Some(Counter(1)));
+        assert_eq!(record.new, Some(Counter(2)));
+    }
+
+    #[test]
+    fn nested_values_are_converted() {
+        let item: HashMap<String, StreamValue> = [(
+            "tags".to_string(),
+            StreamValue::L(vec![
+                StreamValue::M([("name".to_string(), StreamValue::Null(true))].into()),
+                StreamValue::Ss(vec!["a".into()]),
+                StreamValue::B(vec![1, 2]),
+            ]),
+        )]
+        .into();
+
+        let converted = into_item(item);
+        assert_eq!(
+            converted["tags"],
+            AttributeValue::L(vec![
+                AttributeValue::M([("name".to_string(), AttributeValue::Null(true))].into()),
+                AttributeValue::Ss(vec!["a".into()]),
+                AttributeValue::B(vec![1, 2].into()),
+            ])
+        );
+    }
+}
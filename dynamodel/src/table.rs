@@ -0,0 +1,435 @@
+//! A small abstraction over the item operations of a DynamoDB table.
+//!
+//! Write your repository layer against the [`Table`] trait instead of the SDK client.
+//! [`SdkTable`] implements it with `aws_sdk_dynamodb::Client`, and with the `testing`
+//! feature, [`MemoryTable`](crate::testing::MemoryTable) implements it in memory, so that
+//! the repository layer can be tested without DynamoDB Local or network access.
+//!
+//! ```rust
+//! use dynamodel::{table::{Table, TableError}, ConvertError, Dynamodel, Expression};
+//! # use std::collections::HashMap;
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Video {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     title: String,
+//! }
+//!
+//! struct VideoRepository<T> {
+//!     table: T,
+//! }
+//!
+//! impl<T: Table> VideoRepository<T> {
+//!     async fn create(&self, video: Video) -> Result<(), TableError> {
+//!         let condition = Expression::condition("attribute_not_exists(PK)");
+//!         self.table.put_item(video.into(), Some(condition)).await
+//!     }
+//!
+//!     async fn find(&self, id: &str) -> Result<Option<Video>, TableError> {
+//!         let key = [("PK".to_string(), AttributeValue::S(id.into()))].into();
+//!         let item = self.table.get_item(key).await?;
+//!         Ok(item.map(Video::try_from).transpose()?)
+//!     }
+//! }
+//! ```
+
+use super::{ConvertError, Expression, Versioned};
+use aws_sdk_dynamodb::{Client, types::AttributeValue, types::ReturnValue};
+use std::{collections::HashMap, future::Future};
+use thiserror::Error;
+
+/// A DynamoDB item.
+pub type Item = HashMap<String, AttributeValue>;
+
+/// An error occurs on a table operation.
+#[derive(Debug, Error)]
+pub enum TableError {
+    /// The condition expression of the request was not met.
+    #[error("the conditional request failed")]
+    ConditionalCheckFailed,
+
+    /// The stored item has a different version than the one being saved,
+    /// which means another writer has updated it.
+    #[error("the item has been updated by another writer")]
+    VersionConflict,
+
+    /// The request is invalid, like a malformed expression or a key without key attributes.
+    #[error("validation error: {0}")]
+    Validation(String),
+
+    /// An item could not be converted.
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+
+    /// The SDK returned an error.
+    #[error(transparent)]
+    Sdk(#[from] Box<aws_sdk_dynamodb::Error>),
+}
+
+impl TableError {
+    fn sdk(err: impl Into<aws_sdk_dynamodb::Error>) -> Self {
+        match err.into() {
+            aws_sdk_dynamodb::Error::ConditionalCheckFailedException(_) => {
+                Self::ConditionalCheckFailed
+            }
+            err => Self::Sdk(Box::new(err)),
+        }
+    }
+}
+
+/// The parameters of a `Query` request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    /// The index to query. `None` queries the table.
+    pub index_name: Option<String>,
+    /// The key condition expression with its placeholders.
+    pub key_condition: Expression,
+    /// The filter expression with its placeholders.
+    pub filter: Option<Expression>,
+    /// Returns the items in ascending order of the sort key if true.
+    pub scan_forward: bool,
+    /// The maximum number of items to evaluate.
+    pub limit: Option<usize>,
+    /// The `LastEvaluatedKey` of the previous page.
+    pub exclusive_start_key: Option<Item>,
+}
+
+impl Query {
+    /// Creates a query in ascending order with a key condition expression.
+    pub fn new(key_condition: Expression) -> Self {
+        Self {
+            key_condition,
+            scan_forward: true,
+            ..Self::default()
+        }
+    }
+
+    /// Queries the index instead of the table.
+    pub fn index(self, index_name: impl Into<String>) -> Self {
+        Self {
+            index_name: Some(index_name.into()),
+            ..self
+        }
+    }
+
+    /// Sets the filter expression.
+    pub fn filter(self, filter: Expression) -> Self {
+        Self {
+            filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Sets the order of the items.
+    pub fn scan_forward(self, scan_forward: bool) -> Self {
+        Self {
+            scan_forward,
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of items to evaluate.
+    pub fn limit(self, limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Starts the query after this key.
+    pub fn exclusive_start_key(self, key: Item) -> Self {
+        Self {
+            exclusive_start_key: Some(key),
+            ..self
+        }
+    }
+}
+
+/// The parameters of a `Scan` request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scan {
+    /// The index to scan. `None` scans the table.
+    pub index_name: Option<String>,
+    /// The filter expression with its placeholders.
+    pub filter: Option<Expression>,
+    /// The maximum number of items to evaluate.
+    pub limit: Option<usize>,
+    /// The `LastEvaluatedKey` of the previous page.
+    pub exclusive_start_key: Option<Item>,
+}
+
+impl Scan {
+    /// Creates a scan of the whole table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scans the index instead of the table.
+    pub fn index(self, index_name: impl Into<String>) -> Self {
+        Self {
+            index_name: Some(index_name.into()),
+            ..self
+        }
+    }
+
+    /// Sets the filter expression.
+    pub fn filter(self, filter: Expression) -> Self {
+        Self {
+            filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of items to evaluate.
+    pub fn limit(self, limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Starts the scan after this key.
+    pub fn exclusive_start_key(self, key: Item) -> Self {
+        Self {
+            exclusive_start_key: Some(key),
+            ..self
+        }
+    }
+}
+
+/// A page of items returned by `Query` or `Scan`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Page {
+    /// The items in the page.
+    pub items: Vec<Item>,
+    /// The key to start the next page from. `None` if this is the last page.
+    pub last_evaluated_key: Option<Item>,
+}
+
+impl Page {
+    /// Converts the items into `T`.
+    pub fn items_as<T>(self) -> Result<Vec<T>, ConvertError>
+    where
+        T: TryFrom<Item, Error = ConvertError>,
+    {
+        self.items.into_iter().map(T::try_from).collect()
+    }
+}
+
+/// The item operations of a DynamoDB table.
+pub trait Table {
+    /// Gets the item with the key.
+    fn get_item(&self, key: Item) -> impl Future<Output = Result<Option<Item>, TableError>> + Send;
+
+    /// Puts the item if the condition is met.
+    fn put_item(
+        &self,
+        item: Item,
+        condition: Option<Expression>,
+    ) -> impl Future<Output = Result<(), TableError>> + Send;
+
+    /// Deletes the item with the key if the condition is met.
+    fn delete_item(
+        &self,
+        key: Item,
+        condition: Option<Expression>,
+    ) -> impl Future<Output = Result<(), TableError>> + Send;
+
+    /// Updates the item with the key and returns the updated item.
+    ///
+    /// The expression must have an update expression and may have a condition expression.
+    fn update_item(
+        &self,
+        key: Item,
+        expression: Expression,
+    ) -> impl Future<Output = Result<Item, TableError>> + Send;
+
+    /// Queries the table or an index.
+    fn query(&self, query: Query) -> impl Future<Output = Result<Page, TableError>> + Send;
+
+    /// Scans the table or an index.
+    fn scan(&self, scan: Scan) -> impl Future<Output = Result<Page, TableError>> + Send;
+
+    /// Puts the object with optimistic locking.
+    ///
+    /// The version of the object is incremented only if the item is saved. If the stored item
+    /// has a different version, this returns [`TableError::VersionConflict`], and if the version
+    /// can't be incremented, [`TableError::Convert`].
+    fn put_versioned<T>(&self, model: &mut T) -> impl Future<Output = Result<(), TableError>> + Send
+    where
+        Self: Sync,
+        T: Versioned + Clone + Into<Item> + Send,
+    {
+        async move {
+            let mut next = model.clone();
+            let condition = next.increment_version()?;
+
+            match self.put_item(next.clone().into(), Some(condition)).await {
+                Ok(()) => {
+                    *model = next;
+                    Ok(())
+                }
+                Err(TableError::ConditionalCheckFailed) => Err(TableError::VersionConflict),
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
+
+/// A [`Table`] backed by the SDK client.
+#[derive(Debug, Clone)]
+pub struct SdkTable {
+    client: Client,
+    table_name: String,
+}
+
+impl SdkTable {
+    /// Creates a table with the client and the table name.
+    pub fn new(client: Client, table_name: impl Into<String>) -> Self {
+        Self {
+            client,
+            table_name: table_name.into(),
+        }
+    }
+
+    /// The SDK client.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// The name of the table.
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+}
+
+fn limit(limit: Option<usize>) -> Option<i32> {
+    limit.map(|v| i32::try_from(v).unwrap_or(i32::MAX))
+}
+
+impl Table for SdkTable {
+    async fn get_item(&self, key: Item) -> Result<Option<Item>, TableError> {
+        self.client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(key))
+            .send()
+            .await
+            .map(|output| output.item)
+            .map_err(TableError::sdk)
+    }
+
+    async fn put_item(&self, item: Item, condition: Option<Expression>) -> Result<(), TableError> {
+        let condition = condition.as_ref();
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(item))
+            .set_condition_expression(
+                condition
+                    .and_then(Expression::condition_expression)
+                    .map(String::from),
+            )
+            .set_expression_attribute_names(condition.and_then(Expression::attribute_names))
+            .set_expression_attribute_values(condition.and_then(Expression::attribute_values))
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(TableError::sdk)
+    }
+
+    async fn delete_item(
+        &self,
+        key: Item,
+        condition: Option<Expression>,
+    ) -> Result<(), TableError> {
+        let condition = condition.as_ref();
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .set_key(Some(key))
+            .set_condition_expression(
+                condition
+                    .and_then(Expression::condition_expression)
+                    .map(String::from),
+            )
+            .set_expression_attribute_names(condition.and_then(Expression::attribute_names))
+            .set_expression_attribute_values(condition.and_then(Expression::attribute_values))
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(TableError::sdk)
+    }
+
+    async fn update_item(&self, key: Item, expression: Expression) -> Result<Item, TableError> {
+        self.client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(key))
+            .set_update_expression(expression.update_expression().map(String::from))
+            .set_condition_expression(expression.condition_expression().map(String::from))
+            .set_expression_attribute_names(expression.attribute_names())
+            .set_expression_attribute_values(expression.attribute_values())
+            .return_values(ReturnValue::AllNew)
+            .send()
+            .await
+            .map(|output| output.attributes.unwrap_or_default())
+            .map_err(TableError::sdk)
+    }
+
+    async fn query(&self, query: Query) -> Result<Page, TableError> {
+        // The key condition and the filter share the placeholders.
+        let key_condition = query.key_condition;
+        let filter = query.filter.unwrap_or_default();
+        let mut names = key_condition.names().clone();
+        names.extend(filter.names().clone());
+        let mut values = key_condition.values().clone();
+        values.extend(filter.values().clone());
+
+        self.client
+            .query()
+            .table_name(&self.table_name)
+            .set_index_name(query.index_name)
+            .set_key_condition_expression(key_condition.condition_expression().map(String::from))
+            .set_filter_expression(filter.condition_expression().map(String::from))
+            .set_expression_attribute_names(Some(names).filter(|v| !v.is_empty()))
+            .set_expression_attribute_values(Some(values).filter(|v| !v.is_empty()))
+            .scan_index_forward(query.scan_forward)
+            .set_limit(limit(query.limit))
+            .set_exclusive_start_key(query.exclusive_start_key)
+            .send()
+            .await
+            .map(|output| Page {
+                items: output.items.unwrap_or_default(),
+                last_evaluated_key: output.last_evaluated_key,
+            })
+            .map_err(TableError::sdk)
+    }
+
+    async fn scan(&self, scan: Scan) -> Result<Page, TableError> {
+        let filter = scan.filter.as_ref();
+        self.client
+            .scan()
+            .table_name(&self.table_name)
+            .set_index_name(scan.index_name.clone())
+            .set_filter_expression(
+                filter
+                    .and_then(Expression::condition_expression)
+                    .map(String::from),
+            )
+            .set_expression_attribute_names(filter.and_then(Expression::attribute_names))
+            .set_expression_attribute_values(filter.and_then(Expression::attribute_values))
+            .set_limit(limit(scan.limit))
+            .set_exclusive_start_key(scan.exclusive_start_key.clone())
+            .send()
+            .await
+            .map(|output| Page {
+                items: output.items.unwrap_or_default(),
+                last_evaluated_key: output.last_evaluated_key,
+            })
+            .map_err(TableError::sdk)
+    }
+}
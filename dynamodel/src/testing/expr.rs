@@ -0,0 +1,1217 @@
+//! A parser and an evaluator of DynamoDB condition and update expressions.
+
+use super::number::Decimal;
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+type Item = HashMap<String, AttributeValue>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Name(String),
+    Value(String),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Plus,
+    Minus,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = expression.char_indices().peekable();
+
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            '=' => Token::Eq,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '<' => match chars.peek() {
+                Some((_, '>')) => {
+                    chars.next();
+                    Token::Ne
+                }
+                Some((_, '=')) => {
+                    chars.next();
+                    Token::Le
+                }
+                _ => Token::Lt,
+            },
+            '>' => match chars.peek() {
+                Some((_, '=')) => {
+                    chars.next();
+                    Token::Ge
+                }
+                _ => Token::Gt,
+            },
+            '#' | ':' => {
+                let mut word = String::new();
+                while let Some((_, c)) = chars.peek().filter(|(_, c)| is_word(*c)) {
+                    word.push(*c);
+                    chars.next();
+                }
+                if word.is_empty() {
+                    return Err(format!("invalid placeholder at {i} in `{expression}`"));
+                }
+                if c == '#' {
+                    Token::Name(format!("#{word}"))
+                } else {
+                    Token::Value(format!(":{word}"))
+                }
+            }
+            c if is_word(c) => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.peek().filter(|(_, c)| is_word(*c)) {
+                    word.push(*c);
+                    chars.next();
+                }
+                Token::Word(word)
+            }
+            c => return Err(format!("unexpected character `{c}` in `{expression}`")),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// The placeholders of a request, which may be shared by several expressions.
+///
+/// DynamoDB rejects requests with unused placeholders, so this keeps track of them.
+pub(super) struct Placeholders<'a> {
+    names: &'a HashMap<String, String>,
+    values: &'a HashMap<String, AttributeValue>,
+    used_names: RefCell<HashSet<String>>,
+    used_values: RefCell<HashSet<String>>,
+}
+
+impl<'a> Placeholders<'a> {
+    pub(super) fn new(
+        names: &'a HashMap<String, String>,
+        values: &'a HashMap<String, AttributeValue>,
+    ) -> Self {
+        Self {
+            names,
+            values,
+            used_names: RefCell::new(HashSet::new()),
+            used_values: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn name(&self, placeholder: &str) -> Result<String, String> {
+        self.used_names.borrow_mut().insert(placeholder.into());
+        self.names
+            .get(placeholder)
+            .cloned()
+            .ok_or_else(|| format!("attribute name placeholder `{placeholder}` is not defined"))
+    }
+
+    fn value(&self, placeholder: &str) -> Result<AttributeValue, String> {
+        self.used_values.borrow_mut().insert(placeholder.into());
+        self.values
+            .get(placeholder)
+            .cloned()
+            .ok_or_else(|| format!("value placeholder `{placeholder}` is not defined"))
+    }
+
+    /// Checks that every placeholder has been used.
+    pub(super) fn finish(self) -> Result<(), String> {
+        let used = self.used_names.borrow();
+        if let Some(name) = self.names.keys().find(|k| !used.contains(*k)) {
+            return Err(format!("attribute name placeholder `{name}` is not used"));
+        }
+        let used = self.used_values.borrow();
+        if let Some(value) = self.values.keys().find(|k| !used.contains(*k)) {
+            return Err(format!("value placeholder `{value}` is not used"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum PathElement {
+    Attribute(String),
+    Index(usize),
+}
+
+pub(super) type Path = Vec<PathElement>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Operand {
+    Path(Path),
+    Value(AttributeValue),
+    Size(Path),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Condition {
+    Compare(Operand, Comparator, Operand),
+    Between(Operand, Operand, Operand),
+    In(Operand, Vec<Operand>),
+    Exists(Path),
+    NotExists(Path),
+    Type(Path, Operand),
+    BeginsWith(Operand, Operand),
+    Contains(Operand, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SetOperand {
+    Path(Path),
+    Value(AttributeValue),
+    IfNotExists(Path, Box<SetOperand>),
+    ListAppend(Box<SetOperand>, Box<SetOperand>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SetValue {
+    Operand(SetOperand),
+    Plus(SetOperand, SetOperand),
+    Minus(SetOperand, SetOperand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum UpdateAction {
+    Set(Path, SetValue),
+    Remove(Path),
+    Add(Path, AttributeValue),
+    Delete(Path, AttributeValue),
+}
+
+/// A parsed update expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Update {
+    actions: Vec<UpdateAction>,
+}
+
+struct Parser<'a, 'p> {
+    tokens: Vec<Token>,
+    pos: usize,
+    placeholders: &'p Placeholders<'a>,
+}
+
+impl<'a, 'p> Parser<'a, 'p> {
+    fn new(expression: &str, placeholders: &'p Placeholders<'a>) -> Result<Self, String> {
+        Ok(Self {
+            tokens: tokenize(expression)?,
+            pos: 0,
+            placeholders,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("expect `{expected:?}`, but got `{token:?}`")),
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn peek_function(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == name)
+            && self.peek_at(1) == Some(&Token::LParen)
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.peek() {
+            Some(token) => Err(format!("unexpected token `{token:?}`")),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Path, String> {
+        let mut path = vec![self.parse_attribute_name()?];
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.next();
+                    path.push(self.parse_attribute_name()?);
+                }
+                Some(Token::LBracket) => {
+                    self.next();
+                    let index = match self.next() {
+                        Some(Token::Word(w)) => w
+                            .parse::<usize>()
+                            .map_err(|_| format!("invalid list index `{w}`"))?,
+                        token => return Err(format!("expect a list index, but got `{token:?}`")),
+                    };
+                    self.expect(Token::RBracket)?;
+                    path.push(PathElement::Index(index));
+                }
+                _ => return Ok(path),
+            }
+        }
+    }
+
+    fn parse_attribute_name(&mut self) -> Result<PathElement, String> {
+        match self.next() {
+            Some(Token::Word(w)) => Ok(PathElement::Attribute(w)),
+            Some(Token::Name(n)) => Ok(PathElement::Attribute(self.placeholders.name(&n)?)),
+            token => Err(format!("expect an attribute name, but got `{token:?}`")),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        if self.peek_function("size") {
+            self.next();
+            self.expect(Token::LParen)?;
+            let path = self.parse_path()?;
+            self.expect(Token::RParen)?;
+            return Ok(Operand::Size(path));
+        }
+
+        match self.peek() {
+            Some(Token::Value(v)) => {
+                let v = v.clone();
+                self.next();
+                Ok(Operand::Value(self.placeholders.value(&v)?))
+            }
+            _ => self.parse_path().map(Operand::Path),
+        }
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("OR") {
+            self.next();
+            left = Condition::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut left = self.parse_not()?;
+        while self.peek_keyword("AND") {
+            self.next();
+            left = Condition::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition, String> {
+        if self.peek_keyword("NOT") {
+            self.next();
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition, String> {
+        if self.peek() == Some(&Token::LParen) {
+            self.next();
+            let condition = self.parse_condition()?;
+            self.expect(Token::RParen)?;
+            return Ok(condition);
+        }
+
+        for function in [
+            "attribute_exists",
+            "attribute_not_exists",
+            "attribute_type",
+            "begins_with",
+            "contains",
+        ] {
+            if self.peek_function(function) {
+                self.next();
+                self.expect(Token::LParen)?;
+                let condition = match function {
+                    "attribute_exists" => Condition::Exists(self.parse_path()?),
+                    "attribute_not_exists" => Condition::NotExists(self.parse_path()?),
+                    "attribute_type" => {
+                        let path = self.parse_path()?;
+                        self.expect(Token::Comma)?;
+                        Condition::Type(path, self.parse_operand()?)
+                    }
+                    "begins_with" => {
+                        let operand = self.parse_operand()?;
+                        self.expect(Token::Comma)?;
+                        Condition::BeginsWith(operand, self.parse_operand()?)
+                    }
+                    _ => {
+                        let operand = self.parse_operand()?;
+                        self.expect(Token::Comma)?;
+                        Condition::Contains(operand, self.parse_operand()?)
+                    }
+                };
+                self.expect(Token::RParen)?;
+                return Ok(condition);
+            }
+        }
+
+        let left = self.parse_operand()?;
+
+        if self.peek_keyword("BETWEEN") {
+            self.next();
+            let low = self.parse_operand()?;
+            if !self.peek_keyword("AND") {
+                return Err("expect `AND` in `BETWEEN`".into());
+            }
+            self.next();
+            let high = self.parse_operand()?;
+            return Ok(Condition::Between(left, low, high));
+        }
+
+        if self.peek_keyword("IN") {
+            self.next();
+            self.expect(Token::LParen)?;
+            let mut operands = vec![self.parse_operand()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.next();
+                operands.push(self.parse_operand()?);
+            }
+            self.expect(Token::RParen)?;
+            return Ok(Condition::In(left, operands));
+        }
+
+        let comparator = match self.next() {
+            Some(Token::Eq) => Comparator::Eq,
+            Some(Token::Ne) => Comparator::Ne,
+            Some(Token::Lt) => Comparator::Lt,
+            Some(Token::Le) => Comparator::Le,
+            Some(Token::Gt) => Comparator::Gt,
+            Some(Token::Ge) => Comparator::Ge,
+            token => return Err(format!("expect a comparator, but got `{token:?}`")),
+        };
+        Ok(Condition::Compare(left, comparator, self.parse_operand()?))
+    }
+
+    fn parse_set_operand(&mut self) -> Result<SetOperand, String> {
+        if self.peek_function("if_not_exists") {
+            self.next();
+            self.expect(Token::LParen)?;
+            let path = self.parse_path()?;
+            self.expect(Token::Comma)?;
+            let operand = self.parse_set_operand()?;
+            self.expect(Token::RParen)?;
+            return Ok(SetOperand::IfNotExists(path, Box::new(operand)));
+        }
+
+        if self.peek_function("list_append") {
+            self.next();
+            self.expect(Token::LParen)?;
+            let a = self.parse_set_operand()?;
+            self.expect(Token::Comma)?;
+            let b = self.parse_set_operand()?;
+            self.expect(Token::RParen)?;
+            return Ok(SetOperand::ListAppend(Box::new(a), Box::new(b)));
+        }
+
+        match self.peek() {
+            Some(Token::Value(v)) => {
+                let v = v.clone();
+                self.next();
+                Ok(SetOperand::Value(self.placeholders.value(&v)?))
+            }
+            _ => self.parse_path().map(SetOperand::Path),
+        }
+    }
+
+    fn parse_set_value(&mut self) -> Result<SetValue, String> {
+        let left = self.parse_set_operand()?;
+        match self.peek() {
+            Some(Token::Plus) => {
+                self.next();
+                Ok(SetValue::Plus(left, self.parse_set_operand()?))
+            }
+            Some(Token::Minus) => {
+                self.next();
+                Ok(SetValue::Minus(left, self.parse_set_operand()?))
+            }
+            _ => Ok(SetValue::Operand(left)),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<AttributeValue, String> {
+        match self.next() {
+            Some(Token::Value(v)) => self.placeholders.value(&v),
+            token => Err(format!("expect a value placeholder, but got `{token:?}`")),
+        }
+    }
+
+    fn parse_update(&mut self) -> Result<Update, String> {
+        let mut actions = vec![];
+        let mut clauses: Vec<String> = vec![];
+
+        while let Some(token) = self.next() {
+            let clause = match token {
+                Token::Word(w) => w.to_ascii_uppercase(),
+                token => return Err(format!("expect an update action, but got `{token:?}`")),
+            };
+            if clauses.contains(&clause) {
+                return Err(format!("the `{clause}` section can only be used once"));
+            }
+
+            loop {
+                let action = match clause.as_str() {
+                    "SET" => {
+                        let path = self.parse_path()?;
+                        self.expect(Token::Eq)?;
+                        UpdateAction::Set(path, self.parse_set_value()?)
+                    }
+                    "REMOVE" => UpdateAction::Remove(self.parse_path()?),
+                    "ADD" => {
+                        let path = self.parse_path()?;
+                        UpdateAction::Add(path, self.parse_value()?)
+                    }
+                    "DELETE" => {
+                        let path = self.parse_path()?;
+                        UpdateAction::Delete(path, self.parse_value()?)
+                    }
+                    _ => return Err(format!("unknown update action `{clause}`")),
+                };
+                actions.push(action);
+
+                if self.peek() == Some(&Token::Comma) {
+                    self.next();
+                } else {
+                    break;
+                }
+            }
+
+            clauses.push(clause);
+        }
+
+        if actions.is_empty() {
+            return Err("update expression is empty".into());
+        }
+        Ok(Update { actions })
+    }
+}
+
+/// Parses a condition expression.
+pub(super) fn parse_condition(
+    expression: &str,
+    placeholders: &Placeholders<'_>,
+) -> Result<Condition, String> {
+    let mut parser = Parser::new(expression, placeholders)?;
+    let condition = parser.parse_condition()?;
+    parser.finish()?;
+    Ok(condition)
+}
+
+/// Parses an update expression.
+pub(super) fn parse_update(
+    expression: &str,
+    placeholders: &Placeholders<'_>,
+) -> Result<Update, String> {
+    let mut parser = Parser::new(expression, placeholders)?;
+    let update = parser.parse_update()?;
+    parser.finish()?;
+    Ok(update)
+}
+
+/// Returns the attribute at the path.
+pub(super) fn resolve<'a>(item: &'a Item, path: &[PathElement]) -> Option<&'a AttributeValue> {
+    let (first, rest) = path.split_first()?;
+    let mut value = match first {
+        PathElement::Attribute(name) => item.get(name)?,
+        PathElement::Index(_) => return None,
+    };
+    for element in rest {
+        value = match (element, value) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => map.get(name)?,
+            (PathElement::Index(i), AttributeValue::L(list)) => list.get(*i)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn operand_value<'a>(item: &'a Item, operand: &'a Operand) -> Option<Cow<'a, AttributeValue>> {
+    match operand {
+        Operand::Path(path) => resolve(item, path).map(Cow::Borrowed),
+        Operand::Value(value) => Some(Cow::Borrowed(value)),
+        Operand::Size(path) => {
+            let size = match resolve(item, path)? {
+                AttributeValue::S(s) => s.len(),
+                AttributeValue::B(b) => b.as_ref().len(),
+                AttributeValue::Ss(v) | AttributeValue::Ns(v) => v.len(),
+                AttributeValue::Bs(v) => v.len(),
+                AttributeValue::L(v) => v.len(),
+                AttributeValue::M(v) => v.len(),
+                _ => return None,
+            };
+            Some(Cow::Owned(AttributeValue::N(size.to_string())))
+        }
+    }
+}
+
+/// Compares two values of the same scalar type. Returns `None` for other combinations.
+pub(super) fn compare(a: &AttributeValue, b: &AttributeValue) -> Option<Ordering> {
+    match (a, b) {
+        (AttributeValue::N(a), AttributeValue::N(b)) => {
+            Some(Decimal::parse(a).ok()?.cmp(&Decimal::parse(b).ok()?))
+        }
+        (AttributeValue::S(a), AttributeValue::S(b)) => Some(a.as_bytes().cmp(b.as_bytes())),
+        (AttributeValue::B(a), AttributeValue::B(b)) => Some(a.as_ref().cmp(b.as_ref())),
+        _ => None,
+    }
+}
+
+/// Returns true if both values are equal, comparing numbers by value and sets regardless of
+/// their order.
+pub(super) fn equals(a: &AttributeValue, b: &AttributeValue) -> bool {
+    fn same_set<T, F>(a: &[T], b: &[T], eq: F) -> bool
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        a.len() == b.len() && a.iter().all(|x| b.iter().any(|y| eq(x, y)))
+    }
+
+    match (a, b) {
+        (AttributeValue::N(_), AttributeValue::N(_)) => compare(a, b) == Some(Ordering::Equal),
+        (AttributeValue::Ss(a), AttributeValue::Ss(b)) => same_set(a, b, |x, y| x == y),
+        (AttributeValue::Ns(a), AttributeValue::Ns(b)) => same_set(a, b, |x, y| {
+            compare(&AttributeValue::N(x.clone()), &AttributeValue::N(y.clone()))
+                == Some(Ordering::Equal)
+        }),
+        (AttributeValue::Bs(a), AttributeValue::Bs(b)) => same_set(a, b, |x, y| x == y),
+        (AttributeValue::L(a), AttributeValue::L(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| equals(x, y))
+        }
+        (AttributeValue::M(a), AttributeValue::M(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, x)| b.get(k).is_some_and(|y| equals(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+fn type_name(value: &AttributeValue) -> &'static str {
+    match value {
+        AttributeValue::S(_) => "S",
+        AttributeValue::N(_) => "N",
+        AttributeValue::B(_) => "B",
+        AttributeValue::Bool(_) => "BOOL",
+        AttributeValue::Null(_) => "NULL",
+        AttributeValue::Ss(_) => "SS",
+        AttributeValue::Ns(_) => "NS",
+        AttributeValue::Bs(_) => "BS",
+        AttributeValue::L(_) => "L",
+        AttributeValue::M(_) => "M",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Evaluates the condition against the item.
+pub(super) fn evaluate(condition: &Condition, item: &Item) -> bool {
+    let value = |operand| operand_value(item, operand);
+
+    match condition {
+        Condition::Compare(a, comparator, b) => {
+            let (Some(a), Some(b)) = (value(a), value(b)) else {
+                return false;
+            };
+            match comparator {
+                Comparator::Eq => equals(&a, &b),
+                Comparator::Ne => !equals(&a, &b),
+                Comparator::Lt => compare(&a, &b) == Some(Ordering::Less),
+                Comparator::Le => compare(&a, &b).is_some_and(Ordering::is_le),
+                Comparator::Gt => compare(&a, &b) == Some(Ordering::Greater),
+                Comparator::Ge => compare(&a, &b).is_some_and(Ordering::is_ge),
+            }
+        }
+        Condition::Between(v, low, high) => {
+            let (Some(v), Some(low), Some(high)) = (value(v), value(low), value(high)) else {
+                return false;
+            };
+            compare(&low, &v).is_some_and(Ordering::is_le)
+                && compare(&v, &high).is_some_and(Ordering::is_le)
+        }
+        Condition::In(v, candidates) => value(v).is_some_and(|v| {
+            candidates
+                .iter()
+                .any(|c| value(c).is_some_and(|c| equals(&v, &c)))
+        }),
+        Condition::Exists(path) => resolve(item, path).is_some(),
+        Condition::NotExists(path) => resolve(item, path).is_none(),
+        Condition::Type(path, t) => match (resolve(item, path), value(t)) {
+            (Some(v), Some(t)) => t.as_s().is_ok_and(|t| t == type_name(v)),
+            _ => false,
+        },
+        Condition::BeginsWith(v, prefix) => match (value(v).as_deref(), value(prefix).as_deref()) {
+            (Some(AttributeValue::S(v)), Some(AttributeValue::S(p))) => v.starts_with(p.as_str()),
+            (Some(AttributeValue::B(v)), Some(AttributeValue::B(p))) => {
+                v.as_ref().starts_with(p.as_ref())
+            }
+            _ => false,
+        },
+        Condition::Contains(v, operand) => match (value(v).as_deref(), value(operand).as_deref()) {
+            (Some(AttributeValue::S(v)), Some(AttributeValue::S(o))) => v.contains(o.as_str()),
+            (Some(AttributeValue::B(v)), Some(AttributeValue::B(o))) => v
+                .as_ref()
+                .windows(o.as_ref().len().max(1))
+                .any(|w| w == o.as_ref()),
+            (Some(AttributeValue::Ss(v)), Some(AttributeValue::S(o))) => v.contains(o),
+            (Some(AttributeValue::Ns(v)), Some(o @ AttributeValue::N(_))) => {
+                v.iter().any(|n| equals(&AttributeValue::N(n.clone()), o))
+            }
+            (Some(AttributeValue::Bs(v)), Some(AttributeValue::B(o))) => v.contains(o),
+            (Some(AttributeValue::L(v)), Some(o)) => v.iter().any(|e| equals(e, o)),
+            _ => false,
+        },
+        Condition::And(a, b) => evaluate(a, item) && evaluate(b, item),
+        Condition::Or(a, b) => evaluate(a, item) || evaluate(b, item),
+        Condition::Not(c) => !evaluate(c, item),
+    }
+}
+
+/// Returns the top-level attribute names the condition refers to.
+pub(super) fn attribute_names(condition: &Condition) -> Vec<&str> {
+    fn path_name(path: &Path) -> Option<&str> {
+        match path.first() {
+            Some(PathElement::Attribute(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn operand_names<'a>(operand: &'a Operand, names: &mut Vec<&'a str>) {
+        match operand {
+            Operand::Path(path) | Operand::Size(path) => names.extend(path_name(path)),
+            Operand::Value(_) => {}
+        }
+    }
+
+    fn collect<'a>(condition: &'a Condition, names: &mut Vec<&'a str>) {
+        match condition {
+            Condition::Compare(a, _, b)
+            | Condition::BeginsWith(a, b)
+            | Condition::Contains(a, b) => {
+                operand_names(a, names);
+                operand_names(b, names);
+            }
+            Condition::Between(a, b, c) => {
+                for operand in [a, b, c] {
+                    operand_names(operand, names);
+                }
+            }
+            Condition::In(a, operands) => {
+                operand_names(a, names);
+                for operand in operands {
+                    operand_names(operand, names);
+                }
+            }
+            Condition::Exists(path) | Condition::NotExists(path) => {
+                names.extend(path_name(path));
+            }
+            Condition::Type(path, operand) => {
+                names.extend(path_name(path));
+                operand_names(operand, names);
+            }
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                collect(a, names);
+                collect(b, names);
+            }
+            Condition::Not(c) => collect(c, names),
+        }
+    }
+
+    let mut names = vec![];
+    collect(condition, &mut names);
+    names
+}
+
+impl Update {
+    /// Returns the top-level attribute names the update expression modifies.
+    pub(super) fn modified_attributes(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().filter_map(|action| {
+            let path = match action {
+                UpdateAction::Set(path, _)
+                | UpdateAction::Remove(path)
+                | UpdateAction::Add(path, _)
+                | UpdateAction::Delete(path, _) => path,
+            };
+            match path.first() {
+                Some(PathElement::Attribute(name)) => Some(name.as_str()),
+                _ => None,
+            }
+        })
+    }
+
+    /// Applies the update expression to the item.
+    ///
+    /// All operands are evaluated against the item before the update, like DynamoDB does.
+    pub(super) fn apply(&self, item: &mut Item) -> Result<(), String> {
+        let original = item.clone();
+
+        for action in &self.actions {
+            match action {
+                UpdateAction::Set(path, value) => {
+                    let value = set_value(&original, value)?;
+                    set_path(item, path, value)?;
+                }
+                UpdateAction::Remove(path) => remove_path(item, path),
+                UpdateAction::Add(path, value) => {
+                    let value = match (resolve(&original, path), value) {
+                        (
+                            None,
+                            value @ (AttributeValue::N(_)
+                            | AttributeValue::Ss(_)
+                            | AttributeValue::Ns(_)
+                            | AttributeValue::Bs(_)),
+                        ) => value.clone(),
+                        (Some(AttributeValue::N(a)), AttributeValue::N(b)) => {
+                            AttributeValue::N(add(a, b, false)?)
+                        }
+                        (Some(current), value) => union(current, value)?,
+                        (None, _) => {
+                            return Err("ADD only supports numbers and sets".into());
+                        }
+                    };
+                    set_path(item, path, value)?;
+                }
+                UpdateAction::Delete(path, value) => {
+                    let Some(current) = resolve(&original, path) else {
+                        continue;
+                    };
+                    match difference(current, value)? {
+                        Some(value) => set_path(item, path, value)?,
+                        None => remove_path(item, path),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn add(a: &str, b: &str, subtract: bool) -> Result<String, String> {
+    let a = Decimal::parse(a)?;
+    let b = Decimal::parse(b)?;
+    let b = if subtract { b.neg() } else { b };
+    a.checked_add(b).map(|v| v.to_string())
+}
+
+fn set_operand(item: &Item, operand: &SetOperand) -> Result<AttributeValue, String> {
+    match operand {
+        SetOperand::Path(path) => resolve(item, path).cloned().ok_or_else(|| {
+            "the provided expression refers to an attribute that does not exist in the item".into()
+        }),
+        SetOperand::Value(value) => Ok(value.clone()),
+        SetOperand::IfNotExists(path, operand) => match resolve(item, path) {
+            Some(value) => Ok(value.clone()),
+            None => set_operand(item, operand),
+        },
+        SetOperand::ListAppend(a, b) => match (set_operand(item, a)?, set_operand(item, b)?) {
+            (AttributeValue::L(mut a), AttributeValue::L(b)) => {
+                a.extend(b);
+                Ok(AttributeValue::L(a))
+            }
+            _ => Err("list_append only supports lists".into()),
+        },
+    }
+}
+
+fn set_value(item: &Item, value: &SetValue) -> Result<AttributeValue, String> {
+    match value {
+        SetValue::Operand(operand) => set_operand(item, operand),
+        SetValue::Plus(a, b) | SetValue::Minus(a, b) => {
+            match (set_operand(item, a)?, set_operand(item, b)?) {
+                (AttributeValue::N(a), AttributeValue::N(b)) => Ok(AttributeValue::N(add(
+                    &a,
+                    &b,
+                    matches!(value, SetValue::Minus(..)),
+                )?)),
+                _ => Err("arithmetic operators only support numbers".into()),
+            }
+        }
+    }
+}
+
+fn union(current: &AttributeValue, value: &AttributeValue) -> Result<AttributeValue, String> {
+    fn merge<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+        let mut merged = a.to_vec();
+        merged.extend(b.iter().filter(|v| !a.contains(v)).cloned());
+        merged
+    }
+
+    match (current, value) {
+        (AttributeValue::Ss(a), AttributeValue::Ss(b)) => Ok(AttributeValue::Ss(merge(a, b))),
+        (AttributeValue::Ns(a), AttributeValue::Ns(b)) => Ok(AttributeValue::Ns(merge(a, b))),
+        (AttributeValue::Bs(a), AttributeValue::Bs(b)) => Ok(AttributeValue::Bs(merge(a, b))),
+        _ => Err("an operand in the update expression has an incorrect data type".into()),
+    }
+}
+
+fn difference(
+    current: &AttributeValue,
+    value: &AttributeValue,
+) -> Result<Option<AttributeValue>, String> {
+    fn remove<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+        a.iter().filter(|v| !b.contains(v)).cloned().collect()
+    }
+
+    let result = match (current, value) {
+        (AttributeValue::Ss(a), AttributeValue::Ss(b)) => AttributeValue::Ss(remove(a, b)),
+        (AttributeValue::Ns(a), AttributeValue::Ns(b)) => AttributeValue::Ns(remove(a, b)),
+        (AttributeValue::Bs(a), AttributeValue::Bs(b)) => AttributeValue::Bs(remove::<Blob>(a, b)),
+        _ => return Err("DELETE only supports sets".into()),
+    };
+
+    // DynamoDB doesn't store empty sets.
+    let empty = match &result {
+        AttributeValue::Ss(v) | AttributeValue::Ns(v) => v.is_empty(),
+        AttributeValue::Bs(v) => v.is_empty(),
+        _ => false,
+    };
+    Ok(Some(result).filter(|_| !empty))
+}
+
+fn set_path(item: &mut Item, path: &[PathElement], value: AttributeValue) -> Result<(), String> {
+    let invalid = || String::from("the document path provided in the update expression is invalid");
+
+    let Some((last, parents)) = path.split_last() else {
+        return Err(invalid());
+    };
+    let Some((first, parents)) = parents.split_first() else {
+        return match last {
+            PathElement::Attribute(name) => {
+                item.insert(name.clone(), value);
+                Ok(())
+            }
+            PathElement::Index(_) => Err(invalid()),
+        };
+    };
+
+    let mut current = match first {
+        PathElement::Attribute(name) => item.get_mut(name).ok_or_else(invalid)?,
+        PathElement::Index(_) => return Err(invalid()),
+    };
+    for element in parents {
+        current = match (element, current) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => {
+                map.get_mut(name).ok_or_else(invalid)?
+            }
+            (PathElement::Index(i), AttributeValue::L(list)) => {
+                list.get_mut(*i).ok_or_else(invalid)?
+            }
+            _ => return Err(invalid()),
+        };
+    }
+
+    match (last, current) {
+        (PathElement::Attribute(name), AttributeValue::M(map)) => {
+            map.insert(name.clone(), value);
+        }
+        (PathElement::Index(i), AttributeValue::L(list)) => {
+            if *i < list.len() {
+                list[*i] = value;
+            } else {
+                list.push(value);
+            }
+        }
+        _ => return Err(invalid()),
+    }
+    Ok(())
+}
+
+fn remove_path(item: &mut Item, path: &[PathElement]) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let Some((first, parents)) = parents.split_first() else {
+        if let PathElement::Attribute(name) = last {
+            item.remove(name);
+        }
+        return;
+    };
+
+    let PathElement::Attribute(name) = first else {
+        return;
+    };
+    let Some(mut current) = item.get_mut(name) else {
+        return;
+    };
+    for element in parents {
+        let next = match (element, current) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => map.get_mut(name),
+            (PathElement::Index(i), AttributeValue::L(list)) => list.get_mut(*i),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return;
+        };
+        current = next;
+    }
+
+    match (last, current) {
+        (PathElement::Attribute(name), AttributeValue::M(map)) => {
+            map.remove(name);
+        }
+        (PathElement::Index(i), AttributeValue::L(list)) if *i < list.len() => {
+            list.remove(*i);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> Item {
+        [
+            ("PK".to_string(), AttributeValue::S("video#1".into())),
+            ("Views".to_string(), AttributeValue::N("10".into())),
+            (
+                "Tags".to_string(),
+                AttributeValue::Ss(vec!["rust".into(), "aws".into()]),
+            ),
+            (
+                "Comments".to_string(),
+                AttributeValue::L(vec![
+                    AttributeValue::S("first".into()),
+                    AttributeValue::S("second".into()),
+                ]),
+            ),
+            (
+                "Author".to_string(),
+                AttributeValue::M([("Name".to_string(), AttributeValue::S("Kanji".into()))].into()),
+            ),
+        ]
+        .into()
+    }
+
+    fn check(expression: &str, values: &[(&str, AttributeValue)]) -> bool {
+        let names: HashMap<String, String> = [("#n".to_string(), "Name".to_string())].into();
+        let values: HashMap<String, AttributeValue> = values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        let placeholders = Placeholders::new(&names, &values);
+        let condition = parse_condition(expression, &placeholders).unwrap();
+        evaluate(&condition, &item())
+    }
+
+    fn n(v: &str) -> AttributeValue {
+        AttributeValue::N(v.into())
+    }
+
+    fn s(v: &str) -> AttributeValue {
+        AttributeValue::S(v.into())
+    }
+
+    #[test]
+    fn comparisons_are_evaluated() {
+        assert!(check("Views = :v", &[(":v", n("10.0"))]));
+        assert!(check("Views <> :v", &[(":v", n("11"))]));
+        assert!(check("Views < :v", &[(":v", n("11"))]));
+        assert!(check("Views >= :v", &[(":v", n("10"))]));
+        assert!(!check("Views > :v", &[(":v", n("10"))]));
+        assert!(!check("Views = :v", &[(":v", s("10"))]));
+        assert!(check(
+            "Views BETWEEN :a AND :b",
+            &[(":a", n("1")), (":b", n("10"))]
+        ));
+        assert!(check(
+            "PK IN (:a, :b)",
+            &[(":a", s("x")), (":b", s("video#1"))]
+        ));
+        assert!(!check("Missing = :v", &[(":v", n("1"))]));
+    }
+
+    #[test]
+    fn functions_are_evaluated() {
+        assert!(check("attribute_exists(Author.#n)", &[]));
+        assert!(check("attribute_not_exists(Author.Age)", &[]));
+        assert!(check("attribute_type(Tags, :t)", &[(":t", s("SS"))]));
+        assert!(check("begins_with(PK, :p)", &[(":p", s("video#"))]));
+        assert!(check("contains(Tags, :t)", &[(":t", s("rust"))]));
+        assert!(check("contains(Comments[1], :t)", &[(":t", s("eco"))]));
+        assert!(check("size(Comments) = :two", &[(":two", n("2"))]));
+        assert!(check("size(PK) > :five", &[(":five", n("5"))]));
+    }
+
+    #[test]
+    fn logical_operators_are_evaluated_with_precedence() {
+        let values = [(":a", n("10")), (":b", n("0"))];
+        assert!(check(
+            "Views = :b OR Views = :a AND attribute_exists(PK)",
+            &values
+        ));
+        assert!(!check(
+            "(Views = :b OR Views = :a) AND attribute_not_exists(PK)",
+            &values
+        ));
+        assert!(check("NOT Views = :b", &values));
+        assert!(check("not Views = :b and Views = :a", &values));
+    }
+
+    #[test]
+    fn undefined_and_unused_placeholders_are_errors() {
+        let names = HashMap::new();
+        let values: HashMap<String, AttributeValue> =
+            [(":a".to_string(), n("1")), (":unused".to_string(), n("2"))].into();
+
+        let placeholders = Placeholders::new(&names, &values);
+        assert!(parse_condition("#x = :a", &placeholders).is_err());
+
+        let placeholders = Placeholders::new(&names, &values);
+        parse_condition("Views = :a", &placeholders).unwrap();
+        assert!(placeholders.finish().is_err());
+    }
+
+    #[test]
+    fn malformed_expressions_are_errors() {
+        let names = HashMap::new();
+        let values: HashMap<String, AttributeValue> = [(":a".to_string(), n("1"))].into();
+        let placeholders = Placeholders::new(&names, &values);
+
+        for expression in [
+            "Views =",
+            "Views = :a AND",
+            "(Views = :a",
+            "Views :a",
+            "Views = :a)",
+        ] {
+            assert!(
+                parse_condition(expression, &placeholders).is_err(),
+                "{expression} should be an error"
+            );
+        }
+    }
+
+    fn update(expression: &str, values: &[(&str, AttributeValue)]) -> Result<Item, String> {
+        let names: HashMap<String, String> = [("#n".to_string(), "Name".to_string())].into();
+        let values: HashMap<String, AttributeValue> = values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        let placeholders = Placeholders::new(&names, &values);
+        let update = parse_update(expression, &placeholders)?;
+        let mut item = item();
+        update.apply(&mut item)?;
+        Ok(item)
+    }
+
+    #[test]
+    fn set_actions_are_applied() {
+        let item = update(
+            "SET Views = Views + :one, Title = :t, Author.#n = :name, Comments[5] = :c",
+            &[
+                (":one", n("1")),
+                (":t", s("Rust")),
+                (":name", s("Tanaka")),
+                (":c", s("third")),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(item["Views"], n("11"));
+        assert_eq!(item["Title"], s("Rust"));
+        assert_eq!(
+            resolve(
+                &item,
+                &[
+                    PathElement::Attribute("Author".into()),
+                    PathElement::Attribute("Name".into()),
+                ]
+            ),
+            Some(&s("Tanaka"))
+        );
+        assert_eq!(item["Comments"].as_l().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn set_functions_are_applied() {
+        let item = update(
+            "SET Created = if_not_exists(Created, :now), Views = if_not_exists(Views, :zero), Comments = list_append(Comments, :more)",
+            &[
+                (":now", s("2024-01-01")),
+                (":zero", n("0")),
+                (":more", AttributeValue::L(vec![s("third")])),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(item["Created"], s("2024-01-01"));
+        assert_eq!(item["Views"], n("10"));
+        assert_eq!(item["Comments"].as_l().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn remove_add_and_delete_actions_are_applied() {
+        let item = update(
+            "REMOVE Comments[0], Author ADD Views :two, Likes :one, Tags :new DELETE Tags :old",
+            &[
+                (":two", n("2")),
+                (":one", n("1")),
+                (":new", AttributeValue::Ss(vec!["dynamodb".into()])),
+                (":old", AttributeValue::Ss(vec!["aws".into()])),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(item["Comments"], AttributeValue::L(vec![s("second")]));
+        assert!(!item.contains_key("Author"));
+        assert_eq!(item["Views"], n("12"));
+        assert_eq!(item["Likes"], n("1"));
+        // Operands are evaluated against the original item, so `DELETE` removes from the
+        // original set and overwrites the result of `ADD`.
+        assert_eq!(item["Tags"], AttributeValue::Ss(vec!["rust".into()]));
+    }
+
+    #[test]
+    fn invalid_updates_are_errors() {
+        assert!(update("SET Missing.Child = :v", &[(":v", n("1"))]).is_err());
+        assert!(update("SET PK = PK + :v", &[(":v", n("1"))]).is_err());
+        assert!(update("SET A = :v SET B = :v", &[(":v", n("1"))]).is_err());
+        assert!(update("ADD PK :v", &[(":v", n("1"))]).is_err());
+    }
+}
@@ -0,0 +1,803 @@
+//! An in-memory [`Table`] for tests.
+//!
+//! [`MemoryTable`] keeps the items in memory and evaluates key conditions, filters, condition
+//! expressions and update expressions like DynamoDB does, so that code written against the
+//! [`Table`] trait can be tested without DynamoDB Local or network access.
+//!
+//! ```rust
+//! use dynamodel::{table::{Query, Table, TableError}, testing::MemoryTable, Expression, KeySchema};
+//! # use aws_sdk_dynamodb::types::AttributeValue;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let table = MemoryTable::new(KeySchema::new("PK").with_sort_key("SK"));
+//!
+//! let item = [
+//!     ("PK".to_string(), AttributeValue::S("video#1".into())),
+//!     ("SK".to_string(), AttributeValue::S("comment#1".into())),
+//! ]
+//! .into();
+//! let condition = Expression::condition("attribute_not_exists(PK)");
+//! table.put_item(item, Some(condition.clone())).await.unwrap();
+//!
+//! // The same item can't be put twice.
+//! let item = [
+//!     ("PK".to_string(), AttributeValue::S("video#1".into())),
+//!     ("SK".to_string(), AttributeValue::S("comment#1".into())),
+//! ]
+//! .into();
+//! let err = table.put_item(item, Some(condition)).await.unwrap_err();
+//! assert!(matches!(err, TableError::ConditionalCheckFailed));
+//!
+//! let query = Query::new(
+//!     Expression::condition("PK = :pk AND begins_with(SK, :prefix)")
+//!         .value(":pk", "video#1".to_string())
+//!         .value(":prefix", "comment#".to_string()),
+//! );
+//! assert_eq!(table.query(query).await.unwrap().items.len(), 1);
+//! # }
+//! ```
+//!
+//! Only the item operations are supported. Capacity, item size limits and the 1MB page size
+//! are not emulated.
+
+mod expr;
+mod number;
+
+use crate::{
+    Expression, KeySchema,
+    table::{Item, Page, Query, Scan, Table, TableError},
+};
+use aws_sdk_dynamodb::types::AttributeValue;
+use expr::{Comparator, Condition, Operand, PathElement, Placeholders};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+/// A [`Table`] that keeps the items in memory.
+///
+/// The table can have secondary indexes. Like DynamoDB, the indexes are sparse: items without
+/// the key attributes of an index don't appear in it.
+#[derive(Debug)]
+pub struct MemoryTable {
+    key_schema: KeySchema,
+    indexes: HashMap<String, KeySchema>,
+    items: Mutex<Vec<Item>>,
+}
+
+impl MemoryTable {
+    /// Creates an empty table with the key schema.
+    pub fn new(key_schema: KeySchema) -> Self {
+        Self {
+            key_schema,
+            indexes: HashMap::new(),
+            items: Mutex::new(vec![]),
+        }
+    }
+
+    /// Adds a secondary index.
+    pub fn with_index(mut self, index_name: impl Into<String>, key_schema: KeySchema) -> Self {
+        self.indexes.insert(index_name.into(), key_schema);
+        self
+    }
+
+    /// Puts the items without any validation, which is useful to prepare test data.
+    pub fn with_items(self, items: impl IntoIterator<Item = Item>) -> Self {
+        self.lock().extend(items);
+        self
+    }
+
+    /// The key schema of the table.
+    pub fn key_schema(&self) -> KeySchema {
+        self.key_schema
+    }
+
+    /// All the items in the table, in the order of the table keys.
+    pub fn items(&self) -> Vec<Item> {
+        let mut items = self.lock().clone();
+        let keys: Vec<&str> = self.key_schema.attribute_names().collect();
+        items.sort_by(|a, b| compare_by(a, b, &keys));
+        items
+    }
+
+    /// The number of the items in the table.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if the table has no items.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all the items.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Item>> {
+        // A panic in a test must not poison the other operations.
+        self.items.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn index(&self, index_name: Option<&str>) -> Result<KeySchema, TableError> {
+        match index_name {
+            Some(name) => {
+                self.indexes.get(name).copied().ok_or_else(|| {
+                    validation(format!("the table does not have the index `{name}`"))
+                })
+            }
+            None => Ok(self.key_schema),
+        }
+    }
+
+    fn validate_key(&self, key: &Item) -> Result<(), TableError> {
+        if key.len() != self.key_schema.attribute_names().count() {
+            return Err(validation(
+                "the provided key element does not match the schema",
+            ));
+        }
+        self.validate_key_attributes(key)
+    }
+
+    fn validate_key_attributes(&self, item: &Item) -> Result<(), TableError> {
+        for name in self.key_schema.attribute_names() {
+            match item.get(name) {
+                Some(value) if is_key_type(value) => {}
+                Some(_) => {
+                    return Err(validation(format!(
+                        "the key attribute `{name}` must be a string, a number or a binary"
+                    )));
+                }
+                None => return Err(validation(format!("missing the key attribute `{name}`"))),
+            }
+        }
+
+        // Index key attributes are optional, but must have a valid type.
+        for (index, schema) in &self.indexes {
+            for name in schema.attribute_names() {
+                if item.get(name).is_some_and(|v| !is_key_type(v)) {
+                    return Err(validation(format!(
+                        "the key attribute `{name}` of the index `{index}` must be a string, a number or a binary"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self, items: &[Item], key: &Item) -> Option<usize> {
+        items.iter().position(|item| {
+            self.key_schema.attribute_names().all(|name| {
+                matches!((item.get(name), key.get(name)), (Some(a), Some(b)) if expr::equals(a, b))
+            })
+        })
+    }
+
+    // Returns the sorted items of the table or the index, after the exclusive start key.
+    fn read(
+        &self,
+        items: &[Item],
+        schema: KeySchema,
+        scan_forward: bool,
+        exclusive_start_key: Option<&Item>,
+    ) -> Vec<Item> {
+        let order: Vec<&str> = schema
+            .attribute_names()
+            .chain(self.key_schema.attribute_names())
+            .collect();
+        let compare = |a: &Item, b: &Item| {
+            let ordering = compare_by(a, b, &order);
+            if scan_forward {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        };
+
+        let mut items: Vec<Item> = items
+            .iter()
+            .filter(|item| schema.attribute_names().all(|name| item.contains_key(name)))
+            .filter(|item| exclusive_start_key.is_none_or(|key| compare(item, key).is_gt()))
+            .cloned()
+            .collect();
+        items.sort_by(compare);
+        items
+    }
+
+    // Takes a page of the items and filters them.
+    fn page(
+        &self,
+        items: Vec<Item>,
+        schema: KeySchema,
+        limit: Option<usize>,
+        filter: Option<&Condition>,
+    ) -> Page {
+        let more = limit.is_some_and(|limit| items.len() > limit);
+        let mut items: Vec<Item> = items
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+
+        let last_evaluated_key = items.last().filter(|_| more).map(|item| {
+            schema
+                .attribute_names()
+                .chain(self.key_schema.attribute_names())
+                .filter_map(|name| item.get(name).map(|v| (name.to_string(), v.clone())))
+                .collect()
+        });
+
+        if let Some(filter) = filter {
+            items.retain(|item| expr::evaluate(filter, item));
+        }
+
+        Page {
+            items,
+            last_evaluated_key,
+        }
+    }
+}
+
+impl Table for MemoryTable {
+    async fn get_item(&self, key: Item) -> Result<Option<Item>, TableError> {
+        self.validate_key(&key)?;
+        let items = self.lock();
+        Ok(self.position(&items, &key).map(|i| items[i].clone()))
+    }
+
+    async fn put_item(&self, item: Item, condition: Option<Expression>) -> Result<(), TableError> {
+        self.validate_key_attributes(&item)?;
+        let mut items = self.lock();
+        let position = self.position(&items, &item);
+
+        if let Some(condition) = condition {
+            check_condition(&condition, position.map(|i| &items[i]))?;
+        }
+
+        match position {
+            Some(i) => items[i] = item,
+            None => items.push(item),
+        }
+        Ok(())
+    }
+
+    async fn delete_item(
+        &self,
+        key: Item,
+        condition: Option<Expression>,
+    ) -> Result<(), TableError> {
+        self.validate_key(&key)?;
+        let mut items = self.lock();
+        let position = self.position(&items, &key);
+
+        if let Some(condition) = condition {
+            check_condition(&condition, position.map(|i| &items[i]))?;
+        }
+
+        if let Some(i) = position {
+            items.remove(i);
+        }
+        Ok(())
+    }
+
+    async fn update_item(&self, key: Item, expression: Expression) -> Result<Item, TableError> {
+        self.validate_key(&key)?;
+
+        let placeholders = Placeholders::new(expression.names(), expression.values());
+        let update = expression
+            .update_expression()
+            .ok_or_else(|| validation("the update expression is missing"))
+            .and_then(|update| expr::parse_update(update, &placeholders).map_err(validation))?;
+        let condition = expression
+            .condition_expression()
+            .map(|condition| expr::parse_condition(condition, &placeholders))
+            .transpose()
+            .map_err(validation)?;
+        placeholders.finish().map_err(validation)?;
+
+        if let Some(name) = update
+            .modified_attributes()
+            .find(|name| self.key_schema.contains(name))
+        {
+            return Err(validation(format!(
+                "cannot update the key attribute `{name}`"
+            )));
+        }
+
+        let mut items = self.lock();
+        let position = self.position(&items, &key);
+
+        if let Some(condition) = condition {
+            let empty = Item::new();
+            let current = position.map_or(&empty, |i| &items[i]);
+            if !expr::evaluate(&condition, current) {
+                return Err(TableError::ConditionalCheckFailed);
+            }
+        }
+
+        let mut item = position.map_or(key, |i| items[i].clone());
+        update.apply(&mut item).map_err(validation)?;
+        self.validate_key_attributes(&item)?;
+
+        match position {
+            Some(i) => items[i] = item.clone(),
+            None => items.push(item.clone()),
+        }
+        Ok(item)
+    }
+
+    async fn query(&self, query: Query) -> Result<Page, TableError> {
+        let schema = self.index(query.index_name.as_deref())?;
+        if query.limit == Some(0) {
+            return Err(validation("the limit must be greater than 0"));
+        }
+
+        // The key condition and the filter share the placeholders.
+        let filter = query.filter.unwrap_or_default();
+        let mut names = query.key_condition.names().clone();
+        names.extend(filter.names().clone());
+        let mut values = query.key_condition.values().clone();
+        values.extend(filter.values().clone());
+        let placeholders = Placeholders::new(&names, &values);
+
+        let key_condition = query
+            .key_condition
+            .condition_expression()
+            .ok_or_else(|| validation("the key condition expression is missing"))
+            .and_then(|expression| {
+                expr::parse_condition(expression, &placeholders).map_err(validation)
+            })?;
+        validate_key_condition(&key_condition, schema)?;
+
+        let filter = filter
+            .condition_expression()
+            .map(|expression| expr::parse_condition(expression, &placeholders))
+            .transpose()
+            .map_err(validation)?;
+        placeholders.finish().map_err(validation)?;
+
+        if let Some(name) = filter
+            .iter()
+            .flat_map(expr::attribute_names)
+            .find(|name| schema.contains(name))
+        {
+            return Err(validation(format!(
+                "the filter expression can only contain non-primary key attributes: `{name}`"
+            )));
+        }
+
+        let items = self.lock();
+        let items = self.read(
+            &items,
+            schema,
+            query.scan_forward,
+            query.exclusive_start_key.as_ref(),
+        );
+        let items = items
+            .into_iter()
+            .filter(|item| expr::evaluate(&key_condition, item))
+            .collect();
+
+        Ok(self.page(items, schema, query.limit, filter.as_ref()))
+    }
+
+    async fn scan(&self, scan: Scan) -> Result<Page, TableError> {
+        let schema = self.index(scan.index_name.as_deref())?;
+        if scan.limit == Some(0) {
+            return Err(validation("the limit must be greater than 0"));
+        }
+
+        let filter = scan.filter.unwrap_or_default();
+        let placeholders = Placeholders::new(filter.names(), filter.values());
+        let condition = filter
+            .condition_expression()
+            .map(|expression| expr::parse_condition(expression, &placeholders))
+            .transpose()
+            .map_err(validation)?;
+        placeholders.finish().map_err(validation)?;
+
+        let items = self.lock();
+        let items = self.read(&items, schema, true, scan.exclusive_start_key.as_ref());
+
+        Ok(self.page(items, schema, scan.limit, condition.as_ref()))
+    }
+}
+
+fn validation(message: impl Into<String>) -> TableError {
+    TableError::Validation(message.into())
+}
+
+fn is_key_type(value: &AttributeValue) -> bool {
+    matches!(
+        value,
+        AttributeValue::S(_) | AttributeValue::N(_) | AttributeValue::B(_)
+    )
+}
+
+// Compares the items by the attributes in order.
+fn compare_by(a: &Item, b: &Item, names: &[&str]) -> Ordering {
+    names
+        .iter()
+        .map(|name| match (a.get(*name), b.get(*name)) {
+            (Some(a), Some(b)) => expr::compare(a, b).unwrap_or(Ordering::Equal),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn check_condition(expression: &Expression, item: Option<&Item>) -> Result<(), TableError> {
+    if expression.update_expression().is_some() {
+        return Err(validation(
+            "an update expression is not allowed in this request",
+        ));
+    }
+
+    let placeholders = Placeholders::new(expression.names(), expression.values());
+    let condition = expression
+        .condition_expression()
+        .ok_or_else(|| validation("the condition expression is missing"))
+        .and_then(|condition| {
+            expr::parse_condition(condition, &placeholders).map_err(validation)
+        })?;
+    placeholders.finish().map_err(validation)?;
+
+    let empty = Item::new();
+    if expr::evaluate(&condition, item.unwrap_or(&empty)) {
+        Ok(())
+    } else {
+        Err(TableError::ConditionalCheckFailed)
+    }
+}
+
+// A key condition is `pk = :v`, optionally with a condition on the sort key joined by `AND`.
+fn validate_key_condition(condition: &Condition, schema: KeySchema) -> Result<(), TableError> {
+    let invalid = || validation("the key condition expression is invalid");
+
+    let is_key = |operand: &Operand, name: &str| matches!(operand, Operand::Path(path) if path.as_slice() == [PathElement::Attribute(name.into())]);
+    let is_value = |operand: &Operand| matches!(operand, Operand::Value(_));
+
+    let is_partition_key_condition = |condition: &Condition| {
+        matches!(condition, Condition::Compare(a, Comparator::Eq, b)
+            if is_key(a, schema.partition_key()) && is_value(b))
+    };
+    let is_sort_key_condition = |condition: &Condition| {
+        let Some(sort_key) = schema.sort_key() else {
+            return false;
+        };
+        match condition {
+            Condition::Compare(a, comparator, b) => {
+                *comparator != Comparator::Ne && is_key(a, sort_key) && is_value(b)
+            }
+            Condition::Between(a, low, high) => {
+                is_key(a, sort_key) && is_value(low) && is_value(high)
+            }
+            Condition::BeginsWith(a, prefix) => is_key(a, sort_key) && is_value(prefix),
+            _ => false,
+        }
+    };
+
+    match condition {
+        c if is_partition_key_condition(c) => Ok(()),
+        Condition::And(a, b)
+            if is_partition_key_condition(a) && is_sort_key_condition(b)
+                || is_partition_key_condition(b) && is_sort_key_condition(a) =>
+        {
+            Ok(())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: KeySchema = KeySchema::new("PK").with_sort_key("SK");
+    const GSI: KeySchema = KeySchema::new("GSI1PK").with_sort_key("GSI1SK");
+
+    fn s(v: &str) -> AttributeValue {
+        AttributeValue::S(v.into())
+    }
+
+    fn n(v: &str) -> AttributeValue {
+        AttributeValue::N(v.into())
+    }
+
+    fn key(pk: &str, sk: &str) -> Item {
+        [("PK".to_string(), s(pk)), ("SK".to_string(), s(sk))].into()
+    }
+
+    fn comment(video: &str, number: usize, likes: &str) -> Item {
+        let mut item = key(video, &format!("comment#{number:02}"));
+        item.insert("Likes".into(), n(likes));
+        item.insert("GSI1PK".into(), s("comments"));
+        item.insert("GSI1SK".into(), n(likes));
+        item
+    }
+
+    fn table() -> MemoryTable {
+        MemoryTable::new(SCHEMA)
+            .with_index("GSI1", GSI)
+            .with_items([
+                comment("video#1", 1, "5"),
+                comment("video#1", 2, "10"),
+                comment("video#1", 3, "1"),
+                comment("video#2", 1, "7"),
+                key("video#1", "metadata"),
+            ])
+    }
+
+    fn sort_keys(page: &Page) -> Vec<&str> {
+        page.items
+            .iter()
+            .map(|item| item["SK"].as_s().unwrap().as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn items_can_be_put_got_and_deleted() {
+        let table = MemoryTable::new(SCHEMA);
+        let mut item = key("video#1", "metadata");
+        item.insert("Title".into(), s("Rust"));
+
+        table.put_item(item.clone(), None).await.unwrap();
+        assert_eq!(
+            table.get_item(key("video#1", "metadata")).await.unwrap(),
+            Some(item)
+        );
+
+        table
+            .delete_item(key("video#1", "metadata"), None)
+            .await
+            .unwrap();
+        assert!(table.is_empty());
+        assert_eq!(
+            table.get_item(key("video#1", "metadata")).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn numeric_keys_are_compared_by_value() {
+        let table = MemoryTable::new(KeySchema::new("Id"));
+        let item: Item = [("Id".to_string(), n("1.0"))].into();
+        table.put_item(item, None).await.unwrap();
+
+        let key: Item = [("Id".to_string(), n("1"))].into();
+        assert!(table.get_item(key).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn invalid_keys_are_rejected() {
+        let table = MemoryTable::new(SCHEMA);
+
+        let partial: Item = [("PK".to_string(), s("video#1"))].into();
+        let err = table.get_item(partial).await.unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+
+        let mut extra = key("video#1", "metadata");
+        extra.insert("Title".into(), s("Rust"));
+        let err = table.get_item(extra).await.unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+
+        let mut wrong_type = key("video#1", "metadata");
+        wrong_type.insert("SK".into(), AttributeValue::Bool(true));
+        let err = table.put_item(wrong_type, None).await.unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn conditions_are_checked_on_write() {
+        let table = table();
+        let exists = Expression::condition("attribute_exists(PK)");
+
+        let err = table
+            .put_item(key("video#3", "metadata"), Some(exists.clone()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TableError::ConditionalCheckFailed));
+
+        let err = table
+            .delete_item(
+                key("video#1", "comment#01"),
+                Some(Expression::condition("Likes > :v").value(":v", 5)),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TableError::ConditionalCheckFailed));
+
+        table
+            .delete_item(key("video#1", "comment#01"), Some(exists))
+            .await
+            .unwrap();
+        assert_eq!(table.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn unused_placeholders_are_rejected() {
+        let table = table();
+        let condition = Expression::condition("attribute_exists(PK)").value(":unused", 1);
+
+        let err = table
+            .put_item(key("video#1", "metadata"), Some(condition))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn items_are_updated_and_created() {
+        let table = table();
+        let expression = Expression::update("SET #likes = #likes + :one")
+            .with_condition("attribute_exists(PK)")
+            .name("#likes", "Likes")
+            .value(":one", 1);
+
+        let item = table
+            .update_item(key("video#1", "comment#01"), expression)
+            .await
+            .unwrap();
+        assert_eq!(item["Likes"], n("6"));
+
+        let item = table
+            .update_item(
+                key("video#9", "metadata"),
+                Expression::update("ADD Views :one").value(":one", 1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(item, {
+            let mut item = key("video#9", "metadata");
+            item.insert("Views".into(), n("1"));
+            item
+        });
+        assert_eq!(table.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn key_attributes_cannot_be_updated() {
+        let table = table();
+        let err = table
+            .update_item(
+                key("video#1", "metadata"),
+                Expression::update("SET SK = :v").value(":v", "other".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn items_are_queried_by_sort_key_conditions() {
+        let table = table();
+        let query = |condition: &str| {
+            Query::new(
+                Expression::condition(condition)
+                    .value(":pk", "video#1".to_string())
+                    .value(":sk", "comment#02".to_string()),
+            )
+        };
+
+        let page = table.query(query("PK = :pk AND SK >= :sk")).await.unwrap();
+        assert_eq!(
+            sort_keys(&page),
+            vec!["comment#02", "comment#03", "metadata"]
+        );
+
+        let page = table
+            .query(query("begins_with(SK, :sk) AND PK = :pk"))
+            .await
+            .unwrap();
+        assert_eq!(sort_keys(&page), vec!["comment#02"]);
+
+        let page = table
+            .query(query("PK = :pk AND SK < :sk").scan_forward(false))
+            .await
+            .unwrap();
+        assert_eq!(sort_keys(&page), vec!["comment#01"]);
+    }
+
+    #[tokio::test]
+    async fn invalid_key_conditions_are_rejected() {
+        let table = table();
+        for condition in [
+            "SK = :v",
+            "PK > :v",
+            "PK = :v OR SK = :v",
+            "PK = :v AND SK <> :v",
+            "PK = :v AND Likes = :v",
+        ] {
+            let query = Query::new(Expression::condition(condition).value(":v", 1));
+            let err = table.query(query).await.unwrap_err();
+            assert!(
+                matches!(err, TableError::Validation(_)),
+                "{condition} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn queries_are_paginated_before_filtering() {
+        let table = table();
+        let query = Query::new(
+            Expression::condition("PK = :pk AND begins_with(SK, :prefix)")
+                .value(":pk", "video#1".to_string())
+                .value(":prefix", "comment#".to_string()),
+        )
+        .filter(Expression::condition("Likes >= :min").value(":min", 5))
+        .limit(2);
+
+        let page = table.query(query.clone()).await.unwrap();
+        assert_eq!(sort_keys(&page), vec!["comment#01", "comment#02"]);
+        let start = page.last_evaluated_key.unwrap();
+        assert_eq!(start, key("video#1", "comment#02"));
+
+        let page = table
+            .query(query.clone().exclusive_start_key(start))
+            .await
+            .unwrap();
+        // `comment#03` is evaluated but filtered out.
+        assert!(page.items.is_empty());
+        assert_eq!(page.last_evaluated_key, None);
+
+        let err = table
+            .query(query.filter(Expression::condition("SK = :sk").value(":sk", "x".to_string())))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn indexes_are_sparse_and_sorted_by_their_keys() {
+        let table = table();
+        let query =
+            Query::new(Expression::condition("GSI1PK = :pk").value(":pk", "comments".to_string()))
+                .index("GSI1")
+                .scan_forward(false)
+                .limit(3);
+
+        let page = table.query(query).await.unwrap();
+        let likes: Vec<&str> = page
+            .items
+            .iter()
+            .map(|item| item["Likes"].as_n().unwrap().as_str())
+            .collect();
+        assert_eq!(likes, vec!["10", "7", "5"]);
+
+        // The last evaluated key of an index has both the index and the table keys.
+        let mut expected = key("video#1", "comment#01");
+        expected.insert("GSI1PK".into(), s("comments"));
+        expected.insert("GSI1SK".into(), n("5"));
+        assert_eq!(page.last_evaluated_key, Some(expected));
+
+        let err = table.scan(Scan::new().index("GSI2")).await.unwrap_err();
+        assert!(matches!(err, TableError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn scans_return_every_page() {
+        let table = table();
+        let mut scan = Scan::new().limit(2);
+        let mut items = vec![];
+
+        loop {
+            let page = table.scan(scan.clone()).await.unwrap();
+            items.extend(page.items);
+            match page.last_evaluated_key {
+                Some(key) => scan = scan.exclusive_start_key(key),
+                None => break,
+            }
+        }
+
+        assert_eq!(items, table.items());
+
+        let page = table
+            .scan(Scan::new().filter(Expression::condition("Likes < :v").value(":v", 6)))
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 2);
+    }
+}
@@ -0,0 +1,219 @@
+use std::cmp::Ordering;
+
+// DynamoDB numbers have up to 38 significant digits.
+const MAX_DIGITS: u32 = 38;
+
+/// A decimal number with up to 38 significant digits, like DynamoDB's `N` type.
+///
+/// The value is `mantissa * 10^exp`, and the mantissa has no trailing zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Decimal {
+    negative: bool,
+    mantissa: u128,
+    exp: i32,
+}
+
+impl Decimal {
+    const ZERO: Self = Self {
+        negative: false,
+        mantissa: 0,
+        exp: 0,
+    };
+
+    pub(super) fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || format!("`{s}` is not a valid number");
+
+        let (negative, rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (number, exp) = match rest.find(['e', 'E']) {
+            Some(i) => (
+                &rest[..i],
+                rest[i + 1..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            None => (rest, 0),
+        };
+        let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+
+        if int.is_empty() && frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let digits = format!("{int}{frac}");
+        let digits = digits.trim_start_matches('0');
+        let trimmed = digits.trim_end_matches('0');
+        if trimmed.is_empty() {
+            return Ok(Self::ZERO);
+        }
+        if trimmed.len() > MAX_DIGITS as usize {
+            return Err(format!(
+                "`{s}` has more than {MAX_DIGITS} significant digits"
+            ));
+        }
+
+        let trailing_zeros = (digits.len() - trimmed.len()) as i32;
+        Ok(Self {
+            negative,
+            mantissa: trimmed.parse().map_err(|_| invalid())?,
+            exp: exp - frac.len() as i32 + trailing_zeros,
+        })
+    }
+
+    fn new(negative: bool, mut mantissa: u128, mut exp: i32) -> Self {
+        if mantissa == 0 {
+            return Self::ZERO;
+        }
+        while mantissa.is_multiple_of(10) {
+            mantissa /= 10;
+            exp += 1;
+        }
+        Self {
+            negative,
+            mantissa,
+            exp,
+        }
+    }
+
+    fn digits(&self) -> u32 {
+        self.mantissa.checked_ilog10().map_or(0, |v| v + 1)
+    }
+
+    fn cmp_magnitude(&self, other: &Self) -> Ordering {
+        if self.mantissa == 0 || other.mantissa == 0 {
+            return self.mantissa.cmp(&other.mantissa);
+        }
+
+        // Compare the position of the most significant digits first.
+        let a = self.digits() as i32 + self.exp;
+        let b = other.digits() as i32 + other.exp;
+        a.cmp(&b).then_with(|| {
+            // Then the mantissas, padded to the same number of digits.
+            let (da, db) = (self.digits(), other.digits());
+            let ma = self.mantissa * 10u128.pow(db.saturating_sub(da));
+            let mb = other.mantissa * 10u128.pow(da.saturating_sub(db));
+            ma.cmp(&mb)
+        })
+    }
+
+    pub(super) fn checked_add(self, other: Self) -> Result<Self, String> {
+        if self.mantissa == 0 {
+            return Ok(other);
+        }
+        if other.mantissa == 0 {
+            return Ok(self);
+        }
+
+        let overflow = || format!("the result has more than {MAX_DIGITS} significant digits");
+        let exp = self.exp.min(other.exp);
+        let align = |d: Self| {
+            10u128
+                .checked_pow((d.exp - exp) as u32)
+                .and_then(|p| d.mantissa.checked_mul(p))
+                .ok_or_else(overflow)
+        };
+        let (a, b) = (align(self)?, align(other)?);
+
+        let result = if self.negative == other.negative {
+            Self::new(self.negative, a.checked_add(b).ok_or_else(overflow)?, exp)
+        } else if a >= b {
+            Self::new(self.negative, a - b, exp)
+        } else {
+            Self::new(other.negative, b - a, exp)
+        };
+
+        if result.digits() > MAX_DIGITS {
+            return Err(overflow());
+        }
+        Ok(result)
+    }
+
+    pub(super) fn neg(self) -> Self {
+        Self {
+            negative: !self.negative && self.mantissa != 0,
+            ..self
+        }
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_magnitude(other),
+            (true, true) => other.cmp_magnitude(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        let digits = self.mantissa.to_string();
+        if self.exp >= 0 {
+            write!(f, "{digits}{}", "0".repeat(self.exp as usize))
+        } else {
+            let point = digits.len() as i32 + self.exp;
+            if point > 0 {
+                let (int, frac) = digits.split_at(point as usize);
+                write!(f, "{int}.{frac}")
+            } else {
+                write!(f, "0.{}{digits}", "0".repeat(-point as usize))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        Decimal::parse(s).unwrap()
+    }
+
+    #[test]
+    fn numbers_are_parsed_and_formatted() {
+        assert_eq!(d("10").to_string(), "10");
+        assert_eq!(d("-1.50").to_string(), "-1.5");
+        assert_eq!(d("0.001").to_string(), "0.001");
+        assert_eq!(d("1E+2").to_string(), "100");
+        assert_eq!(d("-0").to_string(), "0");
+        assert_eq!(d(".5").to_string(), "0.5");
+        assert!(Decimal::parse("abc").is_err());
+        assert!(Decimal::parse("1e").is_err());
+        assert!(Decimal::parse("-").is_err());
+        assert!(Decimal::parse("123456789012345678901234567890123456789").is_err());
+    }
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        assert_eq!(d("1.0").cmp(&d("1")), Ordering::Equal);
+        assert_eq!(d("9").cmp(&d("10")), Ordering::Less);
+        assert_eq!(d("-9").cmp(&d("-10")), Ordering::Greater);
+        assert_eq!(d("0.11").cmp(&d("0.2")), Ordering::Less);
+        assert_eq!(d("-1").cmp(&d("0")), Ordering::Less);
+        assert_eq!(d("1e3").cmp(&d("999.999")), Ordering::Greater);
+    }
+
+    #[test]
+    fn numbers_are_added_exactly() {
+        assert_eq!(d("0.1").checked_add(d("0.2")).unwrap().to_string(), "0.3");
+        assert_eq!(d("5").checked_add(d("-7")).unwrap().to_string(), "-2");
+        assert_eq!(d("-5").checked_add(d("5")).unwrap().to_string(), "0");
+        assert_eq!(d("10").checked_add(d("1").neg()).unwrap().to_string(), "9");
+        assert!(d("1e100").checked_add(d("1e-100")).is_err());
+    }
+}
@@ -0,0 +1,779 @@
+use super::{AttributeValueConvertible, ConvertError, Expression, number::parse_int};
+use aws_sdk_dynamodb::types::{AttributeValue, TimeToLiveSpecification};
+use std::time::{Duration, SystemTime};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const SECS_PER_DAY: i128 = 86_400;
+
+/// Time types that can be stored as timestamps.
+///
+/// This trait is implemented for `SystemTime`, for `chrono::DateTime<Utc>` and `chrono::NaiveDate`
+/// with the `chrono` feature, for `time::OffsetDateTime` with the `time` feature and for
+/// `jiff::Timestamp` with the `jiff` feature.
+pub trait Timestamp: Sized {
+    /// The current time.
+    fn now() -> Self;
+
+    /// The number of nanoseconds since the Unix epoch.
+    fn unix_nanos(&self) -> i128;
+
+    /// Creates a time from the number of nanoseconds since the Unix epoch.
+    ///
+    /// Returns `None` if the time is out of the range of the type.
+    fn from_unix_nanos(nanos: i128) -> Option<Self>;
+}
+
+impl Timestamp for SystemTime {
+    fn now() -> Self {
+        SystemTime::now()
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        }
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        let duration = |nanos: u128| {
+            let secs = u64::try_from(nanos / NANOS_PER_SEC as u128).ok()?;
+            Some(Duration::new(secs, (nanos % NANOS_PER_SEC as u128) as u32))
+        };
+
+        if nanos >= 0 {
+            SystemTime::UNIX_EPOCH.checked_add(duration(nanos.unsigned_abs())?)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_sub(duration(nanos.unsigned_abs())?)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn now() -> Self {
+        chrono::Utc::now()
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        self.timestamp() as i128 * NANOS_PER_SEC + self.timestamp_subsec_nanos() as i128
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        Self::from_timestamp(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32)
+    }
+}
+
+/// A date is the midnight of the day in UTC.
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::NaiveDate {
+    fn now() -> Self {
+        chrono::Utc::now().date_naive()
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        self.and_time(chrono::NaiveTime::MIN).and_utc().unix_nanos()
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        let time = chrono::DateTime::<chrono::Utc>::from_unix_nanos(nanos)?;
+        (time.time() == chrono::NaiveTime::MIN).then(|| time.date_naive())
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn now() -> Self {
+        time::OffsetDateTime::now_utc()
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        self.unix_timestamp_nanos()
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        Self::from_unix_timestamp_nanos(nanos).ok()
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl Timestamp for jiff::Timestamp {
+    fn now() -> Self {
+        jiff::Timestamp::now()
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        self.as_nanosecond()
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Option<Self> {
+        Self::from_nanosecond(nanos).ok()
+    }
+}
+
+macro_rules! impl_rfc3339_convertible {
+    ($($(#[$meta:meta])* $ty:ty),*) => {
+        $(
+            $(#[$meta])*
+            impl AttributeValueConvertible for $ty {
+                fn into_attribute_value(self) -> AttributeValue {
+                    TimestampFormat::Rfc3339.format(&self)
+                }
+                fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+                    TimestampFormat::Rfc3339.parse(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_rfc3339_convertible! {
+    SystemTime,
+    #[cfg(feature = "chrono")]
+    chrono::DateTime<chrono::Utc>,
+    #[cfg(feature = "time")]
+    time::OffsetDateTime,
+    #[cfg(feature = "jiff")]
+    jiff::Timestamp
+}
+
+/// A date is stored as a `YYYY-MM-DD` string.
+#[cfg(feature = "chrono")]
+impl AttributeValueConvertible for chrono::NaiveDate {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::S(self.format("%Y-%m-%d").to_string())
+    }
+    fn try_from_attribute_value(value: &AttributeValue) -> Result<Self, ConvertError> {
+        let s = value.as_s().map_err(unmatch_err("S"))?;
+        Self::parse_from_str(s, "%Y-%m-%d")
+            .map_err(|_| ConvertError::ParseValue(std::any::type_name::<Self>().into(), s.into()))
+    }
+}
+
+/// How a [`Timestamp`] is stored in an `AttributeValue`.
+///
+/// Fields with the `format` attribute of the [`Dynamodel`](crate::Dynamodel) macro are converted
+/// with this.
+///
+/// ```rust
+/// use dynamodel::{Timestamp, TimestampFormat};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use std::time::{Duration, SystemTime};
+///
+/// let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+///
+/// assert_eq!(
+///     TimestampFormat::Rfc3339.format(&time),
+///     AttributeValue::S("2023-11-14T22:13:20.123000000Z".into()),
+/// );
+/// assert_eq!(
+///     TimestampFormat::EpochSeconds.format(&time),
+///     AttributeValue::N("1700000000".into()),
+/// );
+/// assert_eq!(
+///     TimestampFormat::EpochMillis.format(&time),
+///     AttributeValue::N("1700000000123".into()),
+/// );
+///
+/// let parsed: SystemTime = TimestampFormat::Rfc3339
+///     .parse(&AttributeValue::S("2023-11-15T07:13:20.123+09:00".into()))
+///     .unwrap();
+/// assert_eq!(parsed, time);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TimestampFormat {
+    /// An ISO-8601 string in UTC, like `2024-01-02T03:04:05.000000000Z`, as `AttributeValue::S`.
+    ///
+    /// Fractional seconds are always written with 9 digits, so the strings sort in time order
+    /// and can be used as sort keys.
+    #[default]
+    Rfc3339,
+    /// The number of seconds since the Unix epoch as `AttributeValue::N`.
+    EpochSeconds,
+    /// The number of milliseconds since the Unix epoch as `AttributeValue::N`.
+    EpochMillis,
+    /// A string in UTC with a [strftime pattern](chrono::format::strftime), like `%Y/%m/%d`,
+    /// as `AttributeValue::S`.
+    ///
+    /// A pattern without an offset is parsed as UTC, and a pattern without a time is parsed
+    /// as midnight. The `format` attribute rejects invalid patterns at compile time, and invalid
+    /// specifiers in a hand-written one are left out when formatting.
+    #[cfg(feature = "chrono")]
+    Strftime(&'static str),
+}
+
+impl TimestampFormat {
+    /// Converts the time into an `AttributeValue`.
+    ///
+    /// With [`TimestampFormat::Strftime`], times out of the range of `chrono::DateTime`, over
+    /// 262,000 years away, are clamped to it.
+    pub fn format<T: Timestamp>(self, time: &T) -> AttributeValue {
+        let nanos = time.unix_nanos();
+        match self {
+            Self::Rfc3339 => AttributeValue::S(format_rfc3339(nanos)),
+            Self::EpochSeconds => AttributeValue::N(nanos.div_euclid(NANOS_PER_SEC).to_string()),
+            Self::EpochMillis => AttributeValue::N(nanos.div_euclid(1_000_000).to_string()),
+            #[cfg(feature = "chrono")]
+            Self::Strftime(pattern) => {
+                use chrono::format::{Item, StrftimeItems};
+
+                let time = chrono::DateTime::<chrono::Utc>::from_unix_nanos(nanos).unwrap_or(
+                    if nanos < 0 {
+                        chrono::DateTime::<chrono::Utc>::MIN_UTC
+                    } else {
+                        chrono::DateTime::<chrono::Utc>::MAX_UTC
+                    },
+                );
+                // Formatting an invalid item fails, and `to_string` panics on it.
+                let items = StrftimeItems::new(pattern).filter(|item| *item != Item::Error);
+                AttributeValue::S(time.format_with_items(items).to_string())
+            }
+        }
+    }
+
+    /// Converts an `AttributeValue` into the time.
+    pub fn parse<T: Timestamp>(self, value: &AttributeValue) -> Result<T, ConvertError> {
+        let (raw, nanos) = match self {
+            Self::Rfc3339 => {
+                let s = value.as_s().map_err(unmatch_err("S"))?;
+                (s, parse_rfc3339(s))
+            }
+            Self::EpochSeconds | Self::EpochMillis => {
+                let n = value.as_n().map_err(unmatch_err("N"))?;
+                let unit = if self == Self::EpochSeconds {
+                    NANOS_PER_SEC
+                } else {
+                    1_000_000
+                };
+                // Read like the integer types, so exponent forms like `1.7E+9` are accepted.
+                let nanos = parse_int::<i64>(n).ok().map(|v| v as i128 * unit);
+                (n, nanos)
+            }
+            #[cfg(feature = "chrono")]
+            Self::Strftime(pattern) => {
+                let s = value.as_s().map_err(unmatch_err("S"))?;
+                (s, parse_strftime(s, pattern))
+            }
+        };
+
+        nanos
+            .and_then(T::from_unix_nanos)
+            .ok_or_else(|| ConvertError::ParseValue(std::any::type_name::<T>().into(), raw.into()))
+    }
+}
+
+fn unmatch_err(expected: &str) -> impl Fn(&AttributeValue) -> ConvertError + '_ {
+    |value| ConvertError::AttributeValueUnmatched(expected.into(), value.clone())
+}
+
+/// Objects with audit timestamps.
+///
+/// The [`Dynamodel`](crate::Dynamodel) macro implements this trait when a field has the
+/// `#[dynamodel(created_at)]` or `#[dynamodel(updated_at)]` attribute.
+///
+/// Converting the object into an item fills in the timestamps: the `created_at` field is set
+/// to the current time if it is `None`, and the `updated_at` field is always set to the current
+/// time. [`Timestamped::timestamp_update`] does the same in an `UpdateItem` request.
+///
+/// ```rust
+/// use dynamodel::{Dynamodel, Timestamped};
+/// use std::time::SystemTime;
+///
+/// #[derive(Dynamodel)]
+/// #[dynamodel(rename_all = "PascalCase")]
+/// struct Video {
+///     #[dynamodel(rename = "PK")]
+///     id: String,
+///     #[dynamodel(created_at)]
+///     created_at: Option<SystemTime>,
+///     #[dynamodel(updated_at, format = "epoch_seconds")]
+///     updated_at: SystemTime,
+/// }
+///
+/// assert_eq!(Video::CREATED_AT_ATTRIBUTE, Some("CreatedAt"));
+///
+/// let expression = Video::timestamp_update();
+/// assert_eq!(
+///     expression.update_expression(),
+///     Some(
+///         "SET #dynamodel_created_at = if_not_exists(#dynamodel_created_at, :dynamodel_created_at), \
+///          #dynamodel_updated_at = :dynamodel_updated_at"
+///     ),
+/// );
+/// ```
+pub trait Timestamped {
+    /// The name of the `created_at` attribute, if any.
+    const CREATED_AT_ATTRIBUTE: Option<&'static str>;
+
+    /// The name of the `updated_at` attribute, if any.
+    const UPDATED_AT_ATTRIBUTE: Option<&'static str>;
+
+    /// Returns an update expression that sets the `created_at` attribute to the current time
+    /// if it doesn't exist, and the `updated_at` attribute to the current time.
+    fn timestamp_update() -> Expression;
+
+    /// Builds [`Timestamped::timestamp_update`] from the formatted current times.
+    fn timestamp_update_with(
+        created_at: Option<AttributeValue>,
+        updated_at: Option<AttributeValue>,
+    ) -> Expression {
+        let mut expression = Expression::default();
+
+        if let (Some(name), Some(value)) = (Self::CREATED_AT_ATTRIBUTE, created_at) {
+            expression = expression
+                .with_update(
+                    "SET #dynamodel_created_at = if_not_exists(#dynamodel_created_at, :dynamodel_created_at)",
+                )
+                .name("#dynamodel_created_at", name)
+                .value(":dynamodel_created_at", value);
+        }
+
+        if let (Some(name), Some(value)) = (Self::UPDATED_AT_ATTRIBUTE, updated_at) {
+            expression = expression
+                .with_update("SET #dynamodel_updated_at = :dynamodel_updated_at")
+                .name("#dynamodel_updated_at", name)
+                .value(":dynamodel_updated_at", value);
+        }
+
+        expression
+    }
+}
+
+/// Objects with a TTL (time to live) attribute.
+///
+/// The [`Dynamodel`](crate::Dynamodel) macro implements this trait when a field has the
+/// `#[dynamodel(ttl)]` attribute. The field is a [`Timestamp`] stored as epoch seconds, and
+/// `None` is not stored at all, so that the item never expires.
+///
+/// ```rust
+/// use dynamodel::{Dynamodel, TimeToLive};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use std::{collections::HashMap, time::{Duration, SystemTime}};
+///
+/// #[derive(Dynamodel)]
+/// #[dynamodel(rename_all = "PascalCase")]
+/// struct Session {
+///     #[dynamodel(rename = "PK")]
+///     id: String,
+///     #[dynamodel(ttl)]
+///     expires_at: Option<SystemTime>,
+/// }
+///
+/// assert_eq!(Session::TTL_ATTRIBUTE, "ExpiresAt");
+///
+/// let session = Session {
+///     id: "f8a3".into(),
+///     expires_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+/// };
+/// let item: HashMap<String, AttributeValue> = session.into();
+/// assert_eq!(item["ExpiresAt"], AttributeValue::N("1700000000".into()));
+///
+/// // The specification to enable TTL with `UpdateTimeToLive`.
+/// let specification = Session::time_to_live_specification();
+/// assert_eq!(specification.attribute_name(), "ExpiresAt");
+/// assert!(specification.enabled());
+/// ```
+pub trait TimeToLive {
+    /// The name of the TTL attribute.
+    const TTL_ATTRIBUTE: &'static str;
+
+    /// The specification to enable TTL on the attribute, for `UpdateTimeToLive` requests.
+    fn time_to_live_specification() -> TimeToLiveSpecification {
+        TimeToLiveSpecification::builder()
+            .attribute_name(Self::TTL_ATTRIBUTE)
+            .enabled(true)
+            .build()
+            .expect("both the attribute name and enabled are set")
+    }
+}
+
+// Converts days since the Unix epoch into (year, month, day).
+// Ref: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i128) -> (i128, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i128::from(month <= 2);
+    (year, month, day)
+}
+
+// Converts (year, month, day) into days since the Unix epoch.
+// Ref: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i128, month: u32, day: u32) -> i128 {
+    let year = year - i128::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i128 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i128 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn days_in_month(year: i128, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn parse_strftime(s: &str, pattern: &str) -> Option<i128> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+
+    DateTime::parse_from_str(s, pattern)
+        .map(|t| t.to_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(s, pattern).map(|t| t.and_utc()))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(s, pattern).map(|d| d.and_time(NaiveTime::MIN).and_utc())
+        })
+        .ok()
+        .map(|t| t.unix_nanos())
+}
+
+// RFC 3339 has years from 0000 to 9999 only, so the others are written with a sign and at least
+// four digits like the expanded years of ISO 8601 and chrono, such as `+10000` and `-0001`.
+// `parse_rfc3339` reads them back.
+fn format_rfc3339(nanos: i128) -> String {
+    let secs = nanos.div_euclid(NANOS_PER_SEC);
+    let subsec = nanos.rem_euclid(NANOS_PER_SEC);
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+    let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+    let year = if (0..=9999).contains(&year) {
+        format!("{year:04}")
+    } else {
+        format!("{year:+05}")
+    };
+
+    format!(
+        "{year}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{subsec:09}Z",
+        secs_of_day / 3_600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    )
+}
+
+fn parse_rfc3339(s: &str) -> Option<i128> {
+    fn number(s: &str) -> Option<u32> {
+        s.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| s.parse().ok())
+            .flatten()
+    }
+
+    // The expanded years of `format_rfc3339`. The digits are limited so that the seconds can't
+    // overflow, and `SystemTime` has no more than 12 digits of years.
+    let (year, s) = match s.as_bytes().first() {
+        Some(sign @ (b'+' | b'-')) => {
+            let digits = s[1..].bytes().take_while(u8::is_ascii_digit).count();
+            if !(4..=12).contains(&digits) {
+                return None;
+            }
+            let year = s[1..=digits].parse::<i128>().ok()?;
+            (if *sign == b'-' { -year } else { year }, &s[digits + 1..])
+        }
+        _ => (number(s.get(..4)?)? as i128, &s[4..]),
+    };
+
+    let b = s.as_bytes();
+    if b.len() < 16
+        || b[0] != b'-'
+        || b[3] != b'-'
+        || !matches!(b[6], b'T' | b't' | b' ')
+        || b[9] != b':'
+        || b[12] != b':'
+    {
+        return None;
+    }
+
+    let month = number(&s[1..3])?;
+    let day = number(&s[4..6])?;
+    let hour = number(&s[7..9])? as i128;
+    let minute = number(&s[10..12])? as i128;
+    let second = number(s.get(13..15)?)? as i128;
+    if !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let mut rest = s.get(15..)?;
+    let mut subsec = 0i128;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        subsec = number(&fraction[..digits])? as i128 * 10i128.pow(9 - digits as u32);
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let o = rest.as_bytes();
+            if o.len() != 6 || !matches!(o[0], b'+' | b'-') || o[3] != b':' {
+                return None;
+            }
+            let hours = number(&rest[1..3])? as i128;
+            let minutes = number(&rest[4..6])? as i128;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 3_600 + minutes * 60;
+            if o[0] == b'-' { -offset } else { offset }
+        }
+    };
+
+    let secs =
+        days_from_civil(year, month, day) * SECS_PER_DAY + hour * 3_600 + minute * 60 + second
+            - offset;
+    Some(secs * NANOS_PER_SEC + subsec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(nanos: i128) -> SystemTime {
+        SystemTime::from_unix_nanos(nanos).unwrap()
+    }
+
+    #[test]
+    fn rfc3339_strings_are_formatted_in_utc() {
+        let cases = [
+            (0, "1970-01-01T00:00:00.000000000Z"),
+            (
+                951_782_400 * NANOS_PER_SEC,
+                "2000-02-29T00:00:00.000000000Z",
+            ),
+            (1_700_000_000_100_000_000, "2023-11-14T22:13:20.100000000Z"),
+            (1_700_000_000_000_001_000, "2023-11-14T22:13:20.000001000Z"),
+            (1_700_000_000_000_000_001, "2023-11-14T22:13:20.000000001Z"),
+            (-1, "1969-12-31T23:59:59.999999999Z"),
+            (
+                -62_167_219_200 * NANOS_PER_SEC,
+                "0000-01-01T00:00:00.000000000Z",
+            ),
+            (
+                253_402_300_800 * NANOS_PER_SEC - 1,
+                "9999-12-31T23:59:59.999999999Z",
+            ),
+            (
+                253_402_300_800 * NANOS_PER_SEC,
+                "+10000-01-01T00:00:00.000000000Z",
+            ),
+            (
+                -62_167_219_200 * NANOS_PER_SEC - 1,
+                "-0001-12-31T23:59:59.999999999Z",
+            ),
+            (
+                i64::MAX as i128 * NANOS_PER_SEC,
+                "+292277026596-12-04T15:30:07.000000000Z",
+            ),
+        ];
+
+        for (nanos, expected) in cases {
+            let value = TimestampFormat::Rfc3339.format(&time(nanos));
+            assert_eq!(value, AttributeValue::S(expected.into()));
+
+            let parsed: SystemTime = TimestampFormat::Rfc3339.parse(&value).unwrap();
+            assert_eq!(parsed, time(nanos));
+        }
+    }
+
+    #[test]
+    fn rfc3339_strings_with_offsets_are_parsed() {
+        let parse = |s: &str| {
+            TimestampFormat::Rfc3339
+                .parse::<SystemTime>(&AttributeValue::S(s.into()))
+                .ok()
+                .map(|t| t.unix_nanos())
+        };
+
+        assert_eq!(parse("1970-01-01T09:00:00+09:00"), Some(0));
+        assert_eq!(parse("1969-12-31t19:00:00.5-05:00"), Some(500_000_000));
+        assert_eq!(parse("1970-01-01 00:00:00z"), Some(0));
+
+        for invalid in [
+            "1970-01-01",
+            "1970-01-01T00:00:00",
+            "1970-13-01T00:00:00Z",
+            "2023-02-29T00:00:00Z",
+            "1970-01-01T24:00:00Z",
+            "1970-01-01T00:00:00.Z",
+            "1970-01-01T00:00:00+0900",
+            "+970-01-01T00:00:00Z",
+            "+0000000000000-01-01T00:00:00Z",
+            "-01-01T00:00:00Z",
+            "1970",
+            "1970-01-01T00:00:0\u{e9}Z",
+        ] {
+            assert_eq!(parse(invalid), None, "{invalid} should be invalid");
+        }
+    }
+
+    #[test]
+    fn epoch_numbers_are_truncated_to_the_unit() {
+        let t = time(-1_500_000_000);
+        assert_eq!(
+            TimestampFormat::EpochSeconds.format(&t),
+            AttributeValue::N("-2".into())
+        );
+        assert_eq!(
+            TimestampFormat::EpochMillis.format(&t),
+            AttributeValue::N("-1500".into())
+        );
+
+        let parsed: SystemTime = TimestampFormat::EpochMillis
+            .parse(&AttributeValue::N("-1500".into()))
+            .unwrap();
+        assert_eq!(parsed, t);
+    }
+
+    #[test]
+    fn epoch_numbers_in_exponent_form_are_parsed() {
+        let parsed: SystemTime = TimestampFormat::EpochSeconds
+            .parse(&AttributeValue::N("1.7E+9".into()))
+            .unwrap();
+        assert_eq!(parsed, time(1_700_000_000_000_000_000));
+
+        let parsed: SystemTime = TimestampFormat::EpochMillis
+            .parse(&AttributeValue::N("-15e2".into()))
+            .unwrap();
+        assert_eq!(parsed, time(-1_500_000_000));
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        let result =
+            TimestampFormat::EpochSeconds.parse::<SystemTime>(&AttributeValue::S("1".into()));
+        assert!(matches!(result, Err(ConvertError::AttributeValueUnmatched(t, _)) if t == "N"));
+
+        let result =
+            TimestampFormat::EpochSeconds.parse::<SystemTime>(&AttributeValue::N("1.5".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "1.5"));
+
+        let result =
+            TimestampFormat::EpochSeconds.parse::<SystemTime>(&AttributeValue::N("1.5E+0".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "1.5E+0"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_datetimes_are_timestamps() {
+        let t = chrono::DateTime::<chrono::Utc>::from_unix_nanos(-1_500_000_000).unwrap();
+        assert_eq!(t.to_rfc3339(), "1969-12-31T23:59:58.500+00:00");
+        assert_eq!(t.unix_nanos(), -1_500_000_000);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_datetimes_are_timestamps() {
+        let t = time::OffsetDateTime::from_unix_nanos(1_500_000_000).unwrap();
+        assert_eq!(t.unix_timestamp(), 1);
+        assert_eq!(
+            TimestampFormat::Rfc3339.format(&t),
+            AttributeValue::S("1970-01-01T00:00:01.500000000Z".into())
+        );
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn jiff_timestamps_are_convertible() {
+        let t = jiff::Timestamp::from_unix_nanos(1_700_000_000_123_000_000).unwrap();
+        let value = t.into_attribute_value();
+        assert_eq!(
+            value,
+            AttributeValue::S("2023-11-14T22:13:20.123000000Z".into())
+        );
+        assert_eq!(
+            jiff::Timestamp::try_from_attribute_value(&value).unwrap(),
+            t
+        );
+    }
+
+    #[test]
+    fn rfc3339_strings_sort_in_time_order() {
+        let values: Vec<String> = [-1, 0, 100_000_000, 1_000_000_000, 1_000_000_001]
+            .into_iter()
+            .map(|nanos| time(nanos).into_attribute_value().as_s().unwrap().clone())
+            .collect();
+
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(sorted, values);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn naive_dates_are_convertible() {
+        use chrono::NaiveDate;
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let value = date.into_attribute_value();
+        assert_eq!(value, AttributeValue::S("2024-02-29".into()));
+        assert_eq!(NaiveDate::try_from_attribute_value(&value).unwrap(), date);
+
+        let result = NaiveDate::try_from_attribute_value(&AttributeValue::S("2023-02-29".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "2023-02-29"));
+
+        assert_eq!(
+            TimestampFormat::EpochSeconds.format(&date),
+            AttributeValue::N("1709164800".into())
+        );
+        let result = TimestampFormat::EpochSeconds
+            .parse::<NaiveDate>(&AttributeValue::N("1709164801".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, _))));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn strftime_patterns_are_formatted_in_utc() {
+        let t = time(1_700_000_000 * NANOS_PER_SEC);
+
+        let format = TimestampFormat::Strftime("%Y/%m/%d %H:%M");
+        let value = format.format(&t);
+        assert_eq!(value, AttributeValue::S("2023/11/14 22:13".into()));
+        let parsed: SystemTime = format.parse(&value).unwrap();
+        assert_eq!(parsed, time(1_699_999_980 * NANOS_PER_SEC));
+
+        let format = TimestampFormat::Strftime("%Y-%m-%d %H:%M:%S %z");
+        let parsed: SystemTime = format
+            .parse(&AttributeValue::S("2023-11-15 07:13:20 +0900".into()))
+            .unwrap();
+        assert_eq!(parsed, t);
+
+        let format = TimestampFormat::Strftime("%d.%m.%Y");
+        let parsed: SystemTime = format
+            .parse(&AttributeValue::S("15.11.2023".into()))
+            .unwrap();
+        assert_eq!(parsed, time(1_700_006_400 * NANOS_PER_SEC));
+
+        let result = format.parse::<SystemTime>(&AttributeValue::S("2023-11-15".into()));
+        assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "2023-11-15"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn strftime_formatting_does_not_panic() {
+        let t = time(1_700_000_000 * NANOS_PER_SEC);
+        let value = TimestampFormat::Strftime("%Y %Q").format(&t);
+        assert_eq!(value, AttributeValue::S("2023 ".into()));
+
+        let t = time(i64::MAX as i128 * NANOS_PER_SEC);
+        let value = TimestampFormat::Strftime("%Y").format(&t);
+        assert_eq!(value, AttributeValue::S("+262142".into()));
+    }
+}
@@ -0,0 +1,818 @@
+//! Typed builders for `TransactWriteItems` and `TransactGetItems`.
+//!
+//! [`TransactWrite`] takes your models and keys directly and converts them into the
+//! SDK types. Before sending, it checks the rules DynamoDB would otherwise reject the
+//! transaction for: up to 100 actions, and no two actions on the same item. When DynamoDB
+//! cancels the transaction, the cancellation reasons are mapped back to the actions that
+//! caused them.
+//!
+//! ```rust,no_run
+//! use dynamodel::{transaction::{TransactWrite, TransactionError}, Dynamodel, Expression, KeySchema};
+//!
+//! #[derive(Dynamodel)]
+//! #[dynamodel(rename_all = "PascalCase")]
+//! struct Order {
+//!     #[dynamodel(rename = "PK")]
+//!     id: String,
+//!     #[dynamodel(rename = "SK")]
+//!     sort_key: String,
+//!     total: u64,
+//! }
+//!
+//! #[derive(Dynamodel)]
+//! struct Key {
+//!     #[dynamodel(rename = "PK")]
+//!     pk: String,
+//!     #[dynamodel(rename = "SK")]
+//!     sk: String,
+//! }
+//!
+//! # async fn run(client: aws_sdk_dynamodb::Client) -> Result<(), TransactionError> {
+//! let order = Order { id: "order#1".into(), sort_key: "order".into(), total: 3000 };
+//! let cart = Key { pk: "user#1".into(), sk: "cart".into() };
+//! let stock = Key { pk: "item#1".into(), sk: "stock".into() };
+//!
+//! let result = TransactWrite::new(KeySchema::new("PK").with_sort_key("SK"))
+//!     .put_with("shop", order, Expression::condition("attribute_not_exists(PK)"))
+//!     .update(
+//!         "shop",
+//!         stock,
+//!         Expression::update("ADD #count :minus")
+//!             .with_condition("#count >= :one")
+//!             .name("#count", "Count")
+//!             .value(":minus", -1)
+//!             .value(":one", 1),
+//!     )
+//!     .delete("shop", cart)
+//!     .send(&client)
+//!     .await;
+//!
+//! if let Err(TransactionError::Canceled(failures)) = &result {
+//!     for failure in failures {
+//!         println!("{:?} #{} failed: {}", failure.operation, failure.index, failure.code);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{ConvertError, Expression, KeySchema};
+use aws_sdk_dynamodb::{
+    Client,
+    error::SdkError,
+    types::{
+        AttributeValue, CancellationReason, ConditionCheck, Delete, Get, Put, TransactGetItem,
+        TransactWriteItem, Update,
+    },
+};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The maximum number of actions in a transaction.
+pub const MAX_ACTIONS: usize = 100;
+
+/// An error occurs when building or sending a transaction.
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    /// The transaction has more than 100 actions.
+    #[error("a transaction can have up to {MAX_ACTIONS} actions, but got {0}")]
+    TooManyActions(usize),
+
+    /// Two actions target the same item.
+    #[error("action {index} targets the same item as action {first}")]
+    DuplicateItem {
+        /// The index of the first action on the item.
+        first: usize,
+        /// The index of the second action on the item.
+        index: usize,
+    },
+
+    /// The item or key of an action lacks the key attributes of its table.
+    #[error("the item of action {0} lacks the key attributes")]
+    MissingKey(usize),
+
+    /// An update action has no update expression.
+    #[error("update action {0} has no update expression")]
+    MissingUpdateExpression(usize),
+
+    /// A condition check action has no condition expression.
+    #[error("condition check action {0} has no condition expression")]
+    MissingConditionExpression(usize),
+
+    /// DynamoDB canceled the transaction. Each failure points to the action that caused it.
+    #[error("transaction was canceled: {}", describe(.0))]
+    Canceled(Vec<ActionFailure>),
+
+    /// An item in the response could not be converted into the requested type.
+    #[error(transparent)]
+    Convert(#[from] ConvertError),
+
+    /// The SDK returned an error.
+    #[error(transparent)]
+    Sdk(#[from] Box<aws_sdk_dynamodb::Error>),
+}
+
+fn describe(failures: &[ActionFailure]) -> String {
+    failures
+        .iter()
+        .map(|f| format!("{:?} #{} ({})", f.operation, f.index, f.code))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// The kind of an action in a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Put,
+    Update,
+    ConditionCheck,
+    Delete,
+    Get,
+}
+
+/// An action that caused DynamoDB to cancel the transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionFailure {
+    /// The position of the action in the transaction.
+    pub index: usize,
+    /// The kind of the action.
+    pub operation: Operation,
+    /// The table the action targets.
+    pub table_name: String,
+    /// The cancellation code, like `ConditionalCheckFailed`.
+    pub code: String,
+    /// The cancellation message.
+    pub message: Option<String>,
+    /// The item as it was when the condition failed, if requested with
+    /// `ReturnValuesOnConditionCheckFailure`.
+    pub item: Option<HashMap<String, AttributeValue>>,
+}
+
+impl ActionFailure {
+    /// Returns true if the action failed because its condition expression was not met.
+    pub fn is_condition_failed(&self) -> bool {
+        self.code == "ConditionalCheckFailed"
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Action {
+    operation: Operation,
+    table_name: String,
+    // The item for put actions, the key or the whole item for the others.
+    item: HashMap<String, AttributeValue>,
+    expression: Option<Expression>,
+}
+
+/// A builder for `TransactWriteItems`.
+#[derive(Debug, Clone)]
+pub struct TransactWrite {
+    key_schema: KeySchema,
+    key_schemas: HashMap<String, KeySchema>,
+    actions: Vec<Action>,
+    client_request_token: Option<String>,
+}
+
+impl TransactWrite {
+    /// Creates an empty transaction.
+    ///
+    /// The key schema is used to find the key of every action, to check that no two actions
+    /// target the same item, and to send only the key attributes of the update, condition
+    /// check and delete actions, so they can take a whole model as well as a key. Use
+    /// [`TransactWrite::key_schema`] for tables with another key schema.
+    pub fn new(key_schema: KeySchema) -> Self {
+        Self {
+            key_schema,
+            key_schemas: HashMap::new(),
+            actions: vec![],
+            client_request_token: None,
+        }
+    }
+
+    /// Sets the key schema of a table.
+    pub fn key_schema(mut self, table_name: impl Into<String>, key_schema: KeySchema) -> Self {
+        self.key_schemas.insert(table_name.into(), key_schema);
+        self
+    }
+
+    /// Sets the `ClientRequestToken` to make the transaction idempotent.
+    pub fn client_request_token(self, token: impl Into<String>) -> Self {
+        Self {
+            client_request_token: Some(token.into()),
+            ..self
+        }
+    }
+
+    fn push<T>(
+        mut self,
+        operation: Operation,
+        table_name: impl Into<String>,
+        item: T,
+        expression: Option<Expression>,
+    ) -> Self
+    where
+        T: Into<HashMap<String, AttributeValue>>,
+    {
+        self.actions.push(Action {
+            operation,
+            table_name: table_name.into(),
+            item: item.into(),
+            expression,
+        });
+        self
+    }
+
+    /// Adds a put action of the item.
+    pub fn put<T>(self, table_name: impl Into<String>, item: T) -> Self
+    where
+        T: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::Put, table_name, item, None)
+    }
+
+    /// Adds a put action of the item with a condition expression.
+    pub fn put_with<T>(self, table_name: impl Into<String>, item: T, condition: Expression) -> Self
+    where
+        T: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::Put, table_name, item, Some(condition))
+    }
+
+    /// Adds an update action of the item with the key.
+    ///
+    /// The expression must have an update expression and may have a condition expression.
+    pub fn update<K>(self, table_name: impl Into<String>, key: K, expression: Expression) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::Update, table_name, key, Some(expression))
+    }
+
+    /// Adds a condition check of the item with the key.
+    pub fn condition_check<K>(
+        self,
+        table_name: impl Into<String>,
+        key: K,
+        condition: Expression,
+    ) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::ConditionCheck, table_name, key, Some(condition))
+    }
+
+    /// Adds a delete action of the item with the key.
+    pub fn delete<K>(self, table_name: impl Into<String>, key: K) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::Delete, table_name, key, None)
+    }
+
+    /// Adds a delete action of the item with the key and a condition expression.
+    pub fn delete_with<K>(
+        self,
+        table_name: impl Into<String>,
+        key: K,
+        condition: Expression,
+    ) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.push(Operation::Delete, table_name, key, Some(condition))
+    }
+
+    /// The number of actions in the transaction.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns true if the transaction has no actions.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Validates the transaction and converts the actions into the SDK type.
+    pub fn build(&self) -> Result<Vec<TransactWriteItem>, TransactionError> {
+        let keys = self.validate()?;
+        self.actions
+            .iter()
+            .zip(keys)
+            .enumerate()
+            .map(|(index, (action, key))| build_write(index, action, key))
+            .collect()
+    }
+
+    /// Validates and sends the transaction.
+    pub async fn send(self, client: &Client) -> Result<(), TransactionError> {
+        let items = self.build()?;
+
+        client
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .set_client_request_token(self.client_request_token.clone())
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| match err {
+                SdkError::ServiceError(e) => match e.into_err() {
+                    aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError::TransactionCanceledException(e) => {
+                        cancellation_error(&self.actions, e.cancellation_reasons.unwrap_or_default())
+                    }
+                    e => TransactionError::Sdk(Box::new(e.into())),
+                },
+                e => TransactionError::Sdk(Box::new(e.into())),
+            })
+    }
+
+    fn key_schema_of(&self, table_name: &str) -> KeySchema {
+        self.key_schemas
+            .get(table_name)
+            .copied()
+            .unwrap_or(self.key_schema)
+    }
+
+    // Returns the key of each action.
+    fn validate(&self) -> Result<Vec<HashMap<String, AttributeValue>>, TransactionError> {
+        if self.actions.len() > MAX_ACTIONS {
+            return Err(TransactionError::TooManyActions(self.actions.len()));
+        }
+
+        let mut keys: Vec<HashMap<String, AttributeValue>> = vec![];
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let key = self
+                .key_schema_of(&action.table_name)
+                .key_of(&action.item)
+                .ok_or(TransactionError::MissingKey(index))?;
+
+            let expression = action.expression.as_ref();
+            match action.operation {
+                Operation::Update
+                    if expression.and_then(Expression::update_expression).is_none() =>
+                {
+                    return Err(TransactionError::MissingUpdateExpression(index));
+                }
+                Operation::ConditionCheck
+                    if expression
+                        .and_then(Expression::condition_expression)
+                        .is_none() =>
+                {
+                    return Err(TransactionError::MissingConditionExpression(index));
+                }
+                _ => {}
+            }
+
+            if let Some(first) = self.actions[..index]
+                .iter()
+                .zip(&keys)
+                .position(|(a, k)| a.table_name == action.table_name && *k == key)
+            {
+                return Err(TransactionError::DuplicateItem { first, index });
+            }
+            keys.push(key);
+        }
+
+        Ok(keys)
+    }
+}
+
+fn build_write(
+    index: usize,
+    action: &Action,
+    key: HashMap<String, AttributeValue>,
+) -> Result<TransactWriteItem, TransactionError> {
+    let table_name = Some(action.table_name.clone());
+    let item = Some(action.item.clone());
+    let key = Some(key);
+    let expression = action.expression.as_ref();
+    let condition = expression
+        .and_then(Expression::condition_expression)
+        .map(String::from);
+    let names = expression.and_then(Expression::attribute_names);
+    let values = expression.and_then(Expression::attribute_values);
+
+    let builder = TransactWriteItem::builder();
+    let builder = match action.operation {
+        Operation::Put => builder.put(
+            Put::builder()
+                .set_table_name(table_name)
+                .set_item(item)
+                .set_condition_expression(condition)
+                .set_expression_attribute_names(names)
+                .set_expression_attribute_values(values)
+                .build()
+                .expect("table name and item are set"),
+        ),
+        Operation::Update => builder.update(
+            Update::builder()
+                .set_table_name(table_name)
+                .set_key(key)
+                .set_update_expression(
+                    expression
+                        .and_then(Expression::update_expression)
+                        .map(String::from),
+                )
+                .set_condition_expression(condition)
+                .set_expression_attribute_names(names)
+                .set_expression_attribute_values(values)
+                .build()
+                .map_err(|_| TransactionError::MissingUpdateExpression(index))?,
+        ),
+        Operation::ConditionCheck => builder.condition_check(
+            ConditionCheck::builder()
+                .set_table_name(table_name)
+                .set_key(key)
+                .set_condition_expression(condition)
+                .set_expression_attribute_names(names)
+                .set_expression_attribute_values(values)
+                .build()
+                .map_err(|_| TransactionError::MissingConditionExpression(index))?,
+        ),
+        Operation::Delete => builder.delete(
+            Delete::builder()
+                .set_table_name(table_name)
+                .set_key(key)
+                .set_condition_expression(condition)
+                .set_expression_attribute_names(names)
+                .set_expression_attribute_values(values)
+                .build()
+                .expect("table name and key are set"),
+        ),
+        Operation::Get => unreachable!("get actions are not in write transactions"),
+    };
+
+    Ok(builder.build())
+}
+
+// The cancellation reasons are in the same order as the actions, and actions that didn't
+// cause the cancellation have the code `None`.
+fn cancellation_error(actions: &[Action], reasons: Vec<CancellationReason>) -> TransactionError {
+    let failures = reasons
+        .into_iter()
+        .zip(actions)
+        .enumerate()
+        .filter_map(|(index, (reason, action))| {
+            let code = reason.code?;
+            if code == "None" {
+                return None;
+            }
+            Some(ActionFailure {
+                index,
+                operation: action.operation,
+                table_name: action.table_name.clone(),
+                code,
+                message: reason.message,
+                item: reason.item,
+            })
+        })
+        .collect();
+
+    TransactionError::Canceled(failures)
+}
+
+/// A builder for `TransactGetItems`.
+#[derive(Debug, Clone, Default)]
+pub struct TransactGet {
+    actions: Vec<Action>,
+}
+
+impl TransactGet {
+    /// Creates an empty transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a get action of the item with the key.
+    pub fn get<K>(mut self, table_name: impl Into<String>, key: K) -> Self
+    where
+        K: Into<HashMap<String, AttributeValue>>,
+    {
+        self.actions.push(Action {
+            operation: Operation::Get,
+            table_name: table_name.into(),
+            item: key.into(),
+            expression: None,
+        });
+        self
+    }
+
+    /// The number of actions in the transaction.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns true if the transaction has no actions.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Validates the transaction and converts the actions into the SDK type.
+    pub fn build(&self) -> Result<Vec<TransactGetItem>, TransactionError> {
+        if self.actions.len() > MAX_ACTIONS {
+            return Err(TransactionError::TooManyActions(self.actions.len()));
+        }
+
+        for (index, action) in self.actions.iter().enumerate() {
+            if let Some(first) = self.actions[..index]
+                .iter()
+                .position(|a| a.table_name == action.table_name && a.item == action.item)
+            {
+                return Err(TransactionError::DuplicateItem { first, index });
+            }
+        }
+
+        Ok(self
+            .actions
+            .iter()
+            .map(|action| {
+                let get = Get::builder()
+                    .table_name(&action.table_name)
+                    .set_key(Some(action.item.clone()))
+                    .build()
+                    .expect("table name and key are set");
+                TransactGetItem::builder().get(get).build()
+            })
+            .collect())
+    }
+
+    /// Validates and sends the transaction.
+    pub async fn send(self, client: &Client) -> Result<TransactGetOutput, TransactionError> {
+        let items = self.build()?;
+
+        let output = client
+            .transact_get_items()
+            .set_transact_items(Some(items))
+            .send()
+            .await
+            .map_err(|err| match err {
+                SdkError::ServiceError(e) => match e.into_err() {
+                    aws_sdk_dynamodb::operation::transact_get_items::TransactGetItemsError::TransactionCanceledException(e) => {
+                        cancellation_error(&self.actions, e.cancellation_reasons.unwrap_or_default())
+                    }
+                    e => TransactionError::Sdk(Box::new(e.into())),
+                },
+                e => TransactionError::Sdk(Box::new(e.into())),
+            })?;
+
+        let items = output
+            .responses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|response| response.item)
+            .collect();
+
+        Ok(TransactGetOutput { items })
+    }
+}
+
+/// The items returned by [`TransactGet::send`], in the order of the actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactGetOutput {
+    items: Vec<Option<HashMap<String, AttributeValue>>>,
+}
+
+impl TransactGetOutput {
+    /// The item of the action at the index, or `None` if no such item exists.
+    pub fn item(&self, index: usize) -> Option<&HashMap<String, AttributeValue>> {
+        self.items.get(index).and_then(Option::as_ref)
+    }
+
+    /// Takes the item of the action at the index out and converts it into `T`.
+    pub fn take<T>(&mut self, index: usize) -> Result<Option<T>, ConvertError>
+    where
+        T: TryFrom<HashMap<String, AttributeValue>, Error = ConvertError>,
+    {
+        self.items
+            .get_mut(index)
+            .and_then(Option::take)
+            .map(T::try_from)
+            .transpose()
+    }
+
+    /// The items in the order of the actions.
+    pub fn into_items(self) -> Vec<Option<HashMap<String, AttributeValue>>> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: KeySchema = KeySchema::new("PK").with_sort_key("SK");
+
+    fn key(pk: &str, sk: &str) -> HashMap<String, AttributeValue> {
+        [
+            ("PK".to_string(), AttributeValue::S(pk.into())),
+            ("SK".to_string(), AttributeValue::S(sk.into())),
+        ]
+        .into()
+    }
+
+    fn item(pk: &str, sk: &str) -> HashMap<String, AttributeValue> {
+        let mut item = key(pk, sk);
+        item.insert("Total".into(), AttributeValue::N("3000".into()));
+        item
+    }
+
+    #[test]
+    fn actions_are_converted_into_sdk_types() {
+        let items = TransactWrite::new(SCHEMA)
+            .put("shop", item("order#1", "order"))
+            .update(
+                "shop",
+                key("item#1", "stock"),
+                Expression::update("ADD #c :m")
+                    .name("#c", "Count")
+                    .value(":m", -1),
+            )
+            .condition_check(
+                "shop",
+                key("user#1", "profile"),
+                Expression::condition("attribute_exists(PK)"),
+            )
+            .delete("shop", key("user#1", "cart"))
+            .build()
+            .unwrap();
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].put().unwrap().item(), &item("order#1", "order"));
+        let update = items[1].update().unwrap();
+        assert_eq!(update.update_expression(), "ADD #c :m");
+        assert_eq!(
+            update.expression_attribute_values().unwrap().get(":m"),
+            Some(&AttributeValue::N("-1".into())),
+        );
+        assert_eq!(
+            items[2].condition_check().unwrap().condition_expression(),
+            "attribute_exists(PK)"
+        );
+        assert_eq!(items[3].delete().unwrap().key(), &key("user#1", "cart"));
+    }
+
+    #[test]
+    fn too_many_actions_are_rejected() {
+        let tx = (0..=MAX_ACTIONS).fold(TransactWrite::new(SCHEMA), |tx, i| {
+            tx.put("shop", item(&i.to_string(), "order"))
+        });
+
+        assert!(matches!(
+            tx.build(),
+            Err(TransactionError::TooManyActions(101))
+        ));
+    }
+
+    #[test]
+    fn actions_on_the_same_item_are_rejected() {
+        let result = TransactWrite::new(SCHEMA)
+            .put("shop", item("order#1", "order"))
+            .delete("shop", key("user#1", "cart"))
+            .condition_check(
+                "shop",
+                key("order#1", "order"),
+                Expression::condition("attribute_not_exists(PK)"),
+            )
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(TransactionError::DuplicateItem { first: 0, index: 2 })
+        ));
+    }
+
+    #[test]
+    fn actions_with_a_whole_item_are_compared_by_key() {
+        let result = TransactWrite::new(SCHEMA)
+            .put("shop", item("order#1", "order"))
+            .delete("shop", item("order#1", "order"))
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(TransactionError::DuplicateItem { first: 0, index: 1 })
+        ));
+
+        let items = TransactWrite::new(SCHEMA)
+            .put("shop", item("order#1", "order"))
+            .delete("shop", item("order#2", "order"))
+            .build()
+            .unwrap();
+        assert_eq!(items[1].delete().unwrap().key(), &key("order#2", "order"));
+    }
+
+    #[test]
+    fn same_key_in_another_table_is_not_a_duplicate() {
+        let result = TransactWrite::new(SCHEMA)
+            .delete("shop", key("user#1", "cart"))
+            .delete("archive", key("user#1", "cart"))
+            .build();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn put_items_need_key_attributes_of_the_table() {
+        let result = TransactWrite::new(SCHEMA)
+            .key_schema("users", KeySchema::new("UserId"))
+            .put("shop", item("order#1", "order"))
+            .put("users", item("user#1", "profile"))
+            .build();
+
+        assert!(matches!(result, Err(TransactionError::MissingKey(1))));
+
+        let result = TransactWrite::new(SCHEMA)
+            .delete(
+                "shop",
+                [("PK".to_string(), AttributeValue::S("user#1".into()))],
+            )
+            .build();
+
+        assert!(matches!(result, Err(TransactionError::MissingKey(0))));
+    }
+
+    #[test]
+    fn update_actions_need_update_expression() {
+        let result = TransactWrite::new(SCHEMA)
+            .update(
+                "shop",
+                key("item#1", "stock"),
+                Expression::condition("attribute_exists(PK)"),
+            )
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(TransactionError::MissingUpdateExpression(0))
+        ));
+    }
+
+    #[test]
+    fn cancellation_reasons_are_mapped_to_actions() {
+        let tx = TransactWrite::new(SCHEMA)
+            .put("shop", item("order#1", "order"))
+            .update(
+                "shop",
+                key("item#1", "stock"),
+                Expression::update("ADD #c :m").with_condition("#c >= :one"),
+            )
+            .delete("shop", key("user#1", "cart"));
+
+        let reasons = vec![
+            CancellationReason::builder().code("None").build(),
+            CancellationReason::builder()
+                .code("ConditionalCheckFailed")
+                .message("The conditional request failed")
+                .build(),
+            CancellationReason::builder().code("None").build(),
+        ];
+
+        let err = cancellation_error(&tx.actions, reasons);
+        let TransactionError::Canceled(failures) = err else {
+            unreachable!("should be canceled");
+        };
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].index, 1);
+        assert_eq!(failures[0].operation, Operation::Update);
+        assert_eq!(failures[0].table_name, "shop");
+        assert!(failures[0].is_condition_failed());
+    }
+
+    #[test]
+    fn transact_get_rejects_duplicated_keys() {
+        let result = TransactGet::new()
+            .get("shop", key("order#1", "order"))
+            .get("shop", key("order#1", "order"))
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(TransactionError::DuplicateItem { first: 0, index: 1 })
+        ));
+    }
+
+    #[test]
+    fn transact_get_output_converts_items() {
+        #[derive(Debug, PartialEq)]
+        struct Total(String);
+
+        impl TryFrom<HashMap<String, AttributeValue>> for Total {
+            type Error = ConvertError;
+
+            fn try_from(item: HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+                let total = item
+                    .get("Total")
+                    .ok_or(ConvertError::FieldNotSet("Total".into()))?;
+                Ok(Total(total.as_n().unwrap().clone()))
+            }
+        }
+
+        let mut output = TransactGetOutput {
+            items: vec![None, Some(item("order#1", "order"))],
+        };
+
+        assert_eq!(output.take::<Total>(0).unwrap(), None);
+        assert_eq!(output.take::<Total>(1).unwrap(), Some(Total("3000".into())));
+    }
+}
@@ -0,0 +1,72 @@
+use super::{AttributeValueConvertible, ConvertError, Expression};
+
+/// Objects with a version attribute for optimistic locking.
+///
+/// The [`Dynamodel`](crate::Dynamodel) macro implements this trait when a field has the
+/// `#[dynamodel(version)]` attribute. The field must be an integer, and `0` means the object
+/// has never been saved.
+///
+/// Before saving an object, call [`Versioned::increment_version`] and put the item with the
+/// returned condition. If another writer has saved the item in the meantime, the condition
+/// fails and the write is rejected instead of silently overwriting the other update.
+///
+/// ```rust
+/// use dynamodel::{Dynamodel, Versioned};
+/// # use aws_sdk_dynamodb::types::AttributeValue;
+///
+/// #[derive(Dynamodel)]
+/// #[dynamodel(rename_all = "PascalCase")]
+/// struct Video {
+///     #[dynamodel(rename = "PK")]
+///     id: String,
+///     #[dynamodel(version)]
+///     revision: u32,
+/// }
+///
+/// let mut video = Video { id: "7cf27a02".into(), revision: 0 };
+///
+/// // A new item must not exist yet.
+/// let condition = video.increment_version().unwrap();
+/// assert_eq!(video.revision, 1);
+/// assert_eq!(
+///     condition.condition_expression(),
+///     Some("attribute_not_exists(#dynamodel_version)"),
+/// );
+///
+/// // A saved item must still have the previous version.
+/// let condition = video.increment_version().unwrap();
+/// assert_eq!(video.revision, 2);
+/// assert_eq!(
+///     condition.condition_expression(),
+///     Some("#dynamodel_version = :dynamodel_version"),
+/// );
+/// assert_eq!(condition.names()["#dynamodel_version"], "Revision");
+/// assert_eq!(
+///     condition.values()[":dynamodel_version"],
+///     AttributeValue::N("1".into()),
+/// );
+/// ```
+pub trait Versioned {
+    /// The name of the version attribute.
+    const VERSION_ATTRIBUTE: &'static str;
+
+    /// Increments the version and returns the condition expression to save the object with.
+    ///
+    /// Returns [`ConvertError::NumberOverflow`] without changing the version if it is already the
+    /// maximum value of its type.
+    fn increment_version(&mut self) -> Result<Expression, ConvertError>;
+
+    /// Returns the condition that the stored item has the `previous` version,
+    /// or that the item doesn't exist if `previous` is `None`.
+    fn version_condition<V>(previous: Option<V>) -> Expression
+    where
+        V: AttributeValueConvertible,
+    {
+        let condition = match previous {
+            Some(version) => Expression::condition("#dynamodel_version = :dynamodel_version")
+                .value(":dynamodel_version", version),
+            None => Expression::condition("attribute_not_exists(#dynamodel_version)"),
+        };
+        condition.name("#dynamodel_version", Self::VERSION_ATTRIBUTE)
+    }
+}
@@ -2,9 +2,10 @@ use self::RenameRule::*;
 use proc_macro_error::abort;
 
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum RenameRule {
     /// Don't apply a default rename rule.
+    #[default]
     None,
     /// Rename direct children to "lowercase" style.
     LowerCase,
@@ -125,12 +126,6 @@ impl RenameRule {
     }
 }
 
-impl Default for RenameRule {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,8 +7,8 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use proc_macro_error::{abort, proc_macro_error};
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
-use types::{NamedField, NamedVariant};
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput};
+use types::{fallible_token, NamedField, NamedVariant, ReadMode, WriteMode};
 
 // The main struct we get from parsing the attributes
 // Ref: https://github.com/TedDriggs/darling?tab=readme-ov-file#shape-validation
@@ -25,20 +25,100 @@ struct TargetStruct {
     rename_all: Option<syn::Lit>,
     extra: Option<darling::Result<syn::Path>>,
     tag: Option<String>,
+    by_ref: Option<bool>,
+    key_provider: Option<darling::Result<syn::Path>>,
+    sign: Option<darling::Result<syn::Path>>,
+    #[darling(rename = "crate")]
+    krate: Option<darling::Result<syn::Path>>,
 }
 
 impl TargetStruct {
     fn validate(self) -> darling::Result<Self> {
+        if let Some(f) = self
+            .fields()
+            .into_iter()
+            .find_map(|f| f.try_into.as_ref())
+            .filter(|_| self.is_by_ref())
+        {
+            abort! {
+                f.span(), "Invalid attribute #[dynamodel(try_into = ...)]";
+                note = "Objects with the `by_ref` attribute can't have fallible `try_into` fields.";
+                help = "Try removing either `by_ref` or `try_into`.";
+            }
+        }
+
+        if let Some(f) = self
+            .fields()
+            .into_iter()
+            .find(|f| f.is_marked_fallible() || f.is_json() || f.compress.is_some())
+            .filter(|_| self.is_by_ref())
+        {
+            abort! {
+                f.ty.span(), "Invalid attribute #[dynamodel(by_ref)]";
+                note = "Objects with the `by_ref` attribute can't have `fallible`, `json` or `compress` fields, which can fail to convert.";
+                help = "Try removing `by_ref` and converting with `try_into_item`.";
+            }
+        }
+
         match &self.data {
             darling::ast::Data::Struct(fields) => {
                 for field in fields.fields.iter() {
                     field.validate();
                 }
+
+                if let Some(field) = fields.fields.iter().filter(|f| f.is_version()).nth(1) {
+                    abort! {
+                        field.ident.span(), "Invalid attribute #[dynamodel(version)]";
+                        note = "Only one field can have the `version` attribute.";
+                        help = "Try removing the `version` attribute from the other fields.";
+                    }
+                }
+
+                for (name, is_set) in [
+                    (
+                        "created_at",
+                        types::Field::is_created_at as fn(&types::Field) -> bool,
+                    ),
+                    ("updated_at", types::Field::is_updated_at),
+                    ("ttl", types::Field::is_ttl),
+                ] {
+                    if let Some(field) = fields.fields.iter().filter(|f| is_set(f)).nth(1) {
+                        abort! {
+                            field.ident.span(), "Invalid attribute #[dynamodel({})]", name;
+                            note = "Only one field can have the `{}` attribute.", name;
+                            help = "Try removing the attribute from the other fields.";
+                        }
+                    }
+                }
             }
             darling::ast::Data::Enum(variants) => {
                 for variant in variants {
                     variant.validate();
                 }
+
+                if self.sign.is_some() {
+                    abort! {
+                        self.ident.span(), "Invalid attribute #[dynamodel(sign = ...)]";
+                        note = "The `sign` attribute is only available on structs.";
+                        help = "Try removing the `sign` attribute.";
+                    }
+                }
+            }
+        }
+
+        if self.is_by_ref() && (self.is_encrypted() || self.sign.is_some()) {
+            abort! {
+                self.ident.span(), "Invalid attribute #[dynamodel(by_ref)]";
+                note = "Objects with the `by_ref` attribute can't have `encrypt` fields or the `sign` attribute.";
+                help = "Try removing either `by_ref` or the others.";
+            }
+        }
+
+        if self.key_provider.is_none() && (self.is_encrypted() || self.sign.is_some()) {
+            abort! {
+                self.ident.span(), "Missing attribute #[dynamodel(key_provider = ...)]";
+                note = "Objects with `encrypt` fields or the `sign` attribute need a key provider.";
+                help = "Try adding `#[dynamodel(key_provider = \"path::to::function\")]` returning a `KeyProvider`.";
             }
         }
 
@@ -58,6 +138,47 @@ impl TargetStruct {
         }
     }
 
+    fn key_provider(&self) -> Option<syn::Path> {
+        match self.key_provider.clone().transpose() {
+            Ok(v) => v,
+            Err(err) => {
+                abort! {
+                    err.span(), "Invalid attribute #[dynamodel(key_provider = ...)]";
+                    note = "Invalid argument for `key_provider` attribute. Only paths are allowed.";
+                    help = "Try formating the argument like `path::to::function` or `\"path::to::function\"`";
+                }
+            }
+        }
+    }
+
+    // The path to the `KeySchema` constant whose key attributes the signature covers.
+    fn sign(&self) -> Option<syn::Path> {
+        match self.sign.clone().transpose() {
+            Ok(v) => v,
+            Err(err) => {
+                abort! {
+                    err.span(), "Invalid attribute #[dynamodel(sign = ...)]";
+                    note = "Invalid argument for `sign` attribute. Only paths are allowed.";
+                    help = "Try formating the argument like `path::to::KEY_SCHEMA` or `\"path::to::KEY_SCHEMA\"`";
+                }
+            }
+        }
+    }
+
+    // The path to the dynamodel crate, which the generated code imports as `_dynamodel`.
+    fn krate(&self) -> syn::Path {
+        match self.krate.clone().transpose() {
+            Ok(v) => v.unwrap_or_else(|| syn::parse_quote!(::dynamodel)),
+            Err(err) => {
+                abort! {
+                    err.span(), "Invalid attribute #[dynamodel(crate = ...)]";
+                    note = "Invalid argument for `crate` attribute. Only paths are allowed.";
+                    help = "Try formating the argument like `\"path::to::dynamodel\"`";
+                }
+            }
+        }
+    }
+
     fn rename_rule(&self) -> RenameRule {
         self.rename_all
             .as_ref()
@@ -65,34 +186,181 @@ impl TargetStruct {
             .unwrap_or_default()
     }
 
-    fn impl_traits(self, from_impl: TokenStream2, try_from_impl: TokenStream2) -> TokenStream {
+    // The fields of the struct, or of all the enum variants.
+    fn fields(&self) -> Vec<&types::Field> {
+        match &self.data {
+            darling::ast::Data::Struct(fields) => fields.iter().collect(),
+            darling::ast::Data::Enum(variants) => {
+                variants.iter().flat_map(|v| v.fields.iter()).collect()
+            }
+        }
+    }
+
+    // Whether any field converts into an `AttributeValue` with a fallible `try_into` function,
+    // serde or encryption, or the item is signed.
+    fn is_fallible(&self) -> bool {
+        self.fields().into_iter().any(types::Field::is_fallible) || self.sign.is_some()
+    }
+
+    fn is_encrypted(&self) -> bool {
+        self.fields().into_iter().any(types::Field::is_encrypted)
+    }
+
+    fn is_by_ref(&self) -> bool {
+        self.by_ref.is_some_and(|v| v)
+    }
+
+    // The functions of `try_from_item` fields take a `HashMap`, so the owned conversions from
+    // other item maps convert `item` into one first.
+    fn item_into_hash_map(&self) -> Option<TokenStream2> {
+        self.fields()
+            .into_iter()
+            .any(types::Field::has_try_from_item)
+            .then(|| quote! { let mut item = _dynamodel::ItemMap::into_hash_map(item); })
+    }
+
+    // `from_impl` is `None` if the conversion into an item map is fallible.
+    // `to_item_impl` is `None` unless the object has the `by_ref` attribute.
+    // `try_from_impl` reads a borrowed `HashMap` and `try_from_owned_impl` takes the values out of
+    // any item map.
+    fn impl_traits(
+        self,
+        from_impl: Option<TokenStream2>,
+        to_item_impl: Option<TokenStream2>,
+        try_into_impl: TokenStream2,
+        try_from_impl: TokenStream2,
+        try_from_owned_impl: TokenStream2,
+    ) -> TokenStream {
         let ident = self.ident;
         let (imp, ty, whr) = self.generics.split_for_impl();
 
+        let by_ref_impl = to_item_impl.map(|to_item_impl| {
+            quote! {
+                impl #imp #ident #ty #whr {
+                    /// Converts into a `HashMap<String, AttributeValue>` without consuming `self`.
+                    pub fn to_item(&self) -> ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue> {
+                        self.to_item_map()
+                    }
+
+                    /// Converts into any `ItemMap` without consuming `self`.
+                    pub fn to_item_map<DynamodelItemMap: _dynamodel::ItemMap>(&self) -> DynamodelItemMap {
+                        let value = self;
+                        #to_item_impl
+                    }
+                }
+
+                impl #imp ::std::convert::From<&#ident #ty> for ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue> #whr {
+                    fn from(value: &#ident #ty) -> Self {
+                        value.to_item()
+                    }
+                }
+
+                impl #imp _dynamodel::ToAttributeValue for #ident #ty #whr {
+                    fn to_attribute_value(&self) -> _dynamodel::aws_sdk_dynamodb::types::AttributeValue {
+                        _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(self.to_item())
+                    }
+                }
+            }
+        });
+
+        let fallible = from_impl.is_none();
+        let (into_impl, into_attribute_value) = match from_impl {
+            Some(from_impl) => (
+                quote! {
+                    impl #imp #ident #ty #whr {
+                        /// Converts into any `ItemMap`.
+                        pub fn into_item_map<DynamodelItemMap: _dynamodel::ItemMap>(self) -> DynamodelItemMap {
+                            let value = self;
+                            #from_impl
+                        }
+                    }
+
+                    impl #imp ::std::convert::From<#ident #ty> for ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue> #whr {
+                        fn from(value: #ident #ty) -> Self {
+                            value.into_item_map()
+                        }
+                    }
+                },
+                Some(quote! {
+                    fn into_attribute_value(self) -> _dynamodel::aws_sdk_dynamodb::types::AttributeValue {
+                        _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(self.into())
+                    }
+                }),
+            ),
+            // Fallible objects leave `into_attribute_value` to the default, which fails to compile
+            // when it is used.
+            None => (
+                quote! {
+                    impl #imp ::std::convert::TryFrom<#ident #ty> for ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue> #whr {
+                        type Error = _dynamodel::ConvertError;
+
+                        fn try_from(value: #ident #ty) -> ::std::result::Result<Self, Self::Error> {
+                            value.try_into_item()
+                        }
+                    }
+                },
+                None,
+            ),
+        };
+
         quote! {
-            impl #imp ::std::convert::From<#ident #ty> for ::std::collections::HashMap<String, ::aws_sdk_dynamodb::types::AttributeValue> #whr {
-                fn from(value: #ident #ty) -> Self {
-                    #from_impl
+            #into_impl
+
+            #by_ref_impl
+
+            impl #imp #ident #ty #whr {
+                /// Converts into a `HashMap<String, AttributeValue>`, or returns an error if any
+                /// field can't be converted.
+                pub fn try_into_item(self) -> ::std::result::Result<::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue>, _dynamodel::ConvertError> {
+                    self.try_into_item_map()
+                }
+
+                /// Converts into any `ItemMap`, or returns an error if any
+                /// field can't be converted.
+                pub fn try_into_item_map<DynamodelItemMap: _dynamodel::ItemMap>(self) -> ::std::result::Result<DynamodelItemMap, _dynamodel::ConvertError> {
+                    let value = self;
+                    ::std::result::Result::Ok({ #try_into_impl })
+                }
+
+                /// Converts from any `ItemMap`, taking the values out of it.
+                #[allow(unused_mut)]
+                pub fn try_from_item_map<DynamodelItemMap: _dynamodel::ItemMap>(mut item: DynamodelItemMap) -> ::std::result::Result<Self, _dynamodel::ConvertError> {
+                    #try_from_owned_impl
                 }
             }
 
-            impl #imp ::std::convert::TryFrom<::std::collections::HashMap<String, ::aws_sdk_dynamodb::types::AttributeValue>> for #ident #ty #whr {
-                type Error = ::dynamodel::ConvertError;
+            impl #imp ::std::convert::TryFrom<::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue>> for #ident #ty #whr {
+                type Error = _dynamodel::ConvertError;
 
-                fn try_from(item: ::std::collections::HashMap<String, ::aws_sdk_dynamodb::types::AttributeValue>) -> ::std::result::Result<Self, Self::Error> {
-                    #try_from_impl
+                fn try_from(item: ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue>) -> ::std::result::Result<Self, Self::Error> {
+                    Self::try_from_item_map(item)
                 }
             }
 
-            impl #imp ::dynamodel::AttributeValueConvertible for #ident #ty #whr {
-                fn into_attribute_value(self) -> ::aws_sdk_dynamodb::types::AttributeValue {
-                    ::aws_sdk_dynamodb::types::AttributeValue::M(self.into())
+            impl #imp _dynamodel::AttributeValueConvertible for #ident #ty #whr {
+                const FALLIBLE: bool = #fallible;
+
+                #into_attribute_value
+
+                fn try_into_attribute_value(self) -> ::std::result::Result<_dynamodel::aws_sdk_dynamodb::types::AttributeValue, _dynamodel::ConvertError> {
+                    self.try_into_item().map(_dynamodel::aws_sdk_dynamodb::types::AttributeValue::M)
                 }
 
-                fn try_from_attribute_value(value: &::aws_sdk_dynamodb::types::AttributeValue) -> ::std::result::Result<Self, ::dynamodel::ConvertError> {
+                fn try_from_attribute_value(value: &_dynamodel::aws_sdk_dynamodb::types::AttributeValue) -> ::std::result::Result<Self, _dynamodel::ConvertError> {
                     value.as_m()
-                        .map_err(|e| ::dynamodel::ConvertError::AttributeValueUnmatched("M".into(), e.clone()))
-                        .and_then(|item| Self::try_from(item.clone()))
+                        .map_err(|e| _dynamodel::ConvertError::AttributeValueUnmatched("M".into(), e.clone()))
+                        .and_then(<Self as _dynamodel::AttributeValueConvertible>::try_from_item)
+                }
+
+                fn try_from_attribute_value_owned(value: _dynamodel::aws_sdk_dynamodb::types::AttributeValue) -> ::std::result::Result<Self, _dynamodel::ConvertError> {
+                    match value {
+                        _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(item) => Self::try_from(item),
+                        value => Self::try_from_attribute_value(&value),
+                    }
+                }
+
+                fn try_from_item(item: &::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue>) -> ::std::result::Result<Self, _dynamodel::ConvertError> {
+                    #try_from_impl
                 }
             }
         }.into()
@@ -102,16 +370,11 @@ impl TargetStruct {
         let ident = &self.ident;
         let rename_rule = self.rename_rule();
 
-        let init_hashmap = match self.extra() {
-            Some(path) => quote! { #path(&value); },
-            None => quote! { ::std::collections::HashMap::new(); },
-        };
-
         let set_tag = if let Some(tag) = self.tag.as_ref() {
             quote! {
                 item.insert(
                     #tag.into(),
-                    ::aws_sdk_dynamodb::types::AttributeValue::S(stringify!(#ident).into()),
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue::S(stringify!(#ident).into()),
                 );
             }
         } else {
@@ -128,24 +391,180 @@ impl TargetStruct {
             .map(|f| f.into_named(&rename_rule))
             .collect();
 
-        let set_key_values = fields
+        // The number of keys the fields, the tag and the signature insert.
+        let sign = self.sign();
+        let capacity = fields.iter().filter(|f| !f.skip_into()).count()
+            + usize::from(self.tag.is_some())
+            + usize::from(sign.is_some());
+
+        // The encrypted fields and the signature use the key provider as `__dynamodel_keys`.
+        let encrypted: Vec<&str> = fields
             .iter()
-            .filter_map(|f| f.set_key_value_pair_token(|v| quote!(value.#v)));
+            .filter(|f| f.is_encrypted())
+            .map(NamedField::name)
+            .collect();
+        let init_keys = self
+            .key_provider()
+            .filter(|_| !encrypted.is_empty() || sign.is_some())
+            .map(|path| quote! { let __dynamodel_keys = #path(); });
+
+        let extra = self.extra();
+        let init_hashmap = |mode: WriteMode| match (&extra, mode) {
+            (Some(path), WriteMode::Borrowed) => quote! {
+                ::std::iter::Extend::extend(&mut item, #path(value));
+            },
+            (Some(path), _) => quote! { ::std::iter::Extend::extend(&mut item, #path(&value)); },
+            (None, _) => quote!(),
+        };
 
-        let set_named_fields = fields.iter().map(NamedField::set_named_field_token);
+        let set_key_values = |mode: WriteMode| {
+            let init_hashmap = init_hashmap(mode);
+            let get_value = |v: &Option<syn::Ident>| match mode {
+                WriteMode::Borrowed => quote!(&value.#v),
+                _ => quote!(value.#v),
+            };
+            let set_key_values = fields
+                .iter()
+                .filter_map(|f| f.set_key_value_pair_token(get_value, mode));
+            let sign_item = sign.as_ref().map(|schema| {
+                let sign = fallible_token(
+                    quote! {
+                        _dynamodel::encryption::sign(&__dynamodel_keys, &#schema, &mut item, &[#(#encrypted),*])
+                    },
+                    mode,
+                );
+                quote! { #sign; }
+            });
 
-        let from_impl = quote! {
-            let mut item: Self = #init_hashmap
-            #(#set_key_values)*
-            #set_tag
-            item
+            quote! {
+                #init_keys
+                let mut item = <DynamodelItemMap as _dynamodel::ItemMap>::with_capacity(#capacity);
+                #init_hashmap
+                #(#set_key_values)*
+                #set_tag
+                #sign_item
+                item
+            }
         };
 
-        let try_from_impl = quote! {
-            Ok(Self { #(#set_named_fields,)* })
+        let from_impl = (!self.is_fallible()).then(|| set_key_values(WriteMode::Owned));
+        let to_item_impl = self
+            .is_by_ref()
+            .then(|| set_key_values(WriteMode::Borrowed));
+        let try_into_impl = set_key_values(WriteMode::Fallible);
+
+        let item_into_hash_map = self.item_into_hash_map();
+        let try_from_impl = |mode: ReadMode| {
+            let into_hash_map = item_into_hash_map
+                .as_ref()
+                .filter(|_| mode == ReadMode::Owned);
+            let bindings = fields
+                .iter()
+                .filter_map(|f| f.try_from_item_binding_token(mode));
+            let set_named_fields = fields.iter().map(|f| f.set_named_field_token(mode));
+            let verify_item = sign.as_ref().map(|schema| {
+                let item = match mode {
+                    ReadMode::Borrowed => quote!(item),
+                    ReadMode::Owned => quote!(&item),
+                };
+                quote! {
+                    _dynamodel::encryption::verify(&__dynamodel_keys, &#schema, #item, &[#(#encrypted),*])?;
+                }
+            });
+
+            quote! {
+                #init_keys
+                #into_hash_map
+                #verify_item
+                #(#bindings)*
+                Ok(Self { #(#set_named_fields,)* })
+            }
         };
 
-        self.impl_traits(from_impl, try_from_impl)
+        let versioned_impl = fields
+            .iter()
+            .find(|f| f.is_version())
+            .map(|f| self.versioned_impl(f));
+
+        let timestamped_impl = self.timestamped_impl(&fields);
+        let ttl_impl = fields.iter().find(|f| f.is_ttl()).map(|f| self.ttl_impl(f));
+
+        let mut tokens = TokenStream2::from(self.impl_traits(
+            from_impl,
+            to_item_impl,
+            try_into_impl,
+            try_from_impl(ReadMode::Borrowed),
+            try_from_impl(ReadMode::Owned),
+        ));
+        tokens.extend(versioned_impl);
+        tokens.extend(timestamped_impl);
+        tokens.extend(ttl_impl);
+        tokens.into()
+    }
+
+    fn ttl_impl(&self, field: &NamedField) -> TokenStream2 {
+        let ident = &self.ident;
+        let (imp, ty, whr) = self.generics.split_for_impl();
+        let name = field.name();
+
+        quote! {
+            impl #imp _dynamodel::TimeToLive for #ident #ty #whr {
+                const TTL_ATTRIBUTE: &'static str = #name;
+            }
+        }
+    }
+
+    fn timestamped_impl(&self, fields: &[NamedField]) -> Option<TokenStream2> {
+        let created_at = fields.iter().find(|f| f.is_created_at());
+        let updated_at = fields.iter().find(|f| f.is_updated_at());
+        if created_at.is_none() && updated_at.is_none() {
+            return None;
+        }
+
+        let ident = &self.ident;
+        let (imp, ty, whr) = self.generics.split_for_impl();
+
+        let attribute = |f: Option<&NamedField>| match f.map(NamedField::name) {
+            Some(name) => quote! { Some(#name) },
+            None => quote! { None },
+        };
+        let now = |f: Option<&NamedField>| match f.map(NamedField::now_token) {
+            Some(now) => quote! { Some(#now) },
+            None => quote! { None },
+        };
+
+        let created_at_attribute = attribute(created_at);
+        let updated_at_attribute = attribute(updated_at);
+        let created_at_now = now(created_at);
+        let updated_at_now = now(updated_at);
+
+        Some(quote! {
+            impl #imp _dynamodel::Timestamped for #ident #ty #whr {
+                const CREATED_AT_ATTRIBUTE: Option<&'static str> = #created_at_attribute;
+                const UPDATED_AT_ATTRIBUTE: Option<&'static str> = #updated_at_attribute;
+
+                fn timestamp_update() -> _dynamodel::Expression {
+                    Self::timestamp_update_with(#created_at_now, #updated_at_now)
+                }
+            }
+        })
+    }
+
+    fn versioned_impl(&self, field: &NamedField) -> TokenStream2 {
+        let ident = &self.ident;
+        let (imp, ty, whr) = self.generics.split_for_impl();
+        let name = field.name();
+        let increment_version = field.increment_version_token();
+
+        quote! {
+            impl #imp _dynamodel::Versioned for #ident #ty #whr {
+                const VERSION_ATTRIBUTE: &'static str = #name;
+
+                fn increment_version(&mut self) -> ::std::result::Result<_dynamodel::Expression, _dynamodel::ConvertError> {
+                    #increment_version
+                }
+            }
+        }
     }
 
     fn enum_token(self) -> TokenStream {
@@ -161,21 +580,36 @@ impl TargetStruct {
             .map(|v| v.into_named(&rename_rule))
             .collect();
 
-        let set_key_value_branch = variants.iter().map(NamedVariant::set_key_value);
-        let get_values = variants.iter().map(NamedVariant::get_value_token);
-
-        let from_impl = quote! {
-            match value {
-                #(#ident::#set_key_value_branch)*
+        let set_key_values = |mode: WriteMode| {
+            let set_key_value_branch = variants.iter().map(|v| v.set_key_value(mode));
+            quote! {
+                match value {
+                    #(#ident::#set_key_value_branch)*
+                }
             }
         };
 
-        let try_from_impl = quote! {
-            #(#get_values)*
-            Err(::dynamodel::ConvertError::VariantNotFound)
+        let from_impl = (!self.is_fallible()).then(|| set_key_values(WriteMode::Owned));
+        let to_item_impl = self
+            .is_by_ref()
+            .then(|| set_key_values(WriteMode::Borrowed));
+        let try_into_impl = set_key_values(WriteMode::Fallible);
+
+        let try_from_impl = |mode: ReadMode| {
+            let get_values = variants.iter().map(|v| v.get_value_token(mode));
+            quote! {
+                #(#get_values)*
+                Err(_dynamodel::ConvertError::VariantNotFound)
+            }
         };
 
-        self.impl_traits(from_impl, try_from_impl)
+        self.impl_traits(
+            from_impl,
+            to_item_impl,
+            try_into_impl,
+            try_from_impl(ReadMode::Borrowed),
+            try_from_impl(ReadMode::Owned),
+        )
     }
 
     fn enum_token_tagged(self) -> TokenStream {
@@ -193,39 +627,64 @@ impl TargetStruct {
             .map(|v| v.into_named(&rename_rule))
             .collect();
 
-        let set_key_value_branch = variants.iter().map(|v| v.set_tagged_key_value(&tag));
-        let get_values = variants.iter().map(NamedVariant::get_value_token_tagged);
-
-        let from_impl = quote! {
-            match value {
-                #(#ident::#set_key_value_branch)*
+        let set_key_values = |mode: WriteMode| {
+            let set_key_value_branch = variants.iter().map(|v| v.set_tagged_key_value(&tag, mode));
+            quote! {
+                match value {
+                    #(#ident::#set_key_value_branch)*
+                }
             }
         };
 
-        let try_from_impl = quote! {
-            let tag = item
-                .get(#tag_str)
-                .ok_or(::dynamodel::ConvertError::FieldNotSet(#tag_str.into()))
-                .and_then(|v| {
-                    v.as_s().map_err(|e| {
-                        ::dynamodel::ConvertError::AttributeValueUnmatched("S".into(), e.clone())
-                    })
-                })
-                .map(|v| v.clone())?;
+        let from_impl = (!self.is_fallible()).then(|| set_key_values(WriteMode::Owned));
+        let to_item_impl = self
+            .is_by_ref()
+            .then(|| set_key_values(WriteMode::Borrowed));
+        let try_into_impl = set_key_values(WriteMode::Fallible);
+
+        let item_into_hash_map = self.item_into_hash_map();
+        let try_from_impl = |mode: ReadMode| {
+            let into_hash_map = item_into_hash_map
+                .as_ref()
+                .filter(|_| mode == ReadMode::Owned);
+            let get_values = variants.iter().map(|v| v.get_value_token_tagged(mode));
+            // The variants take the values out of `item`, so the tag must not borrow it.
+            let own_tag = (mode == ReadMode::Owned).then(|| quote! { let tag = tag.clone(); });
 
-            match tag.as_str() {
-                #(#get_values,)*
-                _ => {},
-            }
+            quote! {
+                #into_hash_map
+                let tag = item
+                    .get(#tag_str)
+                    .ok_or(_dynamodel::ConvertError::FieldNotSet(#tag_str.into()))
+                    .and_then(|v| {
+                        v.as_s().map_err(|e| {
+                            _dynamodel::ConvertError::AttributeValueUnmatched("S".into(), e.clone())
+                        })
+                    })?;
+                #own_tag
+
+                match tag.as_str() {
+                    #(#get_values,)*
+                    _ => {},
+                }
 
-            Err(::dynamodel::ConvertError::VariantNotFound)
+                Err(_dynamodel::ConvertError::VariantNotFound)
+            }
         };
 
-        self.impl_traits(from_impl, try_from_impl)
+        self.impl_traits(
+            from_impl,
+            to_item_impl,
+            try_into_impl,
+            try_from_impl(ReadMode::Borrowed),
+            try_from_impl(ReadMode::Owned),
+        )
     }
 
     fn token_stream(self) -> TokenStream {
-        match self.data {
+        let krate = self.krate();
+
+        let tokens = TokenStream2::from(match self.data {
             darling::ast::Data::Struct(_) => self.struct_token(),
             darling::ast::Data::Enum(_) => {
                 if self.tag.is_some() {
@@ -234,7 +693,16 @@ impl TargetStruct {
                     self.enum_token()
                 }
             }
+        });
+
+        // Like serde, the impls are in an anonymous const to import the crate under a fixed name.
+        quote! {
+            const _: () = {
+                use #krate as _dynamodel;
+                #tokens
+            };
         }
+        .into()
     }
 }
 
@@ -2,9 +2,48 @@ use super::case::RenameRule;
 use darling::{FromField, FromVariant};
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::spanned::Spanned;
 
+// How the generated code converts a model into an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    // Consumes the model with `AttributeValueConvertible::into_attribute_value`.
+    Owned,
+    // Consumes the model with `AttributeValueConvertible::try_into_attribute_value` in a function
+    // returning `Result<_, ConvertError>`.
+    Fallible,
+    // Borrows the model with `ToAttributeValue::to_attribute_value`, so the values are references.
+    Borrowed,
+}
+
+impl WriteMode {
+    // The value as a reference for the functions borrowing it.
+    fn as_ref(self, value: TokenStream) -> TokenStream {
+        match self {
+            WriteMode::Borrowed => value,
+            _ => quote! { &#value },
+        }
+    }
+
+    // The value as an owned one for the functions taking it.
+    fn to_owned(self, value: TokenStream) -> TokenStream {
+        match self {
+            WriteMode::Borrowed => quote! { ::std::clone::Clone::clone(#value) },
+            _ => value,
+        }
+    }
+}
+
+// How the generated code reads a model from an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadMode {
+    // Reads the values from `item: &HashMap<String, AttributeValue>`.
+    Borrowed,
+    // Takes the values out of `item: HashMap<String, AttributeValue>` with `remove`.
+    Owned,
+}
+
 #[derive(Debug)]
 pub struct NamedField {
     name: String,
@@ -24,6 +63,10 @@ impl NamedField {
         self.field.into.as_ref()
     }
 
+    fn attr_try_into(&self) -> Option<&syn::Expr> {
+        self.field.try_into.as_ref()
+    }
+
     fn attr_try_from(&self) -> Option<&syn::Expr> {
         self.field.try_from.as_ref()
     }
@@ -36,19 +79,140 @@ impl NamedField {
         self.field.skip_into.as_ref().is_some_and(|v| *v)
     }
 
-    pub fn set_named_field_token(&self) -> TokenStream {
+    pub fn is_version(&self) -> bool {
+        self.field.is_version()
+    }
+
+    pub fn increment_version_token(&self) -> TokenStream {
+        let field_name = self.ident();
+        let ty = self.ty();
+
+        quote! {
+            let previous = self.#field_name;
+            self.#field_name = previous.checked_add(1).ok_or_else(|| {
+                _dynamodel::ConvertError::NumberOverflow(
+                    stringify!(#ty).into(),
+                    format!("{previous} + 1"),
+                )
+            })?;
+            ::std::result::Result::Ok(Self::version_condition((previous != 0).then_some(previous)))
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn is_created_at(&self) -> bool {
+        self.field.is_created_at()
+    }
+
+    pub fn is_updated_at(&self) -> bool {
+        self.field.is_updated_at()
+    }
+
+    pub fn is_ttl(&self) -> bool {
+        self.field.is_ttl()
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.field.is_encrypted()
+    }
+
+    // The current time formatted for this `created_at` or `updated_at` field.
+    pub fn now_token(&self) -> TokenStream {
+        let ty = self.ty();
+        let ty = option_inner(ty).unwrap_or(ty);
+        let format = self.field.timestamp_format();
+
+        quote! {
+            #format.format(&<#ty as _dynamodel::Timestamp>::now())
+        }
+    }
+
+    // The local variable for the value of the `try_from_item` function.
+    fn try_from_item_ident(&self) -> syn::Ident {
+        format_ident!("__dynamodel_{}", self.ident().as_ref().unwrap())
+    }
+
+    // Reads this `try_from_item` field before the other fields remove their values from `item`.
+    pub fn try_from_item_binding_token(&self, mode: ReadMode) -> Option<TokenStream> {
+        let f = self
+            .attr_try_from_item()
+            .filter(|_| mode == ReadMode::Owned)?;
+        let local = self.try_from_item_ident();
+        Some(quote! { let #local = #f(&item)?; })
+    }
+
+    pub fn set_named_field_token(&self, mode: ReadMode) -> TokenStream {
         let field_name = self.ident();
         let ty = self.ty();
         let hash_key = self.name.as_str();
 
         if let Some(f) = self.attr_try_from_item() {
-            return quote! { #field_name: #f(&item)? };
+            return match mode {
+                ReadMode::Borrowed => quote! { #field_name: #f(item)? },
+                ReadMode::Owned => {
+                    let local = self.try_from_item_ident();
+                    quote! { #field_name: #local }
+                }
+            };
         }
 
-        let get_value = quote! { item.get(#hash_key) };
+        let get_value = match mode {
+            ReadMode::Borrowed => quote! { item.get(#hash_key) },
+            ReadMode::Owned => quote! { _dynamodel::ItemMap::remove(&mut item, #hash_key) },
+        };
+
+        // Encrypted and compressed values are decrypted and decompressed into new ones, so they are
+        // read as owned values.
+        let compression = self.field.compression();
+        let (get_value, mode) = if self.field.is_encrypted() || compression.is_some() {
+            let mut value = match mode {
+                ReadMode::Borrowed => quote! { #get_value.cloned() },
+                ReadMode::Owned => get_value,
+            };
+            if self.field.is_encrypted() {
+                value = quote! {
+                    #value
+                        .map(|v| _dynamodel::encryption::decrypt(&__dynamodel_keys, #hash_key, v))
+                        .transpose()?
+                };
+            }
+            if compression.is_some() {
+                value = quote! {
+                    #value
+                        .map(|v| _dynamodel::compression::decompress(#hash_key, v))
+                        .transpose()?
+                };
+            }
+            (value, ReadMode::Owned)
+        } else {
+            (get_value, mode)
+        };
         let field_not_set = not_set_err(field_name);
 
+        if let Some(parse) = self.parse_token(mode) {
+            return if is_optional(ty) {
+                quote! {
+                    #field_name: #get_value
+                        .map(#parse)
+                        .transpose()?
+                }
+            } else {
+                quote! {
+                    #field_name: #get_value
+                        .ok_or(#field_not_set)
+                        .and_then(#parse)?
+                }
+            };
+        }
+
         if let Some(f) = self.attr_try_from() {
+            let f = match mode {
+                ReadMode::Borrowed => quote! { #f },
+                ReadMode::Owned => quote! { |v| #f(&v) },
+            };
             return quote! {
                 #field_name: #get_value
                     .ok_or(#field_not_set)
@@ -56,22 +220,24 @@ impl NamedField {
             };
         }
 
+        let convert = try_from_attribute_value_token(mode);
+
         if is_optional(ty) {
             quote! {
                 #field_name: #get_value
-                    .map(::dynamodel::AttributeValueConvertible::try_from_attribute_value)
+                    .map(#convert)
                     .transpose()?
             }
         } else {
             quote! {
                 #field_name: #get_value
                     .ok_or(#field_not_set)
-                    .and_then(::dynamodel::AttributeValueConvertible::try_from_attribute_value)?
+                    .and_then(#convert)?
             }
         }
     }
 
-    fn set_key_value_pairs<T>(&self, get_value: T) -> TokenStream
+    fn set_key_value_pairs<T>(&self, get_value: T, mode: WriteMode) -> TokenStream
     where
         T: Fn(&Option<syn::Ident>) -> TokenStream,
     {
@@ -82,41 +248,157 @@ impl NamedField {
 
         let get_value_token = get_value(field_name);
 
+        if self.is_updated_at() {
+            let now = self.now_token();
+            return quote! {
+                item.insert(#hash_key, #now);
+            };
+        }
+
+        if self.is_created_at() {
+            let format = self.field.timestamp_format();
+            let v = mode.as_ref(quote!(v));
+            return if is_optional(ty) {
+                let now = self.now_token();
+                quote! {
+                    let v = match #get_value_token {
+                        Some(v) => #format.format(#v),
+                        None => #now,
+                    };
+                    item.insert(#hash_key, v);
+                }
+            } else {
+                quote! {
+                    let v = #get_value_token;
+                    item.insert(#hash_key, #format.format(#v));
+                }
+            };
+        }
+
+        if let Some(attribute_value) = self.format_token(mode) {
+            let attribute_value = self.encode_token(attribute_value, mode);
+            return if is_optional(ty) {
+                quote! {
+                    if let Some(v) = #get_value_token {
+                        item.insert(#hash_key, #attribute_value);
+                    }
+                }
+            } else {
+                quote! {
+                    let v = #get_value_token;
+                    item.insert(#hash_key, #attribute_value);
+                }
+            };
+        }
+
         if let Some(f) = self.attr_into() {
+            let v = mode.to_owned(quote!(v));
+            let attribute_value = self.encode_token(quote! { #f(#v) }, mode);
             return quote! {
                 let v = #get_value_token;
-                item.insert(#hash_key, #f(v));
+                item.insert(#hash_key, #attribute_value);
             };
         }
 
+        if let Some(f) = self.attr_try_into() {
+            let attribute_value = self.encode_token(quote! { #f(v)? }, mode);
+            return quote! {
+                let v = #get_value_token;
+                item.insert(#hash_key, #attribute_value);
+            };
+        }
+
+        let v = quote!(v);
+        let attribute_value = into_attribute_value_token(&v, mode);
+        let attribute_value = self.encode_token(attribute_value, mode);
+
         if is_optional(ty) {
             quote! {
                 if let Some(v) = #get_value_token {
-                    item.insert(
-                        #hash_key,
-                        ::dynamodel::AttributeValueConvertible::into_attribute_value(v),
-                    );
+                    item.insert(#hash_key, #attribute_value);
                 }
             }
         } else {
             quote! {
                 let v = #get_value_token;
-                item.insert(
-                    #hash_key,
-                    ::dynamodel::AttributeValueConvertible::into_attribute_value(v),
-                );
+                item.insert(#hash_key, #attribute_value);
             }
         }
     }
 
-    pub fn set_key_value_pair_token<T>(&self, get_value: T) -> Option<TokenStream>
+    // Compresses the `AttributeValue` of the field if it has `compress`, and then encrypts it with
+    // the `__dynamodel_keys` provider if it has `encrypt`.
+    fn encode_token(&self, attribute_value: TokenStream, mode: WriteMode) -> TokenStream {
+        let name = self.name.as_str();
+        let mut attribute_value = attribute_value;
+
+        if let Some(compression) = self.field.compression() {
+            attribute_value = fallible_token(
+                quote! { _dynamodel::compression::compress(#name, #compression, #attribute_value) },
+                mode,
+            );
+        }
+
+        if self.field.is_encrypted() {
+            attribute_value = fallible_token(
+                quote! { _dynamodel::encryption::encrypt(&__dynamodel_keys, #name, #attribute_value) },
+                mode,
+            );
+        }
+
+        attribute_value
+    }
+
+    // Reads an `AttributeValue` named `v` for the fields with `format` or `json`.
+    fn parse_token(&self, mode: ReadMode) -> Option<TokenStream> {
+        let v = match mode {
+            ReadMode::Borrowed => quote!(v),
+            ReadMode::Owned => quote!(&v),
+        };
+
+        if self.field.has_format() {
+            let format = self.field.timestamp_format();
+            return Some(quote! { |v| #format.parse(#v) });
+        }
+
+        if self.field.is_json() {
+            let name = self.name.as_str();
+            return Some(quote! {
+                |v| _dynamodel::json_attribute::from_attribute_value(#name, #v)
+            });
+        }
+
+        None
+    }
+
+    // Converts the value named `v` into an `AttributeValue` for the fields with `format` or `json`.
+    fn format_token(&self, mode: WriteMode) -> Option<TokenStream> {
+        let v = mode.as_ref(quote!(v));
+
+        if self.field.has_format() {
+            let format = self.field.timestamp_format();
+            return Some(quote! { #format.format(#v) });
+        }
+
+        if self.field.is_json() {
+            let name = self.name.as_str();
+            let json = quote! { _dynamodel::json_attribute::to_attribute_value(#name, #v) };
+            return Some(fallible_token(json, mode));
+        }
+
+        None
+    }
+
+    // Sets the key-value pair of this field to `item`.
+    // The token is in a function returning `Result<_, ConvertError>` if the mode is `Fallible`.
+    pub fn set_key_value_pair_token<T>(&self, get_value: T, mode: WriteMode) -> Option<TokenStream>
     where
         T: Fn(&Option<syn::Ident>) -> TokenStream,
     {
         if self.skip_into() {
             None
         } else {
-            Some(self.set_key_value_pairs(get_value))
+            Some(self.set_key_value_pairs(get_value, mode))
         }
     }
 }
@@ -131,6 +413,16 @@ pub struct Field {
     pub rename: Option<String>,
     pub skip_into: Option<bool>,
     pub try_from_item: Option<syn::Expr>,
+    pub version: Option<bool>,
+    pub created_at: Option<bool>,
+    pub updated_at: Option<bool>,
+    pub format: Option<syn::LitStr>,
+    pub ttl: Option<bool>,
+    pub try_into: Option<syn::Expr>,
+    pub json: Option<bool>,
+    pub compress: Option<syn::LitStr>,
+    pub encrypt: Option<bool>,
+    pub fallible: Option<bool>,
 }
 
 impl Field {
@@ -142,6 +434,248 @@ impl Field {
                 help = "Try removing either `try_from` or `try_from_item`.";
             }
         }
+
+        if self.into.is_some() && self.try_into.is_some() {
+            abort! {
+                self.into.clone().unwrap().span(), "Invalid attribute #[dynamodel(into = ..., try_into = ...)]";
+                note = "Either `into` or `try_into` can be set.";
+                help = "Try removing either `into` or `try_into`.";
+            }
+        }
+
+        if self.is_version() && (is_optional(&self.ty) || self.skip_into.is_some_and(|v| v)) {
+            abort! {
+                self.ty.span(), "Invalid attribute #[dynamodel(version)]";
+                note = "The version field must be an integer that is converted into the item.";
+                help = "Try changing the type to an integer like `u64` and removing `skip_into`.";
+            }
+        }
+
+        let time_attributes: Vec<&str> = [
+            ("created_at", self.is_created_at()),
+            ("updated_at", self.is_updated_at()),
+            ("ttl", self.is_ttl()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect();
+
+        if time_attributes.len() > 1 {
+            abort! {
+                self.ty.span(), "Invalid attribute #[dynamodel({})]", time_attributes.join(", ");
+                note = "Only one of `created_at`, `updated_at` and `ttl` can be set.";
+                help = "Try using separate fields for them.";
+            }
+        }
+
+        if let Some(name) = time_attributes.first().filter(|_| {
+            self.into.is_some()
+                || self.try_into.is_some()
+                || self.try_from.is_some()
+                || self.try_from_item.is_some()
+                || self.skip_into.is_some_and(|v| v)
+        }) {
+            abort! {
+                self.ty.span(), "Invalid attribute #[dynamodel({})]", name;
+                note = "Time fields are converted with their format, so `into`, `try_into`, `try_from`, `try_from_item` and `skip_into` can't be set.";
+                help = "Try removing them.";
+            }
+        }
+
+        if self.format.is_some() && self.is_ttl() {
+            abort! {
+                self.format.span(), "Invalid attribute #[dynamodel(format = ...)]";
+                note = "The `ttl` field is always stored as epoch seconds.";
+                help = "Try removing the `format` attribute.";
+            }
+        }
+
+        if self.format.is_some()
+            && (self.into.is_some()
+                || self.try_into.is_some()
+                || self.try_from.is_some()
+                || self.try_from_item.is_some())
+        {
+            abort! {
+                self.format.span(), "Invalid attribute #[dynamodel(format = ...)]";
+                note = "Fields with `format` are converted with it, so `into`, `try_into`, `try_from` and `try_from_item` can't be set.";
+                help = "Try removing either `format` or the others.";
+            }
+        }
+
+        if self.is_json()
+            && (self.format.is_some()
+                || self.is_timestamp()
+                || self.is_version()
+                || self.into.is_some()
+                || self.try_into.is_some()
+                || self.try_from.is_some()
+                || self.try_from_item.is_some())
+        {
+            abort! {
+                self.ty.span(), "Invalid attribute #[dynamodel(json)]";
+                note = "Fields with `json` are converted with serde, so `format`, `created_at`, `updated_at`, `ttl`, `version`, `into`, `try_into`, `try_from` and `try_from_item` can't be set.";
+                help = "Try removing either `json` or the others.";
+            }
+        }
+
+        if self.is_marked_fallible()
+            && (self.has_format()
+                || self.is_json()
+                || self.into.is_some()
+                || self.try_into.is_some())
+        {
+            abort! {
+                self.ty.span(), "Invalid attribute #[dynamodel(fallible)]";
+                note = "Fields with `fallible` are converted with `try_into_attribute_value`, so `format`, `created_at`, `updated_at`, `ttl`, `json`, `into` and `try_into` can't be set.";
+                help = "Try removing either `fallible` or the others.";
+            }
+        }
+
+        if self.compress.is_some()
+            && (self.is_timestamp() || self.is_version() || self.try_from_item.is_some())
+        {
+            abort! {
+                self.compress.span(), "Invalid attribute #[dynamodel(compress = ...)]";
+                note = "The `created_at`, `updated_at`, `ttl`, `version` and `try_from_item` fields can't be compressed.";
+                help = "Try removing the `compress` attribute.";
+            }
+        }
+
+        if self.is_encrypted()
+            && (self.is_timestamp() || self.is_version() || self.try_from_item.is_some())
+        {
+            abort! {
+                self.ident.span(), "Invalid attribute #[dynamodel(encrypt)]";
+                note = "The `created_at`, `updated_at`, `ttl`, `version` and `try_from_item` fields can't be encrypted.";
+                help = "Try removing the `encrypt` attribute.";
+            }
+        }
+
+        if self.compress.is_some() {
+            // Checks the algorithm eagerly to report the error on the attribute.
+            self.compression();
+        }
+
+        if self.has_format() {
+            // Checks the format eagerly to report the error on the attribute.
+            self.timestamp_format();
+        }
+    }
+
+    pub fn is_version(&self) -> bool {
+        self.version.is_some_and(|v| v)
+    }
+
+    pub fn is_created_at(&self) -> bool {
+        self.created_at.is_some_and(|v| v)
+    }
+
+    pub fn is_updated_at(&self) -> bool {
+        self.updated_at.is_some_and(|v| v)
+    }
+
+    pub fn is_ttl(&self) -> bool {
+        self.ttl.is_some_and(|v| v)
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.json.is_some_and(|v| v)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypt.is_some_and(|v| v)
+    }
+
+    // Whether the field has the `fallible` attribute because its type can fail to convert.
+    pub fn is_marked_fallible(&self) -> bool {
+        self.fallible.is_some_and(|v| v)
+    }
+
+    // The algorithm of the `compress` attribute.
+    fn compression(&self) -> Option<TokenStream> {
+        let variant = match self.compress.as_ref()?.value().as_str() {
+            "zstd" => quote!(Zstd),
+            "gzip" => quote!(Gzip),
+            _ => {
+                abort! {
+                    self.compress.span(), "Invalid attribute #[dynamodel(compress = ...)]";
+                    note = "The algorithm must be either `zstd` or `gzip`.";
+                    help = "Try `#[dynamodel(compress = \"zstd\")]`";
+                }
+            }
+        };
+        Some(quote! { _dynamodel::compression::Compression::#variant })
+    }
+
+    pub fn is_fallible(&self) -> bool {
+        self.try_into.is_some()
+            || self.compress.is_some()
+            || self.is_encrypted()
+            || self.is_json()
+            || self.is_marked_fallible()
+    }
+
+    pub fn has_try_from_item(&self) -> bool {
+        self.try_from_item.is_some()
+    }
+
+    pub fn is_timestamp(&self) -> bool {
+        self.is_created_at() || self.is_updated_at() || self.is_ttl()
+    }
+
+    // Whether the field is converted with a `TimestampFormat` instead of `AttributeValueConvertible`.
+    pub fn has_format(&self) -> bool {
+        self.is_timestamp() || self.format.is_some()
+    }
+
+    fn timestamp_format(&self) -> TokenStream {
+        if self.is_ttl() {
+            // DynamoDB TTL requires epoch seconds.
+            return quote! { _dynamodel::TimestampFormat::EpochSeconds };
+        }
+
+        let variant = match self.format.as_ref().map(syn::LitStr::value).as_deref() {
+            None | Some("rfc3339") => quote!(Rfc3339),
+            Some("epoch_seconds") => quote!(EpochSeconds),
+            Some("epoch_millis") => quote!(EpochMillis),
+            Some(pattern) if pattern.contains('%') => {
+                self.validate_strftime(pattern);
+                quote!(Strftime(#pattern))
+            }
+            Some(_) => {
+                abort! {
+                    self.format.span(), "Invalid attribute #[dynamodel(format = ...)]";
+                    note = "The format must be one of `rfc3339`, `epoch_seconds`, `epoch_millis` or a strftime pattern like `%Y-%m-%d`.";
+                    help = "Try `#[dynamodel(format = \"epoch_seconds\")]`";
+                }
+            }
+        };
+        quote! { _dynamodel::TimestampFormat::#variant }
+    }
+
+    #[cfg(feature = "chrono")]
+    fn validate_strftime(&self, pattern: &str) {
+        let is_invalid = chrono::format::StrftimeItems::new(pattern)
+            .any(|item| item == chrono::format::Item::Error);
+        if is_invalid {
+            abort! {
+                self.format.span(), "Invalid attribute #[dynamodel(format = ...)]";
+                note = "`{}` is not a valid strftime pattern.", pattern;
+                help = "See https://docs.rs/chrono/latest/chrono/format/strftime/ for the specifiers.";
+            }
+        }
+    }
+
+    // The `chrono` feature of dynamodel enables the one of this crate, so the pattern can't be
+    // used without it.
+    #[cfg(not(feature = "chrono"))]
+    fn validate_strftime(&self, _pattern: &str) {
+        abort! {
+            self.format.span(), "Invalid attribute #[dynamodel(format = ...)]";
+            note = "Strftime patterns require the `chrono` feature.";
+            help = "Try enabling the `chrono` feature of dynamodel.";
+        }
     }
 
     pub fn into_named(self, rule: &RenameRule) -> NamedField {
@@ -189,18 +723,33 @@ impl NamedVariant {
             .collect()
     }
 
-    fn set_key_value_branch(&self, return_token: TokenStream) -> TokenStream {
+    // `extra_keys` is the number of keys `return_token` inserts into `item`.
+    // `item` is the item map if `in_item_map`, or a `HashMap` for the `AttributeValue::M` otherwise.
+    fn set_key_value_branch(
+        &self,
+        return_token: TokenStream,
+        extra_keys: usize,
+        in_item_map: bool,
+        mode: WriteMode,
+    ) -> TokenStream {
         let ident = self.ident();
 
         let fields = self.fields();
         let field_names = fields.iter().map(NamedField::ident);
+        let capacity = fields.iter().filter(|f| !f.skip_into()).count() + extra_keys;
         let set_key_values = fields
             .iter()
-            .filter_map(|f| f.set_key_value_pair_token(|v| quote!(#v)));
+            .filter_map(|f| f.set_key_value_pair_token(|v| quote!(#v), mode));
+
+        let item_type = if in_item_map {
+            quote! { DynamodelItemMap }
+        } else {
+            quote! { ::std::collections::HashMap<String, _dynamodel::aws_sdk_dynamodb::types::AttributeValue> }
+        };
 
         quote! {
             #ident { #(#field_names,)* } => {
-                let mut item: Self = ::std::collections::HashMap::new();
+                let mut item = <#item_type>::with_capacity(#capacity);
                 #(#set_key_values)*
                 #return_token
             }
@@ -211,7 +760,7 @@ impl NamedVariant {
         self.variant.fields.is_newtype()
     }
 
-    fn newtype_value_token(&self) -> TokenStream {
+    fn newtype_value_token(&self, mode: ReadMode) -> TokenStream {
         self.assert_newtype();
 
         let ident = self.ident();
@@ -219,26 +768,32 @@ impl NamedVariant {
 
         let fields = self.fields();
         let ty = fields[0].ty();
+        let convert = try_from_attribute_value_token(mode);
 
         let transform = if is_optional(ty) {
             quote! {
                 |v| match v {
-                    ::aws_sdk_dynamodb::types::AttributeValue::Null(_) => Ok(None),
-                    _ => ::dynamodel::AttributeValueConvertible::try_from_attribute_value(v).map(|v| Some(v))
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue::Null(_) => Ok(None),
+                    _ => (#convert)(v).map(|v| Some(v))
                 }
             }
         } else {
-            quote! { ::dynamodel::AttributeValueConvertible::try_from_attribute_value }
+            convert
+        };
+
+        let get_value = match mode {
+            ReadMode::Borrowed => quote! { item.get(#hash_key) },
+            ReadMode::Owned => quote! { _dynamodel::ItemMap::remove(&mut item, #hash_key) },
         };
 
         quote! {
-            if let Some(v) = item.get(#hash_key).map(#transform).transpose()? {
+            if let Some(v) = #get_value.map(#transform).transpose()? {
                 return Ok(Self::#ident(v));
             }
         }
     }
 
-    fn newtype_value_token_tagged(&self) -> TokenStream {
+    fn newtype_value_token_tagged(&self, mode: ReadMode) -> TokenStream {
         self.assert_newtype();
 
         let ident = self.ident();
@@ -247,11 +802,21 @@ impl NamedVariant {
         let fields = self.fields();
         let ty = fields[0].ty();
 
+        let inner = match mode {
+            ReadMode::Borrowed => {
+                quote! { _dynamodel::AttributeValueConvertible::try_from_item(item) }
+            }
+            ReadMode::Owned => quote! {
+                _dynamodel::AttributeValueConvertible::try_from_attribute_value_owned(
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(_dynamodel::ItemMap::into_hash_map(item)),
+                )
+            },
+        };
+
         if is_optional(ty) {
             quote! {
                 #name => {
-                    let v = ::aws_sdk_dynamodb::types::AttributeValue::M(item);
-                    let inner = ::dynamodel::AttributeValueConvertible::try_from_attribute_value(&v)
+                    let inner = #inner
                         .map(|v| Some(v))
                         .unwrap_or(None);
                     return Ok(Self::#ident(inner));
@@ -260,47 +825,74 @@ impl NamedVariant {
         } else {
             quote! {
                 #name => {
-                    let v = ::aws_sdk_dynamodb::types::AttributeValue::M(item);
-                    return Ok(Self::#ident(::dynamodel::AttributeValueConvertible::try_from_attribute_value(&v)?));
+                    return Ok(Self::#ident(#inner?));
                 }
             }
         }
     }
 
-    fn named_value_token(&self) -> TokenStream {
+    fn named_value_token(&self, mode: ReadMode) -> TokenStream {
         let ident = self.ident();
         let hash_key = self.name.as_str();
-        let err = unmatch_err("M");
 
         let fields = self.fields();
-        let fields_token = fields.iter().map(NamedField::set_named_field_token);
+        let bindings = fields
+            .iter()
+            .filter_map(|f| f.try_from_item_binding_token(mode));
+        let fields_token = fields.iter().map(|f| f.set_named_field_token(mode));
+
+        let get_item = match mode {
+            ReadMode::Borrowed => {
+                let err = unmatch_err("M");
+                quote! {
+                    item
+                        .get(#hash_key)
+                        .map(|v| v.as_m().map_err(|e| #err))
+                        .transpose()?
+                }
+            }
+            ReadMode::Owned => quote! {
+                _dynamodel::ItemMap::remove(&mut item, #hash_key)
+                    .map(|v| match v {
+                        _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(item) => Ok(item),
+                        e => Err(_dynamodel::ConvertError::AttributeValueUnmatched("M".into(), e)),
+                    })
+                    .transpose()?
+            },
+        };
+
+        let item = match mode {
+            ReadMode::Borrowed => quote!(item),
+            ReadMode::Owned => quote!(mut item),
+        };
 
         quote! {
-            if let Some(ref item) = item
-                .get(#hash_key)
-                .map(|v| v.as_m().map_err(|e| #err))
-                .transpose()?
-            {
+            if let Some(#item) = #get_item {
+                #(#bindings)*
                 return Ok(Self::#ident { #(#fields_token,)* });
             }
         }
     }
 
-    fn named_value_token_tagged(&self) -> TokenStream {
+    fn named_value_token_tagged(&self, mode: ReadMode) -> TokenStream {
         let ident = self.ident();
         let name = self.name.as_str();
 
         let fields = self.fields();
-        let fields_token = fields.iter().map(NamedField::set_named_field_token);
+        let bindings = fields
+            .iter()
+            .filter_map(|f| f.try_from_item_binding_token(mode));
+        let fields_token = fields.iter().map(|f| f.set_named_field_token(mode));
 
         quote! {
             #name => {
+                #(#bindings)*
                 return Ok(Self::#ident { #(#fields_token,)* });
             }
         }
     }
 
-    fn set_newtype_key_value(&self) -> TokenStream {
+    fn set_newtype_key_value(&self, mode: WriteMode) -> TokenStream {
         self.assert_newtype();
 
         let ident = self.ident();
@@ -310,22 +902,26 @@ impl NamedVariant {
         let ty = fields[0].ty();
 
         let attribute_value = if is_optional(ty) {
+            let inner = quote!(v);
+            let inner = into_attribute_value_token(&inner, mode);
             quote! {
-                v.map(::dynamodel::AttributeValueConvertible::into_attribute_value)
-                    .unwrap_or(::aws_sdk_dynamodb::types::AttributeValue::Null(true))
+                match v {
+                    Some(v) => #inner,
+                    None => _dynamodel::aws_sdk_dynamodb::types::AttributeValue::Null(true),
+                }
             }
         } else {
-            quote! { ::dynamodel::AttributeValueConvertible::into_attribute_value(v) }
+            into_attribute_value_token(&quote!(v), mode)
         };
 
+        let item = item_map_with_entry_token(quote!(#name.into()), attribute_value);
+
         quote! {
-            #ident(v) => {
-                [(#name.into(), #attribute_value)].into()
-            }
+            #ident(v) => #item
         }
     }
 
-    fn set_tagged_newtype_key_value(&self, tag: &str) -> TokenStream {
+    fn set_tagged_newtype_key_value(&self, tag: &str, mode: WriteMode) -> TokenStream {
         self.assert_newtype();
 
         let ident = self.ident();
@@ -334,76 +930,101 @@ impl NamedVariant {
         let fields = self.fields();
         let ty = fields[0].ty();
 
-        let init_hashmap = if is_optional(ty) {
-            quote! { v.map(Self::from).unwrap_or_else(Self::new); }
+        let into_item = if mode == WriteMode::Fallible {
+            let err = unmatch_err("M");
+            quote! {
+                |v| match _dynamodel::AttributeValueConvertible::try_into_attribute_value(v)? {
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(item) => Ok(item),
+                    e => Err(#err),
+                }
+            }
         } else {
-            quote! { v.into(); }
+            // The concrete type keeps the error readable when the field isn't an object.
+            quote! {
+                ::std::collections::HashMap::<
+                    ::std::string::String,
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue,
+                >::from
+            }
+        };
+
+        let hash_map = match (is_optional(ty), mode) {
+            (true, WriteMode::Fallible) => quote! {
+                v.map(#into_item).transpose()?.unwrap_or_default()
+            },
+            (true, WriteMode::Owned) => quote! { v.map(#into_item).unwrap_or_default() },
+            (true, WriteMode::Borrowed) => quote! {
+                v.as_ref().map(#into_item).unwrap_or_default()
+            },
+            (false, WriteMode::Fallible) => quote! { (#into_item)(v)? },
+            (false, _) => quote! { (#into_item)(v) },
         };
 
         quote! {
             #ident(v) => {
-                let mut item: Self = #init_hashmap
+                let mut item: DynamodelItemMap = _dynamodel::ItemMap::from_hash_map(#hash_map);
                 item.insert(
                     #tag.into(),
-                    ::aws_sdk_dynamodb::types::AttributeValue::S(#name.into()),
+                    _dynamodel::aws_sdk_dynamodb::types::AttributeValue::S(#name.into()),
                 );
                 item
             }
         }
     }
 
-    fn set_named_key_value(&self) -> TokenStream {
+    fn set_named_key_value(&self, mode: WriteMode) -> TokenStream {
         let name = self.name.as_str();
-        let return_token = quote! {
-            [(#name.into(), ::aws_sdk_dynamodb::types::AttributeValue::M(item))].into()
-        };
+        let return_token = item_map_with_entry_token(
+            quote!(#name.into()),
+            quote!(_dynamodel::aws_sdk_dynamodb::types::AttributeValue::M(item)),
+        );
 
-        self.set_key_value_branch(return_token)
+        self.set_key_value_branch(return_token, 0, false, mode)
     }
 
-    fn set_tagged_named_key_value(&self, tag: &str) -> TokenStream {
+    fn set_tagged_named_key_value(&self, tag: &str, mode: WriteMode) -> TokenStream {
         let name = self.name.as_str();
 
         let return_token = quote! {
             item.insert(
                 #tag.into(),
-                ::aws_sdk_dynamodb::types::AttributeValue::S(#name.into()),
+                _dynamodel::aws_sdk_dynamodb::types::AttributeValue::S(#name.into()),
             );
             item
         };
 
-        self.set_key_value_branch(return_token)
+        self.set_key_value_branch(return_token, 1, true, mode)
     }
 
-    pub fn get_value_token(&self) -> TokenStream {
+    pub fn get_value_token(&self, mode: ReadMode) -> TokenStream {
         if self.is_newtype() {
-            self.newtype_value_token()
+            self.newtype_value_token(mode)
         } else {
-            self.named_value_token()
+            self.named_value_token(mode)
         }
     }
 
-    pub fn get_value_token_tagged(&self) -> TokenStream {
+    pub fn get_value_token_tagged(&self, mode: ReadMode) -> TokenStream {
         if self.is_newtype() {
-            self.newtype_value_token_tagged()
+            self.newtype_value_token_tagged(mode)
         } else {
-            self.named_value_token_tagged()
+            self.named_value_token_tagged(mode)
         }
     }
 
-    pub fn set_key_value(&self) -> TokenStream {
+    pub fn set_key_value(&self, mode: WriteMode) -> TokenStream {
         if self.is_newtype() {
-            self.set_newtype_key_value()
+            self.set_newtype_key_value(mode)
         } else {
-            self.set_named_key_value()
+            self.set_named_key_value(mode)
         }
     }
 
-    pub fn set_tagged_key_value(&self, tag: &str) -> TokenStream {
+    pub fn set_tagged_key_value(&self, tag: &str, mode: WriteMode) -> TokenStream {
         if self.is_newtype() {
-            self.set_tagged_newtype_key_value(tag)
+            self.set_tagged_newtype_key_value(tag, mode)
         } else {
-            self.set_tagged_named_key_value(tag)
+            self.set_tagged_named_key_value(tag, mode)
         }
     }
 }
@@ -421,6 +1042,30 @@ impl Variant {
     pub fn validate(&self) {
         for field in self.fields.fields.iter() {
             field.validate();
+
+            if field.is_version() {
+                abort! {
+                    field.ident.span(), "Invalid attribute #[dynamodel(version)]";
+                    note = "The `version` attribute is only available on struct fields.";
+                    help = "Try removing the `version` attribute.";
+                }
+            }
+
+            if field.is_encrypted() {
+                abort! {
+                    field.ident.span(), "Invalid attribute #[dynamodel(encrypt)]";
+                    note = "The `encrypt` attribute is only available on struct fields.";
+                    help = "Try removing the `encrypt` attribute.";
+                }
+            }
+
+            if field.is_timestamp() {
+                abort! {
+                    field.ident.span(), "Invalid attribute #[dynamodel(created_at)], #[dynamodel(updated_at)] or #[dynamodel(ttl)]";
+                    note = "The `created_at`, `updated_at` and `ttl` attributes are only available on struct fields.";
+                    help = "Try removing the attribute.";
+                }
+            }
         }
     }
 
@@ -437,20 +1082,83 @@ impl Variant {
     }
 }
 
+// An item map with the single key-value pair.
+fn item_map_with_entry_token(key: TokenStream, value: TokenStream) -> TokenStream {
+    quote! {
+        {
+            let attribute_value = #value;
+            let mut item = <DynamodelItemMap as _dynamodel::ItemMap>::with_capacity(1);
+            item.insert(#key, attribute_value);
+            item
+        }
+    }
+}
+
 fn not_set_err(ident: &Option<syn::Ident>) -> TokenStream {
     quote! {
-        ::dynamodel::ConvertError::FieldNotSet(stringify!(#ident).into())
+        _dynamodel::ConvertError::FieldNotSet(stringify!(#ident).into())
+    }
+}
+
+// Returns the error of the result with `?`. The objects using this are fallible, so they have
+// neither `From` nor `ToItem` and only `Fallible` reaches here.
+pub fn fallible_token(result: TokenStream, mode: WriteMode) -> TokenStream {
+    match mode {
+        WriteMode::Fallible => quote! { #result? },
+        _ => unreachable!("fallible conversions in {mode:?} mode"),
+    }
+}
+
+// Converts the value into an `AttributeValue`, returning the error with `?` if `Fallible`.
+// `Owned` fails to compile if the type of the value is fallible.
+fn into_attribute_value_token(value: &TokenStream, mode: WriteMode) -> TokenStream {
+    match mode {
+        WriteMode::Owned => {
+            quote! { _dynamodel::into_infallible_attribute_value(#value) }
+        }
+        WriteMode::Fallible => {
+            quote! { _dynamodel::AttributeValueConvertible::try_into_attribute_value(#value)? }
+        }
+        WriteMode::Borrowed => quote! { _dynamodel::ToAttributeValue::to_attribute_value(#value) },
+    }
+}
+
+// Converts an `AttributeValue`, taking it if the mode is `Owned`.
+fn try_from_attribute_value_token(mode: ReadMode) -> TokenStream {
+    match mode {
+        ReadMode::Borrowed => {
+            quote! { _dynamodel::AttributeValueConvertible::try_from_attribute_value }
+        }
+        ReadMode::Owned => {
+            quote! { _dynamodel::AttributeValueConvertible::try_from_attribute_value_owned }
+        }
     }
 }
 
 fn unmatch_err(ty: &str) -> TokenStream {
     quote! {
-        ::dynamodel::ConvertError::AttributeValueUnmatched(#ty.into(), e.clone())
+        _dynamodel::ConvertError::AttributeValueUnmatched(#ty.into(), e.clone())
     }
 }
 
 static OPTIONS_TYPE: [&str; 3] = ["Option|", "std|option|Option|", "core|option|Option|"];
 
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    if !is_optional(ty) {
+        return None;
+    }
+    let syn::Type::Path(p) = ty else {
+        return None;
+    };
+    match &p.path.segments.last()?.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
 fn is_optional(ty: &syn::Type) -> bool {
     if let syn::Type::Path(p) = ty {
         let idents_of_path = p.path.segments.iter().fold(String::new(), |mut acc, v| {
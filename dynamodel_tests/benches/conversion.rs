@@ -0,0 +1,124 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use dynamodel::Dynamodel;
+use std::collections::HashMap;
+
+const WIDE_FIELDS: u64 = 48;
+
+macro_rules! wide_model {
+    (strings: [$($s:ident),*], numbers: [$($n:ident),*]) => {
+        #[derive(Dynamodel, Debug, Clone, PartialEq)]
+        #[dynamodel(extra = "Wide::sort_key", rename_all = "PascalCase")]
+        struct Wide {
+            $($s: String,)*
+            $($n: u64,)*
+        }
+
+        fn wide() -> Wide {
+            Wide {
+                $($s: stringify!($s).repeat(8),)*
+                $($n: u64::MAX / 3,)*
+            }
+        }
+    };
+}
+
+wide_model! {
+    strings: [
+        s00, s01, s02, s03, s04, s05, s06, s07, s08, s09, s10, s11,
+        s12, s13, s14, s15, s16, s17, s18, s19, s20, s21, s22, s23
+    ],
+    numbers: [
+        n00, n01, n02, n03, n04, n05, n06, n07, n08, n09, n10, n11,
+        n12, n13, n14, n15, n16, n17, n18, n19, n20, n21, n22, n23
+    ]
+}
+
+impl Wide {
+    fn sort_key(&self) -> HashMap<String, AttributeValue> {
+        [("SK".to_string(), AttributeValue::S("Wide".into()))].into()
+    }
+}
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Node {
+    name: String,
+    weight: u64,
+    labels: Vec<String>,
+    children: Vec<Node>,
+}
+
+// A tree `depth` levels deep where each node has `fanout` children.
+fn tree(depth: u32, fanout: usize) -> Node {
+    Node {
+        name: format!("node#{depth}"),
+        weight: depth.into(),
+        labels: vec!["a".into(), "b".into()],
+        children: if depth == 0 {
+            vec![]
+        } else {
+            (0..fanout).map(|_| tree(depth - 1, fanout)).collect()
+        },
+    }
+}
+
+fn nodes(depth: u32, fanout: usize) -> u64 {
+    (0..=depth).map(|d| fanout.pow(d) as u64).sum()
+}
+
+fn wide_model(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wide_model");
+    group.throughput(Throughput::Elements(WIDE_FIELDS));
+
+    let model = wide();
+    let item: HashMap<String, AttributeValue> = model.clone().into();
+
+    group.bench_function("into", |b| {
+        b.iter_batched(
+            || model.clone(),
+            HashMap::<String, AttributeValue>::from,
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("try_from", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| Wide::try_from(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn deep_model(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_model");
+
+    for (depth, fanout) in [(16, 1), (64, 1), (6, 3)] {
+        let id = format!("depth={depth}/fanout={fanout}");
+        let model = tree(depth, fanout);
+        let item: HashMap<String, AttributeValue> = model.clone().into();
+        group.throughput(Throughput::Elements(nodes(depth, fanout)));
+
+        group.bench_with_input(BenchmarkId::new("into", &id), &model, |b, model| {
+            b.iter_batched(
+                || model.clone(),
+                HashMap::<String, AttributeValue>::from,
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("try_from", &id), &item, |b, item| {
+            b.iter_batched(
+                || item.clone(),
+                |item| Node::try_from(item).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, wide_model, deep_model);
+criterion_main!(benches);
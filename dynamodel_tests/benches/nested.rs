@@ -0,0 +1,62 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use dynamodel::Dynamodel;
+use std::collections::HashMap;
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+struct Document {
+    id: String,
+    entries: Vec<Entry>,
+}
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+struct Entry {
+    key: String,
+    value: u64,
+    tags: Vec<String>,
+    meta: Meta,
+}
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+struct Meta {
+    author: String,
+    note: Option<String>,
+}
+
+fn document(entries: usize) -> Document {
+    Document {
+        id: "7cf27a02".into(),
+        entries: (0..entries)
+            .map(|i| Entry {
+                key: format!("entry#{i:05}"),
+                value: i as u64,
+                tags: vec!["rust".into(), "aws".into(), "dynamodb".into()],
+                meta: Meta {
+                    author: "VideoGal12".into(),
+                    note: Some("x".repeat(64)),
+                },
+            })
+            .collect(),
+    }
+}
+
+fn nested_documents(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_document");
+
+    for entries in [10, 100, 1000] {
+        let item: HashMap<String, AttributeValue> = document(entries).into();
+        group.throughput(Throughput::Elements(entries as u64));
+        group.bench_with_input(BenchmarkId::new("try_from", entries), &item, |b, item| {
+            b.iter_batched(
+                || item.clone(),
+                |item| Document::try_from(item).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, nested_documents);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+use super::*;
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref)]
+enum Message {
+    Request { id: String, method: String },
+    Response { id: String, result: Option<u32> },
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref, tag = "type")]
+enum TaggedMessage {
+    Request { id: String, method: String },
+    Response { id: String, result: Option<u32> },
+}
+
+#[test]
+fn test_to_item() {
+    let msg = Message::Request {
+        id: "1".into(),
+        method: "GET".into(),
+    };
+
+    let expected: HashMap<String, AttributeValue> = [(
+        "Request".to_string(),
+        AttributeValue::M(
+            [
+                ("id".to_string(), AttributeValue::S("1".into())),
+                ("method".to_string(), AttributeValue::S("GET".into())),
+            ]
+            .into(),
+        ),
+    )]
+    .into();
+
+    assert_eq!(msg.to_item(), expected);
+
+    let converted: HashMap<String, AttributeValue> = (&msg).into();
+    assert_eq!(converted, expected);
+
+    let converted: HashMap<String, AttributeValue> = msg.into();
+    assert_eq!(converted, expected);
+}
+
+#[test]
+fn test_to_item_tagged() {
+    let msg = TaggedMessage::Response {
+        id: "1".into(),
+        result: Some(200),
+    };
+
+    let expected: HashMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Response".into())),
+        ("id".to_string(), AttributeValue::S("1".into())),
+        ("result".to_string(), AttributeValue::N("200".into())),
+    ]
+    .into();
+
+    assert_eq!(msg.to_item(), expected);
+
+    let converted: HashMap<String, AttributeValue> = msg.into();
+    assert_eq!(converted, expected);
+
+    let msg = TaggedMessage::Response {
+        id: "1".into(),
+        result: None,
+    };
+
+    let expected: HashMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Response".into())),
+        ("id".to_string(), AttributeValue::S("1".into())),
+    ]
+    .into();
+
+    assert_eq!(msg.to_item(), expected);
+}
@@ -1,4 +1,5 @@
 use super::*;
 
+mod by_ref;
 mod rename_all;
 mod tag;
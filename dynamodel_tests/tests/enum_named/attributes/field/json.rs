@@ -0,0 +1,27 @@
+use super::*;
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+enum Event {
+    Tagged {
+        #[dynamodel(json)]
+        tags: Vec<String>,
+    },
+}
+
+#[test]
+fn test_json_field() {
+    let event = Event::Tagged {
+        tags: vec!["a".into(), "b".into()],
+    };
+
+    let inner: HashMap<String, AttributeValue> =
+        [("tags".to_string(), AttributeValue::S(r#"["a","b"]"#.into()))].into();
+    let item: HashMap<String, AttributeValue> =
+        [("Tagged".to_string(), AttributeValue::M(inner))].into();
+
+    let converted = event.clone().try_into_item().unwrap();
+    assert_eq!(converted, item);
+
+    let converted: Event = item.try_into().unwrap();
+    assert_eq!(converted, event);
+}
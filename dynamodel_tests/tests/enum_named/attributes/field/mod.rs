@@ -1,3 +1,5 @@
 use super::*;
 
+mod json;
 mod rename;
+mod try_into;
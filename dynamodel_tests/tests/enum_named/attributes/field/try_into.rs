@@ -0,0 +1,57 @@
+use super::*;
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+struct Body {
+    text: String,
+}
+
+#[derive(Dynamodel, Debug, Clone, PartialEq)]
+#[dynamodel(tag = "type")]
+enum Message {
+    Request {
+        #[dynamodel(try_into = "to_path")]
+        path: String,
+    },
+    Response(Body),
+}
+
+fn to_path(value: String) -> Result<AttributeValue, ConvertError> {
+    if value.starts_with('/') {
+        Ok(AttributeValue::S(value))
+    } else {
+        Err(ConvertError::ParseValue("path".into(), value))
+    }
+}
+
+#[test]
+fn test_try_into_hashmap() {
+    let m = Message::Request {
+        path: "/videos".into(),
+    };
+    let item: HashMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Request".into())),
+        ("path".to_string(), AttributeValue::S("/videos".into())),
+    ]
+    .into();
+    assert_eq!(m.clone().try_into_item().unwrap(), item);
+    assert_ok_eq!(Message::try_from(item), m);
+
+    let m = Message::Response(Body { text: "OK".into() });
+    let item: HashMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Response".into())),
+        ("text".to_string(), AttributeValue::S("OK".into())),
+    ]
+    .into();
+    let converted: HashMap<String, AttributeValue> = m.clone().try_into().unwrap();
+    assert_eq!(converted, item);
+    assert_ok_eq!(Message::try_from(item), m);
+}
+
+#[test]
+fn test_try_into_hashmap_error() {
+    let m = Message::Request {
+        path: "videos".into(),
+    };
+    let result = m.try_into_item();
+    assert!(matches!(result, Err(ConvertError::ParseValue(_, v)) if v == "videos"));
+}
@@ -1,13 +1,19 @@
 use super::*;
 
 macro_rules! int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { id: Option<$ty>, method: String },
-                    Response { id: Option<$ty>, result: String },
+                    Request { $($attr)* id: Option<$ty>, method: String },
+                    Response { $($attr)* id: Option<$ty>, result: String },
                 }
 
                 #[test]
@@ -16,7 +22,7 @@ macro_rules! int_test {
                         id: Some(10),
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.$($into)*;
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("id".to_string(), AttributeValue::N("10".into())),
@@ -36,7 +42,7 @@ macro_rules! int_test {
                         id: None,
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.$($into)*;
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("method".to_string(), AttributeValue::S("GET".into())),
@@ -140,7 +146,8 @@ macro_rules! int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules! float_test {
     ($($ty:ty),*) => {
@@ -148,8 +155,8 @@ macro_rules! float_test {
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { id: Option<$ty>, method: String },
-                    Response { id: Option<$ty>, result: String },
+                    Request { #[dynamodel(fallible)] id: Option<$ty>, method: String },
+                    Response { #[dynamodel(fallible)] id: Option<$ty>, result: String },
                 }
 
                 #[test]
@@ -158,7 +165,7 @@ macro_rules! float_test {
                         id: Some(1.2),
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.try_into_item().unwrap();
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("id".to_string(), AttributeValue::N("1.2".into())),
@@ -178,7 +185,7 @@ macro_rules! float_test {
                         id: None,
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.try_into_item().unwrap();
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("method".to_string(), AttributeValue::S("GET".into())),
@@ -1,13 +1,19 @@
 use super::*;
 
 macro_rules! int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { id: $ty, method: String },
-                    Response { id: $ty, result: String },
+                    Request { $($attr)* id: $ty, method: String },
+                    Response { $($attr)* id: $ty, result: String },
                 }
 
                 #[test]
@@ -16,7 +22,7 @@ macro_rules! int_test {
                         id: 10,
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.$($into)*;
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("id".to_string(), AttributeValue::N("10".into())),
@@ -116,7 +122,8 @@ macro_rules! int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules! float_test {
     ($($ty:ty),*) => {
@@ -124,8 +131,8 @@ macro_rules! float_test {
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { id: $ty, method: String },
-                    Response { id: $ty, result: String },
+                    Request { #[dynamodel(fallible)] id: $ty, method: String },
+                    Response { #[dynamodel(fallible)] id: $ty, result: String },
                 }
 
                 #[test]
@@ -134,7 +141,7 @@ macro_rules! float_test {
                         id: 1.2,
                         method: "GET".into(),
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.try_into_item().unwrap();
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("id".to_string(), AttributeValue::N("1.2".into())),
@@ -1,12 +1,18 @@
 use super::*;
 
 macro_rules! int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { ids: Vec<$ty> },
+                    Request { $($attr)* ids: Vec<$ty> },
                     Response { id: String, result: String },
                 }
 
@@ -15,7 +21,7 @@ macro_rules! int_test {
                     let msg = [<Message$ty>]::Request {
                         ids: vec![10, 20],
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.$($into)*;
 
                     let inner: HashMap<String, AttributeValue> = [
                         (
@@ -39,7 +45,7 @@ macro_rules! int_test {
                     let msg = [<Message$ty>]::Request {
                         ids: vec![],
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.$($into)*;
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("ids".to_string(), AttributeValue::L(vec![])),
@@ -169,7 +175,8 @@ macro_rules! int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules! float_test {
     ($($ty:ty),*) => {
@@ -177,7 +184,7 @@ macro_rules! float_test {
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<Message$ty>] {
-                    Request { ids: Vec<$ty> },
+                    Request { #[dynamodel(fallible)] ids: Vec<$ty> },
                     Response { id: String, result: String },
                 }
 
@@ -186,7 +193,7 @@ macro_rules! float_test {
                     let msg = [<Message$ty>]::Request {
                         ids: vec![1.2, 3.45],
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.try_into_item().unwrap();
 
                     let inner: HashMap<String, AttributeValue> = [
                         (
@@ -210,7 +217,7 @@ macro_rules! float_test {
                     let msg = [<Message$ty>]::Request {
                         ids: vec![],
                     };
-                    let actual: HashMap<String, AttributeValue> = msg.into();
+                    let actual: HashMap<String, AttributeValue> = msg.try_into_item().unwrap();
 
                     let inner: HashMap<String, AttributeValue> = [
                         ("ids".to_string(), AttributeValue::L(vec![])),
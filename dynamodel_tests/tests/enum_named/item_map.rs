@@ -0,0 +1,53 @@
+use super::*;
+use std::collections::BTreeMap;
+
+#[derive(Dynamodel, Debug, PartialEq)]
+#[dynamodel(tag = "type")]
+enum Tagged {
+    Circle {
+        radius: u32,
+        #[dynamodel(try_from_item = "count_keys")]
+        keys: usize,
+    },
+}
+
+#[derive(Dynamodel, Debug, PartialEq)]
+enum Untagged {
+    Square { width: u32 },
+}
+
+fn count_keys(item: &HashMap<String, AttributeValue>) -> Result<usize, ConvertError> {
+    Ok(item.len())
+}
+
+#[test]
+fn test_convert_tagged_btree_map() {
+    let item: BTreeMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Circle".into())),
+        ("radius".to_string(), AttributeValue::N("5".into())),
+        ("keys".to_string(), AttributeValue::N("3".into())),
+    ]
+    .into();
+
+    let converted: BTreeMap<String, AttributeValue> =
+        Tagged::Circle { radius: 5, keys: 3 }.into_item_map();
+    assert_eq!(converted, item);
+
+    let converted = Tagged::try_from_item_map(item).unwrap();
+    assert_eq!(converted, Tagged::Circle { radius: 5, keys: 3 });
+}
+
+#[test]
+fn test_convert_untagged_btree_map() {
+    let item: BTreeMap<String, AttributeValue> = [(
+        "Square".to_string(),
+        AttributeValue::M([("width".to_string(), AttributeValue::N("3".into()))].into()),
+    )]
+    .into();
+
+    let converted: BTreeMap<String, AttributeValue> = Untagged::Square { width: 3 }.into_item_map();
+    assert_eq!(converted, item);
+
+    let converted = Untagged::try_from_item_map(item).unwrap();
+    assert_eq!(converted, Untagged::Square { width: 3 });
+}
@@ -2,3 +2,4 @@ use super::*;
 
 mod attributes;
 mod fields;
+mod item_map;
@@ -0,0 +1,68 @@
+use super::*;
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref)]
+enum NewType {
+    Val(Option<Example>),
+    Num(u32),
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref, tag = "type")]
+enum NewTypeTagged {
+    Val(Example),
+    Opt(Option<Example>),
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref)]
+struct Example {
+    id: String,
+}
+
+#[test]
+fn test_to_item() {
+    let val = NewType::Val(Some(Example { id: "foo".into() }));
+
+    let expected: HashMap<String, AttributeValue> = [(
+        "Val".to_string(),
+        AttributeValue::M([("id".to_string(), AttributeValue::S("foo".into()))].into()),
+    )]
+    .into();
+
+    assert_eq!(val.to_item(), expected);
+
+    let converted: HashMap<String, AttributeValue> = val.into();
+    assert_eq!(converted, expected);
+
+    let val = NewType::Val(None);
+    let expected: HashMap<String, AttributeValue> =
+        [("Val".to_string(), AttributeValue::Null(true))].into();
+    assert_eq!(val.to_item(), expected);
+
+    let val = NewType::Num(7);
+    let expected: HashMap<String, AttributeValue> =
+        [("Num".to_string(), AttributeValue::N("7".into()))].into();
+    assert_eq!(HashMap::from(&val), expected);
+}
+
+#[test]
+fn test_to_item_tagged() {
+    let val = NewTypeTagged::Val(Example { id: "foo".into() });
+
+    let expected: HashMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Val".into())),
+        ("id".to_string(), AttributeValue::S("foo".into())),
+    ]
+    .into();
+
+    assert_eq!(val.to_item(), expected);
+
+    let converted: HashMap<String, AttributeValue> = val.into();
+    assert_eq!(converted, expected);
+
+    let val = NewTypeTagged::Opt(None);
+    let expected: HashMap<String, AttributeValue> =
+        [("type".to_string(), AttributeValue::S("Opt".into()))].into();
+    assert_eq!(val.to_item(), expected);
+}
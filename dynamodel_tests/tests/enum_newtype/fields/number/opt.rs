@@ -1,18 +1,24 @@
 use super::*;
 
 macro_rules!  int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<NewType$ty>] {
-                    Num(Option<$ty>),
+                    Num($($attr)* Option<$ty>),
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Num(Some(10));
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::N("10".into())),
@@ -22,7 +28,7 @@ macro_rules!  int_test {
                     assert_eq!(actual, expected);
 
                     let val = [<NewType$ty>]::Num(None);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::Null(true)),
@@ -89,7 +95,8 @@ macro_rules!  int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules!  float_test {
     ($($ty:ty),*) => {
@@ -97,13 +104,13 @@ macro_rules!  float_test {
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<NewType$ty>] {
-                    Num(Option<$ty>),
+                    Num(#[dynamodel(fallible)] Option<$ty>),
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Num(Some(1.2));
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::N("1.2".into())),
@@ -113,7 +120,7 @@ macro_rules!  float_test {
                     assert_eq!(actual, expected);
 
                     let val = [<NewType$ty>]::Num(None);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::Null(true)),
@@ -1,18 +1,24 @@
 use super::*;
 
 macro_rules!  int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<NewType$ty>] {
-                    Num($ty),
+                    Num($($attr)* $ty),
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Num(10);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::N("10".into())),
@@ -68,7 +74,8 @@ macro_rules!  int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules!  float_test {
     ($($ty:ty),*) => {
@@ -76,13 +83,13 @@ macro_rules!  float_test {
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
                 enum [<NewType$ty>] {
-                    Num($ty),
+                    Num(#[dynamodel(fallible)] $ty),
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Num(1.2);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Num".to_string(), AttributeValue::N("1.2".into())),
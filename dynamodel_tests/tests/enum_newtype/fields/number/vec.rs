@@ -1,7 +1,13 @@
 use super::*;
 
 macro_rules!  int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Dynamodel, Debug, PartialEq)]
@@ -12,7 +18,7 @@ macro_rules!  int_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Nums(vec![10, 15]);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Nums".to_string(),
@@ -29,7 +35,7 @@ macro_rules!  int_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap_from_empty_vector>]() {
                     let val = [<NewType$ty>]::Nums(vec![]);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = [(
                         "Nums".to_string(),
@@ -97,7 +103,8 @@ macro_rules!  int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules!  float_test {
     ($($ty:ty),*) => {
@@ -111,7 +118,7 @@ macro_rules!  float_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let val = [<NewType$ty>]::Nums(vec![1.2, 3.45]);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = [
                         ("Nums".to_string(),
@@ -128,7 +135,7 @@ macro_rules!  float_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap_from_empty_vector>]() {
                     let val = [<NewType$ty>]::Nums(vec![]);
-                    let actual: HashMap<String, AttributeValue> = val.into();
+                    let actual: HashMap<String, AttributeValue> = val.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = [(
                         "Nums".to_string(),
@@ -0,0 +1,56 @@
+use super::*;
+use std::collections::BTreeMap;
+
+#[derive(Dynamodel, Debug, PartialEq)]
+#[dynamodel(tag = "type")]
+enum Tagged {
+    Val(Option<Example>),
+}
+
+#[derive(Dynamodel, Debug, PartialEq)]
+enum Untagged {
+    Val(Example),
+}
+
+#[derive(Dynamodel, Debug, PartialEq)]
+struct Example {
+    id: String,
+}
+
+#[test]
+fn test_convert_tagged_btree_map() {
+    let item: BTreeMap<String, AttributeValue> = [
+        ("type".to_string(), AttributeValue::S("Val".into())),
+        ("id".to_string(), AttributeValue::S("foo".into())),
+    ]
+    .into();
+
+    let val = Tagged::Val(Some(Example { id: "foo".into() }));
+    let converted: BTreeMap<String, AttributeValue> = val.into_item_map();
+    assert_eq!(converted, item);
+
+    let converted = Tagged::try_from_item_map(item).unwrap();
+    assert_eq!(converted, Tagged::Val(Some(Example { id: "foo".into() })));
+
+    let converted: BTreeMap<String, AttributeValue> = Tagged::Val(None).into_item_map();
+    assert_eq!(
+        converted,
+        [("type".to_string(), AttributeValue::S("Val".into()))].into()
+    );
+}
+
+#[test]
+fn test_convert_untagged_btree_map() {
+    let item: BTreeMap<String, AttributeValue> = [(
+        "Val".to_string(),
+        AttributeValue::M([("id".to_string(), AttributeValue::S("foo".into()))].into()),
+    )]
+    .into();
+
+    let converted: BTreeMap<String, AttributeValue> =
+        Untagged::Val(Example { id: "foo".into() }).into_item_map();
+    assert_eq!(converted, item);
+
+    let converted = Untagged::try_from_item_map(item).unwrap();
+    assert_eq!(converted, Untagged::Val(Example { id: "foo".into() }));
+}
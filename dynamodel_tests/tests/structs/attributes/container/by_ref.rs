@@ -0,0 +1,116 @@
+use super::*;
+use dynamodel::ToAttributeValue;
+use std::time::{Duration, SystemTime};
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref, extra = "Video::key", rename_all = "PascalCase")]
+struct Video {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    views: Option<u64>,
+    tags: Vec<String>,
+    author: Author,
+    #[dynamodel(into = "to_status", try_from = "from_status")]
+    published: bool,
+    #[dynamodel(created_at, format = "epoch_seconds")]
+    created_at: Option<SystemTime>,
+    #[dynamodel(skip_into, try_from_item = "no_cache")]
+    cache: Vec<u8>,
+}
+
+impl Video {
+    fn key(&self) -> HashMap<String, AttributeValue> {
+        [("SK".to_string(), AttributeValue::S("Video".into()))].into()
+    }
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref)]
+struct Author {
+    name: String,
+}
+
+fn to_status(published: bool) -> AttributeValue {
+    AttributeValue::S(if published { "PUBLIC" } else { "DRAFT" }.into())
+}
+
+fn from_status(value: &AttributeValue) -> Result<bool, ConvertError> {
+    Ok(value.as_s().is_ok_and(|v| v == "PUBLIC"))
+}
+
+fn no_cache(_item: &HashMap<String, AttributeValue>) -> Result<Vec<u8>, ConvertError> {
+    Ok(vec![])
+}
+
+fn video() -> Video {
+    Video {
+        id: "6b8c736e".into(),
+        views: Some(42),
+        tags: vec!["rust".into(), "aws".into()],
+        author: Author {
+            name: "VideoGal12".into(),
+        },
+        published: true,
+        created_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1657114888)),
+        cache: vec![],
+    }
+}
+
+fn item() -> HashMap<String, AttributeValue> {
+    [
+        ("PK".to_string(), AttributeValue::S("6b8c736e".into())),
+        ("SK".to_string(), AttributeValue::S("Video".into())),
+        ("Views".to_string(), AttributeValue::N("42".into())),
+        (
+            "Tags".to_string(),
+            AttributeValue::L(vec![
+                AttributeValue::S("rust".into()),
+                AttributeValue::S("aws".into()),
+            ]),
+        ),
+        (
+            "Author".to_string(),
+            AttributeValue::M(
+                [("name".to_string(), AttributeValue::S("VideoGal12".into()))].into(),
+            ),
+        ),
+        ("Published".to_string(), AttributeValue::S("PUBLIC".into())),
+        (
+            "CreatedAt".to_string(),
+            AttributeValue::N("1657114888".into()),
+        ),
+    ]
+    .into()
+}
+
+#[test]
+fn test_convert_by_reference() {
+    let v = video();
+
+    assert_eq!(v.to_item(), item());
+
+    let converted: HashMap<String, AttributeValue> = (&v).into();
+    assert_eq!(converted, item());
+
+    let converted: HashMap<String, AttributeValue> = v.clone().into();
+    assert_eq!(converted, item());
+
+    assert_eq!(v.to_attribute_value(), AttributeValue::M(item()));
+
+    let converted: Video = item().try_into().unwrap();
+    assert_eq!(converted, v);
+}
+
+#[test]
+fn test_convert_by_reference_without_optional_values() {
+    let v = Video {
+        views: None,
+        created_at: None,
+        ..video()
+    };
+
+    let item = v.to_item();
+    assert!(!item.contains_key("Views"));
+    assert!(item.get("CreatedAt").is_some_and(AttributeValue::is_n));
+    assert!(!item.contains_key("Cache"));
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+mod facade {
+    pub use dynamodel as renamed;
+}
+
+#[derive(facade::renamed::Dynamodel, Debug, PartialEq)]
+#[dynamodel(crate = "facade::renamed", rename_all = "PascalCase")]
+struct Video {
+    id: String,
+    views: facade::renamed::Number,
+}
+
+#[test]
+fn test_convert_with_crate_path() {
+    let video = Video {
+        id: "7cf27a02".into(),
+        views: 42.into(),
+    };
+
+    let item: HashMap<String, facade::renamed::aws_sdk_dynamodb::types::AttributeValue> =
+        video.into();
+    assert_eq!(item["Id"], AttributeValue::S("7cf27a02".into()));
+    assert_eq!(item["Views"], AttributeValue::N("42".into()));
+
+    let converted = Video::try_from(item).unwrap();
+    assert_eq!(
+        converted,
+        Video {
+            id: "7cf27a02".into(),
+            views: 42.into(),
+        }
+    );
+}
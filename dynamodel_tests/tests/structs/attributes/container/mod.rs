@@ -1,5 +1,7 @@
 use super::*;
 
+mod by_ref;
+mod crate_path;
 mod extra;
 mod rename_all;
 mod tag;
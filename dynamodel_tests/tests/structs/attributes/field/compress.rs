@@ -0,0 +1,121 @@
+use super::*;
+use dynamodel::{AttributeValueConvertible, compression};
+use std::collections::BTreeMap;
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Document {
+    id: String,
+    #[dynamodel(compress = "zstd")]
+    body: String,
+    #[dynamodel(compress = "gzip")]
+    paragraphs: Option<Vec<String>>,
+    #[dynamodel(json, compress = "gzip")]
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Dynamodel, Debug, PartialEq)]
+struct Measurement {
+    #[dynamodel(compress = "zstd")]
+    samples: Vec<f64>,
+    #[dynamodel(json, compress = "gzip")]
+    counts: HashMap<(u8, u8), u32>,
+}
+
+fn document() -> Document {
+    Document {
+        id: "7cf27a02".into(),
+        body: "lorem ipsum ".repeat(100),
+        paragraphs: Some(vec!["lorem".into(), "ipsum".into()]),
+        metadata: [("author".to_string(), "Kanji".to_string())].into(),
+    }
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+#[test]
+fn test_into_hashmap() {
+    let item = document().try_into_item().unwrap();
+
+    assert_eq!(item["Id"], s("7cf27a02"));
+    for name in ["Body", "Paragraphs", "Metadata"] {
+        assert!(compression::is_compressed(&item[name]), "{name}");
+    }
+
+    let body = item["Body"].as_b().unwrap().as_ref();
+    assert_eq!(&body[..5], b"DZ\x01zS");
+    assert!(body.len() < document().body.len());
+
+    let metadata = item["Metadata"].as_b().unwrap().as_ref();
+    assert_eq!(&metadata[..5], b"DZ\x01gS");
+
+    let item = Document {
+        paragraphs: None,
+        ..document()
+    }
+    .try_into_item()
+    .unwrap();
+    assert!(!item.contains_key("Paragraphs"));
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    let item = document().try_into_item().unwrap();
+    assert_ok_eq!(Document::try_from(item.clone()), document());
+    assert_ok_eq!(
+        Document::try_from_attribute_value(&AttributeValue::M(item.clone())),
+        document()
+    );
+    assert_ok_eq!(Document::try_from_item_map(item), document());
+}
+
+#[test]
+fn test_try_from_hashmap_legacy_values() {
+    let item: HashMap<String, AttributeValue> = [
+        ("Id".to_string(), s("7cf27a02")),
+        ("Body".to_string(), s(&document().body)),
+        (
+            "Paragraphs".to_string(),
+            AttributeValue::L(vec![s("lorem"), s("ipsum")]),
+        ),
+        ("Metadata".to_string(), s(r#"{"author":"Kanji"}"#)),
+    ]
+    .into();
+    assert_ok_eq!(Document::try_from(item), document());
+}
+
+#[test]
+fn test_try_from_hashmap_corrupted_value() {
+    let mut item = document().try_into_item().unwrap();
+    item.insert(
+        "Body".into(),
+        AttributeValue::B(b"DZ\x01zS corrupted".to_vec().into()),
+    );
+    assert!(matches!(
+        Document::try_from(item),
+        Err(ConvertError::Compression(name, _)) if name == "Body"
+    ));
+}
+
+#[test]
+fn test_try_into_hashmap_invalid_value() {
+    let measurement = Measurement {
+        samples: vec![1.5, f64::NAN],
+        counts: HashMap::new(),
+    };
+    assert!(matches!(
+        measurement.try_into_item(),
+        Err(ConvertError::InvalidNumber(v)) if v == "NaN"
+    ));
+
+    let measurement = Measurement {
+        samples: vec![1.5],
+        counts: [((1, 2), 3)].into(),
+    };
+    assert!(matches!(
+        measurement.try_into_item(),
+        Err(ConvertError::Json(name, _)) if name == "counts"
+    ));
+}
@@ -0,0 +1,199 @@
+use super::*;
+use dynamodel::{
+    AttributeValueConvertible, KeySchema,
+    encryption::{self, DataKey, StaticKeyProvider},
+};
+use std::collections::BTreeMap;
+
+const TABLE: KeySchema = KeySchema::new("PK").with_sort_key("SK");
+
+fn keys() -> StaticKeyProvider {
+    StaticKeyProvider::new(DataKey::new("2024-06", [2; 32]))
+        .with_key(DataKey::new("2024-01", [1; 32]))
+}
+
+fn old_keys() -> StaticKeyProvider {
+    StaticKeyProvider::new(DataKey::new("2024-01", [1; 32]))
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(key_provider = "keys", sign = "TABLE", rename_all = "PascalCase")]
+struct Customer {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    #[dynamodel(rename = "SK")]
+    revision: u32,
+    name: String,
+    #[dynamodel(encrypt)]
+    email: String,
+    #[dynamodel(encrypt)]
+    phone: Option<String>,
+    #[dynamodel(encrypt, json, compress = "gzip")]
+    notes: BTreeMap<String, String>,
+}
+
+// Written before rotating the keys
+#[derive(Dynamodel, Debug, PartialEq)]
+#[dynamodel(key_provider = "old_keys")]
+struct Contact {
+    #[dynamodel(encrypt)]
+    email: String,
+}
+
+#[derive(Dynamodel, Debug, PartialEq)]
+#[dynamodel(key_provider = "keys")]
+struct RotatedContact {
+    #[dynamodel(encrypt)]
+    email: String,
+}
+
+fn customer() -> Customer {
+    Customer {
+        id: "customer#1".into(),
+        revision: 1,
+        name: "Kanji".into(),
+        email: "kanji@example.com".into(),
+        phone: Some("+81-3-0000-0000".into()),
+        notes: [("tier".to_string(), "gold".to_string())].into(),
+    }
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+#[test]
+fn test_try_into_item() {
+    let item = customer().try_into_item().unwrap();
+
+    assert_eq!(item["PK"], s("customer#1"));
+    assert_eq!(item["SK"], AttributeValue::N("1".into()));
+    assert_eq!(item["Name"], s("Kanji"));
+    for name in ["Email", "Phone", "Notes"] {
+        assert!(encryption::is_encrypted(&item[name]), "{name}");
+    }
+    assert!(item.contains_key(encryption::SIGNATURE_ATTRIBUTE));
+    assert_eq!(item.len(), 7);
+
+    let item = Customer {
+        phone: None,
+        ..customer()
+    }
+    .try_into_item()
+    .unwrap();
+    assert!(!item.contains_key("Phone"));
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    let item = customer().try_into_item().unwrap();
+    assert_ok_eq!(Customer::try_from(item.clone()), customer());
+    assert_ok_eq!(
+        Customer::try_from_attribute_value(&AttributeValue::M(item)),
+        customer()
+    );
+
+    let item = Customer {
+        phone: None,
+        ..customer()
+    }
+    .try_into_item()
+    .unwrap();
+    assert_ok_eq!(
+        Customer::try_from(item),
+        Customer {
+            phone: None,
+            ..customer()
+        }
+    );
+}
+
+#[test]
+fn test_try_from_hashmap_older_key() {
+    let item = Contact {
+        email: "kanji@example.com".into(),
+    }
+    .try_into_item()
+    .unwrap();
+    let bytes = item["email"].as_b().unwrap().as_ref();
+    assert_eq!(&bytes[..11], b"DE\x01\x072024-01");
+
+    assert_ok_eq!(
+        RotatedContact::try_from(item),
+        RotatedContact {
+            email: "kanji@example.com".into(),
+        }
+    );
+
+    let item = RotatedContact {
+        email: "kanji@example.com".into(),
+    }
+    .try_into_item()
+    .unwrap();
+    assert!(matches!(
+        Contact::try_from(item),
+        Err(ConvertError::Decryption(name, _)) if name == "email"
+    ));
+}
+
+#[test]
+fn test_try_from_hashmap_tampered_item() {
+    let item = customer().try_into_item().unwrap();
+
+    // An encrypted value copied from another item
+    let mut other = item.clone();
+    let another = Customer {
+        id: "customer#2".into(),
+        ..customer()
+    }
+    .try_into_item()
+    .unwrap();
+    other.insert("Email".into(), another["Email"].clone());
+    assert!(matches!(
+        Customer::try_from(other),
+        Err(ConvertError::Decryption(name, _)) if name == encryption::SIGNATURE_ATTRIBUTE
+    ));
+
+    // A removed encrypted value
+    let mut removed = item.clone();
+    removed.remove("Phone");
+    assert!(matches!(
+        Customer::try_from(removed),
+        Err(ConvertError::Decryption(name, _)) if name == encryption::SIGNATURE_ATTRIBUTE
+    ));
+
+    // A plaintext value
+    let mut plaintext = item.clone();
+    plaintext.insert("Email".into(), s("kanji@example.com"));
+    assert!(Customer::try_from(plaintext).is_err());
+
+    // Attributes other than the keys and the encrypted ones aren't signed.
+    let mut renamed = item;
+    renamed.insert("Name".into(), s("Tanaka"));
+    assert_ok_eq!(
+        Customer::try_from(renamed),
+        Customer {
+            name: "Tanaka".into(),
+            ..customer()
+        }
+    );
+}
+
+#[test]
+fn test_try_from_hashmap_modified_value() {
+    let item = Contact {
+        email: "kanji@example.com".into(),
+    }
+    .try_into_item()
+    .unwrap();
+
+    let mut bytes = item["email"].as_b().unwrap().clone().into_inner();
+    *bytes.last_mut().unwrap() ^= 1;
+    let item: HashMap<String, AttributeValue> =
+        [("email".to_string(), AttributeValue::B(bytes.into()))].into();
+
+    assert!(matches!(
+        Contact::try_from(item),
+        Err(ConvertError::Decryption(name, _)) if name == "email"
+    ));
+}
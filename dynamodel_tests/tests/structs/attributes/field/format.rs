@@ -0,0 +1,94 @@
+use super::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::time::{Duration, SystemTime};
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Event {
+    #[dynamodel(format = "epoch_seconds")]
+    started_at: DateTime<Utc>,
+    #[dynamodel(format = "epoch_millis")]
+    finished_at: Option<jiff::Timestamp>,
+    #[dynamodel(format = "%Y/%m/%d")]
+    day: NaiveDate,
+    #[dynamodel(format = "%Y-%m-%d %H:%M:%S")]
+    logged_at: SystemTime,
+    #[dynamodel(format = "rfc3339")]
+    published_at: time::OffsetDateTime,
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn n(v: &str) -> AttributeValue {
+    AttributeValue::N(v.into())
+}
+
+fn event() -> Event {
+    Event {
+        started_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        finished_at: Some(jiff::Timestamp::from_millisecond(1_700_000_000_123).unwrap()),
+        day: NaiveDate::from_ymd_opt(2023, 11, 14).unwrap(),
+        logged_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        published_at: time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+    }
+}
+
+fn item() -> HashMap<String, AttributeValue> {
+    [
+        ("StartedAt".to_string(), n("1700000000")),
+        ("FinishedAt".to_string(), n("1700000000123")),
+        ("Day".to_string(), s("2023/11/14")),
+        ("LoggedAt".to_string(), s("2023-11-14 22:13:20")),
+        (
+            "PublishedAt".to_string(),
+            s("2023-11-14T22:13:20.000000000Z"),
+        ),
+    ]
+    .into()
+}
+
+#[test]
+fn test_into_hashmap() {
+    let actual: HashMap<String, AttributeValue> = event().into();
+    assert_eq!(actual, item());
+
+    let actual: HashMap<String, AttributeValue> = Event {
+        finished_at: None,
+        ..event()
+    }
+    .into();
+    assert!(!actual.contains_key("FinishedAt"));
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    assert_ok_eq!(Event::try_from(item()), event());
+
+    let mut item = item();
+    item.remove("FinishedAt");
+    assert_ok_eq!(
+        Event::try_from(item),
+        Event {
+            finished_at: None,
+            ..event()
+        }
+    );
+}
+
+#[test]
+fn test_try_from_hashmap_invalid_value() {
+    let mut item = item();
+    item.insert("Day".into(), s("2023-11-14"));
+    let actual = Event::try_from(item);
+    assert!(matches!(actual, Err(ConvertError::ParseValue(_, v)) if v == "2023-11-14"));
+
+    let mut item = self::item();
+    item.insert("StartedAt".into(), s("1700000000"));
+    assert_attribute_unmatch!(Event::try_from(item), "N");
+
+    let mut item = self::item();
+    item.remove("LoggedAt");
+    assert_field_not_set!(Event::try_from(item), "logged_at");
+}
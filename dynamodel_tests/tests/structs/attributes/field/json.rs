@@ -0,0 +1,104 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+struct Dimensions {
+    width: u32,
+    height: u32,
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Image {
+    id: String,
+    #[dynamodel(json)]
+    dimensions: Dimensions,
+    #[dynamodel(json, rename = "Labels")]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Dynamodel, Debug)]
+struct Histogram {
+    #[dynamodel(json)]
+    counts: HashMap<(u8, u8), u32>,
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn image() -> Image {
+    Image {
+        id: "7cf27a02".into(),
+        dimensions: Dimensions {
+            width: 640,
+            height: 480,
+        },
+        tags: Some(vec!["cat".into()]),
+    }
+}
+
+fn item() -> HashMap<String, AttributeValue> {
+    [
+        ("Id".to_string(), s("7cf27a02")),
+        ("Dimensions".to_string(), s(r#"{"width":640,"height":480}"#)),
+        ("Labels".to_string(), s(r#"["cat"]"#)),
+    ]
+    .into()
+}
+
+#[test]
+fn test_into_hashmap() {
+    assert_ok_eq!(image().try_into_item(), item());
+
+    let actual: HashMap<String, AttributeValue> = image().try_into().unwrap();
+    assert_eq!(actual, item());
+
+    let actual = Image {
+        tags: None,
+        ..image()
+    }
+    .try_into_item()
+    .unwrap();
+    assert!(!actual.contains_key("Labels"));
+}
+
+#[test]
+fn test_into_hashmap_unserializable_value() {
+    let histogram = Histogram {
+        counts: [((1, 2), 3)].into(),
+    };
+    assert!(matches!(
+        histogram.try_into_item(),
+        Err(ConvertError::Json(name, _)) if name == "counts"
+    ));
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    assert_ok_eq!(Image::try_from(item()), image());
+
+    let mut item = item();
+    item.remove("Labels");
+    assert_ok_eq!(
+        Image::try_from(item),
+        Image {
+            tags: None,
+            ..image()
+        }
+    );
+}
+
+#[test]
+fn test_try_from_hashmap_invalid_json() {
+    let mut item = item();
+    item.insert("Dimensions".into(), s(r#"{"width":640}"#));
+    assert!(matches!(
+        Image::try_from(item),
+        Err(ConvertError::Json(name, _)) if name == "Dimensions"
+    ));
+
+    let mut item = self::item();
+    item.insert("Labels".into(), AttributeValue::L(vec![]));
+    assert_attribute_unmatch!(Image::try_from(item), "S");
+}
@@ -1,5 +1,13 @@
 use super::*;
 
+mod compress;
+mod encrypt;
+mod format;
 mod into_and_try_from;
+mod json;
 mod rename;
 mod skip_into_and_try_from_item;
+mod timestamps;
+mod try_into;
+mod ttl;
+mod version;
@@ -81,3 +81,40 @@ fn test_try_from_hashmap() {
 
     assert_ok_eq!(actual, expected);
 }
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Reply {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    #[dynamodel(skip_into, try_from_item = "get_thread")]
+    thread: String,
+}
+
+fn get_thread(item: &HashMap<String, AttributeValue>) -> Result<String, ConvertError> {
+    item.get("PK")
+        .ok_or(ConvertError::FieldNotSet("PK".into()))
+        .and_then(|v| {
+            v.as_s()
+                .map_err(|e| ConvertError::AttributeValueUnmatched("S".into(), e.clone()))
+        })
+        .map(|v| v.split('#').next().unwrap().to_string())
+}
+
+#[test]
+fn test_try_from_item_sees_values_of_other_fields() {
+    let expected = Reply {
+        id: "Thread1#Reply2".into(),
+        thread: "Thread1".into(),
+    };
+
+    let item: HashMap<String, AttributeValue> =
+        [("PK".to_string(), AttributeValue::S("Thread1#Reply2".into()))].into();
+
+    assert_ok_eq!(
+        <Reply as dynamodel::AttributeValueConvertible>::try_from_item(&item),
+        expected
+    );
+
+    let actual = Reply::try_from(item);
+    assert_ok_eq!(actual, expected);
+}
@@ -0,0 +1,136 @@
+use super::*;
+use dynamodel::{
+    Expression, KeySchema, Timestamp, Timestamped, table::Table, testing::MemoryTable,
+};
+use std::time::{Duration, SystemTime};
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Video {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    #[dynamodel(created_at)]
+    created_at: Option<SystemTime>,
+    #[dynamodel(updated_at, format = "epoch_seconds", rename = "Modified")]
+    updated_at: SystemTime,
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+struct Comment {
+    id: String,
+    #[dynamodel(created_at, format = "epoch_millis")]
+    created_at: chrono::DateTime<chrono::Utc>,
+    #[dynamodel(updated_at)]
+    updated_at: Option<time::OffsetDateTime>,
+}
+
+fn epoch(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn n(v: &str) -> AttributeValue {
+    AttributeValue::N(v.into())
+}
+
+#[test]
+fn test_attribute_names() {
+    assert_eq!(Video::CREATED_AT_ATTRIBUTE, Some("CreatedAt"));
+    assert_eq!(Video::UPDATED_AT_ATTRIBUTE, Some("Modified"));
+    assert_eq!(Comment::CREATED_AT_ATTRIBUTE, Some("created_at"));
+}
+
+#[test]
+fn test_timestamps_are_filled_in_on_write() {
+    let before = SystemTime::now().unix_nanos() / 1_000_000_000;
+
+    let video = Video {
+        id: "7cf27a02".into(),
+        created_at: None,
+        updated_at: epoch(0),
+    };
+    let item: HashMap<String, AttributeValue> = video.into();
+
+    let converted: Video = item.clone().try_into().unwrap();
+    let created_at = converted.created_at.unwrap().unix_nanos() / 1_000_000_000;
+    let updated_at = converted.updated_at.unix_nanos() / 1_000_000_000;
+    assert!(created_at >= before);
+    assert!(updated_at >= before);
+    assert!(item["CreatedAt"].as_s().unwrap().ends_with('Z'));
+    assert!(item["Modified"].is_n());
+}
+
+#[test]
+fn test_created_at_is_kept_on_write() {
+    let video = Video {
+        id: "7cf27a02".into(),
+        created_at: Some(epoch(1_700_000_000)),
+        updated_at: epoch(0),
+    };
+    let item: HashMap<String, AttributeValue> = video.into();
+    assert_eq!(item["CreatedAt"], s("2023-11-14T22:13:20.000000000Z"));
+    assert_ne!(item["Modified"], n("0"));
+}
+
+#[test]
+fn test_timestamps_are_read_with_their_format() {
+    let item: HashMap<String, AttributeValue> = [
+        ("id".to_string(), s("1")),
+        ("created_at".to_string(), n("1700000000123")),
+        ("updated_at".to_string(), s("2023-11-15T07:13:20+09:00")),
+    ]
+    .into();
+    let comment: Comment = item.try_into().unwrap();
+
+    assert_eq!(comment.created_at.timestamp_millis(), 1_700_000_000_123);
+    assert_eq!(
+        comment.updated_at.map(|t| t.unix_timestamp()),
+        Some(1_700_000_000)
+    );
+
+    let item: HashMap<String, AttributeValue> = [
+        ("id".to_string(), s("1")),
+        ("created_at".to_string(), s("2023-11-14T22:13:20Z")),
+    ]
+    .into();
+    assert_attribute_unmatch!(Comment::try_from(item), "N");
+
+    let item: HashMap<String, AttributeValue> = [
+        ("id".to_string(), s("1")),
+        ("created_at".to_string(), n("0")),
+    ]
+    .into();
+    let comment: Comment = item.try_into().unwrap();
+    assert_eq!(comment.updated_at, None);
+}
+
+#[tokio::test]
+async fn test_timestamp_update_keeps_created_at() {
+    let table = MemoryTable::new(KeySchema::new("PK"));
+    let key: HashMap<String, AttributeValue> = [("PK".to_string(), s("7cf27a02"))].into();
+
+    let expression = Video::timestamp_update()
+        .merge(Expression::update("SET Title = :title").value(":title", "Rust".to_string()));
+    let first: Video = table
+        .update_item(key.clone(), expression)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(first.created_at.is_some());
+
+    let mut item: HashMap<String, AttributeValue> = first.clone().into();
+    item.insert("CreatedAt".into(), s("2000-01-01T00:00:00Z"));
+    table.put_item(item, None).await.unwrap();
+
+    let second: Video = table
+        .update_item(key, Video::timestamp_update())
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(second.created_at, Some(epoch(946_684_800)));
+}
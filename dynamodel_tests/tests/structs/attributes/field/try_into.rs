@@ -0,0 +1,113 @@
+use super::*;
+use dynamodel::{AttributeValueConvertible, Expression};
+
+#[derive(Debug, Dynamodel, PartialEq, Clone)]
+struct Video {
+    #[dynamodel(try_into = "non_empty")]
+    title: String,
+    #[dynamodel(fallible)]
+    rating: f64,
+}
+
+#[derive(Debug, Dynamodel, PartialEq, Clone)]
+struct Playlist {
+    name: String,
+    #[dynamodel(fallible)]
+    videos: Vec<Video>,
+}
+
+#[derive(Debug)]
+struct EmptyString;
+
+impl From<EmptyString> for ConvertError {
+    fn from(_: EmptyString) -> Self {
+        ConvertError::Other("the string is empty".into())
+    }
+}
+
+fn non_empty(value: String) -> Result<AttributeValue, EmptyString> {
+    if value.is_empty() {
+        Err(EmptyString)
+    } else {
+        Ok(AttributeValue::S(value))
+    }
+}
+
+fn video(title: &str) -> Video {
+    Video {
+        title: title.into(),
+        rating: 4.5,
+    }
+}
+
+#[test]
+fn test_try_into_hashmap() {
+    let expected: HashMap<String, AttributeValue> = [
+        ("title".to_string(), AttributeValue::S("Rust".into())),
+        ("rating".to_string(), AttributeValue::N("4.5".into())),
+    ]
+    .into();
+
+    assert_eq!(video("Rust").try_into_item().unwrap(), expected);
+
+    let item: HashMap<String, AttributeValue> = video("Rust").try_into().unwrap();
+    assert_eq!(item, expected);
+    assert_ok_eq!(Video::try_from(item), video("Rust"));
+}
+
+#[test]
+fn test_try_into_hashmap_error() {
+    let result: Result<HashMap<String, AttributeValue>, ConvertError> = video("").try_into();
+    assert!(
+        matches!(result, Err(ConvertError::Other(e)) if e.to_string() == "the string is empty")
+    );
+
+    let result = Video {
+        rating: f64::NAN,
+        ..video("Rust")
+    }
+    .try_into_item();
+    assert!(matches!(result, Err(ConvertError::InvalidNumber(v)) if v == "NaN"));
+}
+
+#[test]
+fn test_errors_of_nested_items_are_returned() {
+    let playlist = Playlist {
+        name: "Favorites".into(),
+        videos: vec![video("Rust"), video("")],
+    };
+    let result = playlist.clone().try_into_item();
+    assert!(
+        matches!(result, Err(ConvertError::Other(e)) if e.to_string() == "the string is empty")
+    );
+    assert!(playlist.try_into_attribute_value().is_err());
+
+    let result = Expression::update("SET #v = :v").try_value(":v", video(""));
+    assert!(matches!(result, Err(ConvertError::Other(_))));
+
+    let playlist = Playlist {
+        name: "Favorites".into(),
+        videos: vec![video("Rust")],
+    };
+    let item = playlist.clone().try_into_item().unwrap();
+    assert_eq!(
+        item["videos"],
+        AttributeValue::L(vec![AttributeValue::M(
+            video("Rust").try_into_item().unwrap()
+        )])
+    );
+    assert_ok_eq!(Playlist::try_from(item), playlist);
+}
+
+#[test]
+fn test_fallible_types_are_marked() {
+    // The derived `From` of an object with a field of a fallible type fails to compile, so the
+    // field needs `fallible` like `Playlist::videos`.
+    let fallible = [
+        <Video as AttributeValueConvertible>::FALLIBLE,
+        <Vec<Video> as AttributeValueConvertible>::FALLIBLE,
+        <Playlist as AttributeValueConvertible>::FALLIBLE,
+        <String as AttributeValueConvertible>::FALLIBLE,
+    ];
+    assert_eq!(fallible, [true, true, true, false]);
+}
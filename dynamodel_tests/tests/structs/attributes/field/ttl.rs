@@ -0,0 +1,92 @@
+use super::*;
+use dynamodel::TimeToLive;
+use std::time::{Duration, SystemTime};
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Session {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    #[dynamodel(ttl)]
+    expires_at: Option<SystemTime>,
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+struct Cache {
+    key: String,
+    #[dynamodel(ttl, rename = "ttl")]
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn n(v: &str) -> AttributeValue {
+    AttributeValue::N(v.into())
+}
+
+#[test]
+fn test_ttl_attribute() {
+    assert_eq!(Session::TTL_ATTRIBUTE, "ExpiresAt");
+    assert_eq!(Cache::TTL_ATTRIBUTE, "ttl");
+    assert_eq!(Cache::time_to_live_specification().attribute_name(), "ttl");
+}
+
+#[test]
+fn test_ttl_is_stored_as_epoch_seconds() {
+    let session = Session {
+        id: "f8a3".into(),
+        expires_at: Some(SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_999)),
+    };
+    let item: HashMap<String, AttributeValue> = session.into();
+    let expected: HashMap<String, AttributeValue> = [
+        ("PK".to_string(), s("f8a3")),
+        ("ExpiresAt".to_string(), n("1700000000")),
+    ]
+    .into();
+    assert_eq!(item, expected);
+
+    let session: Session = item.try_into().unwrap();
+    assert_eq!(
+        session.expires_at,
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+    );
+}
+
+#[test]
+fn test_none_never_expires() {
+    let session = Session {
+        id: "f8a3".into(),
+        expires_at: None,
+    };
+    let item: HashMap<String, AttributeValue> = session.clone().into();
+    assert!(!item.contains_key("ExpiresAt"));
+
+    let converted: Session = item.try_into().unwrap();
+    assert_eq!(converted, session);
+}
+
+#[test]
+fn test_chrono_ttl() {
+    let item: HashMap<String, AttributeValue> = [
+        ("key".to_string(), s("k")),
+        ("ttl".to_string(), n("1700000000")),
+    ]
+    .into();
+    let cache: Cache = item.clone().try_into().unwrap();
+    assert_eq!(cache.expires_at.timestamp(), 1_700_000_000);
+
+    let converted: HashMap<String, AttributeValue> = cache.into();
+    assert_eq!(converted, item);
+
+    let item: HashMap<String, AttributeValue> = [("key".to_string(), s("k"))].into();
+    assert_field_not_set!(Cache::try_from(item), "expires_at");
+
+    let item: HashMap<String, AttributeValue> = [
+        ("key".to_string(), s("k")),
+        ("ttl".to_string(), s("1700000000")),
+    ]
+    .into();
+    assert_attribute_unmatch!(Cache::try_from(item), "N");
+}
@@ -0,0 +1,114 @@
+use super::*;
+use dynamodel::{
+    KeySchema, Versioned,
+    table::{Table, TableError},
+    testing::MemoryTable,
+};
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(rename_all = "PascalCase")]
+struct Video {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    title: String,
+    #[dynamodel(version, rename = "Ver")]
+    revision: u32,
+}
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+struct Counter {
+    id: String,
+    #[dynamodel(version)]
+    version: u8,
+}
+
+fn video() -> Video {
+    Video {
+        id: "7cf27a02".into(),
+        title: "Rust".into(),
+        revision: 0,
+    }
+}
+
+#[test]
+fn test_version_attribute_uses_renamed_name() {
+    assert_eq!(Video::VERSION_ATTRIBUTE, "Ver");
+}
+
+#[test]
+fn test_increment_version() {
+    let mut video = video();
+
+    let condition = video.increment_version().unwrap();
+    assert_eq!(video.revision, 1);
+    assert_eq!(
+        condition.condition_expression(),
+        Some("attribute_not_exists(#dynamodel_version)")
+    );
+    assert_eq!(condition.names()["#dynamodel_version"], "Ver");
+    assert!(condition.values().is_empty());
+
+    let condition = video.increment_version().unwrap();
+    assert_eq!(video.revision, 2);
+    assert_eq!(
+        condition.condition_expression(),
+        Some("#dynamodel_version = :dynamodel_version")
+    );
+    assert_eq!(
+        condition.values()[":dynamodel_version"],
+        AttributeValue::N("1".into())
+    );
+
+    let item: HashMap<String, AttributeValue> = video.into();
+    assert_eq!(item["Ver"], AttributeValue::N("2".into()));
+}
+
+#[test]
+fn test_increment_version_at_max() {
+    let mut counter = Counter {
+        id: "7cf27a02".into(),
+        version: u8::MAX - 1,
+    };
+    counter.increment_version().unwrap();
+    assert_eq!(counter.version, u8::MAX);
+
+    assert!(matches!(
+        counter.increment_version(),
+        Err(ConvertError::NumberOverflow(ty, v)) if ty == "u8" && v == "255 + 1"
+    ));
+    assert_eq!(counter.version, u8::MAX);
+}
+
+#[tokio::test]
+async fn test_put_versioned() {
+    let table = MemoryTable::new(KeySchema::new("PK"));
+
+    let mut video = video();
+    table.put_versioned(&mut video).await.unwrap();
+    assert_eq!(video.revision, 1);
+
+    let mut stale = video.clone();
+
+    video.title = "Rust 2024".into();
+    table.put_versioned(&mut video).await.unwrap();
+    assert_eq!(video.revision, 2);
+
+    stale.title = "Lost update".into();
+    let err = table.put_versioned(&mut stale).await.unwrap_err();
+    assert!(matches!(err, TableError::VersionConflict));
+    assert_eq!(stale.revision, 1);
+
+    let key = [("PK".to_string(), AttributeValue::S("7cf27a02".into()))].into();
+    let stored: Video = table
+        .get_item(key)
+        .await
+        .unwrap()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(stored, video);
+
+    // A new object can't overwrite an existing item.
+    let err = table.put_versioned(&mut self::video()).await.unwrap_err();
+    assert!(matches!(err, TableError::VersionConflict));
+}
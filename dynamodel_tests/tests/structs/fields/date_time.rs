@@ -0,0 +1,68 @@
+use super::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::time::SystemTime;
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Model {
+    chrono: DateTime<Utc>,
+    date: NaiveDate,
+    time: time::OffsetDateTime,
+    jiff: jiff::Timestamp,
+    system: Option<SystemTime>,
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn model() -> Model {
+    Model {
+        chrono: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        date: NaiveDate::from_ymd_opt(2023, 11, 14).unwrap(),
+        time: time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+        jiff: jiff::Timestamp::from_second(1_700_000_000).unwrap(),
+        system: None,
+    }
+}
+
+#[test]
+fn test_into_hashmap() {
+    let actual: HashMap<String, AttributeValue> = model().into();
+
+    let expected: HashMap<String, AttributeValue> = [
+        ("chrono".to_string(), s("2023-11-14T22:13:20.000000000Z")),
+        ("date".to_string(), s("2023-11-14")),
+        ("time".to_string(), s("2023-11-14T22:13:20.000000000Z")),
+        ("jiff".to_string(), s("2023-11-14T22:13:20.000000000Z")),
+    ]
+    .into();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    let item: HashMap<String, AttributeValue> = [
+        ("chrono".to_string(), s("2023-11-14T22:13:20Z")),
+        ("date".to_string(), s("2023-11-14")),
+        ("time".to_string(), s("2023-11-15T07:13:20+09:00")),
+        ("jiff".to_string(), s("2023-11-14T22:13:20.000Z")),
+    ]
+    .into();
+
+    assert_ok_eq!(Model::try_from(item), model());
+}
+
+#[test]
+fn test_try_from_hashmap_invalid_value() {
+    let mut item: HashMap<String, AttributeValue> = model().into();
+    item.insert("date".into(), s("2023/11/14"));
+
+    let actual = Model::try_from(item);
+    assert!(matches!(actual, Err(ConvertError::ParseValue(_, v)) if v == "2023/11/14"));
+
+    let mut item: HashMap<String, AttributeValue> = model().into();
+    item.insert("jiff".into(), AttributeValue::N("1700000000".into()));
+
+    assert_attribute_unmatch!(Model::try_from(item), "S");
+}
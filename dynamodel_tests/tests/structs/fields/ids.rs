@@ -0,0 +1,69 @@
+use super::*;
+use dynamodel::CompactUuid;
+use ulid::Ulid;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Model {
+    id: Uuid,
+    parent_id: Option<CompactUuid>,
+    event_id: Ulid,
+    homepage: Url,
+}
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::S(v.into())
+}
+
+fn model() -> Model {
+    let id = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+    Model {
+        id,
+        parent_id: Some(CompactUuid(id)),
+        event_id: Ulid::from_string("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap(),
+        homepage: Url::parse("https://example.com/").unwrap(),
+    }
+}
+
+fn item() -> HashMap<String, AttributeValue> {
+    [
+        ("id".to_string(), s("67e55044-10b1-426f-9247-bb680e5fe0c8")),
+        (
+            "parent_id".to_string(),
+            AttributeValue::B(model().id.as_bytes().to_vec().into()),
+        ),
+        ("event_id".to_string(), s("01ARZ3NDEKTSV4RRFFQ69G5FAV")),
+        ("homepage".to_string(), s("https://example.com/")),
+    ]
+    .into()
+}
+
+#[test]
+fn test_into_hashmap() {
+    let actual: HashMap<String, AttributeValue> = model().into();
+    assert_eq!(actual, item());
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    assert_ok_eq!(Model::try_from(item()), model());
+}
+
+#[test]
+fn test_try_from_hashmap_invalid_value() {
+    let mut item = item();
+    item.insert("event_id".into(), s("not-a-ulid"));
+
+    let actual = Model::try_from(item);
+    assert!(
+        matches!(actual, Err(ConvertError::ParseValue(t, v)) if t == "ulid::Ulid" && v == "not-a-ulid")
+    );
+
+    let mut item = self::item();
+    item.insert(
+        "parent_id".into(),
+        s("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+    );
+    assert_attribute_unmatch!(Model::try_from(item), "B");
+}
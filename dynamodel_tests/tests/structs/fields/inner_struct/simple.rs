@@ -42,6 +42,25 @@ fn test_try_from_hashmap() {
     assert_ok_eq!(actual, expected);
 }
 
+#[test]
+fn test_try_from_item_by_reference() {
+    use dynamodel::AttributeValueConvertible;
+
+    let mut item: HashMap<String, AttributeValue> = HashMap::new();
+    item.insert(
+        "inner".into(),
+        AttributeValue::M(Inner { attr: "foo".into() }.into()),
+    );
+
+    let actual = Outer::try_from_item(&item);
+    let expected = Outer {
+        inner: Inner { attr: "foo".into() },
+    };
+
+    assert_ok_eq!(actual, expected);
+    assert!(item.contains_key("inner"));
+}
+
 #[test]
 fn test_try_from_hashmap_field_not_set() {
     let item: HashMap<String, AttributeValue> = HashMap::new();
@@ -1,6 +1,9 @@
 use super::*;
 
 mod bool;
+mod date_time;
+mod ids;
 mod inner_struct;
 mod number;
+mod serde_types;
 mod string;
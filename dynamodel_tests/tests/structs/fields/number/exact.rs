@@ -0,0 +1,127 @@
+use super::*;
+use dynamodel::{AttributeValueConvertible, Number};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Model {
+    price: Decimal,
+    balance: Option<Number>,
+}
+
+fn n(v: &str) -> AttributeValue {
+    AttributeValue::N(v.into())
+}
+
+#[test]
+fn test_into_hashmap() {
+    let m = Model {
+        price: "19.90".parse().unwrap(),
+        balance: Some("123456789012345678901234567890123456.78".parse().unwrap()),
+    };
+    let actual: HashMap<String, AttributeValue> = m.into();
+
+    let expected: HashMap<String, AttributeValue> = [
+        ("price".to_string(), n("19.90")),
+        (
+            "balance".to_string(),
+            n("123456789012345678901234567890123456.78"),
+        ),
+    ]
+    .into();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    let item: HashMap<String, AttributeValue> = [
+        ("price".to_string(), n("0.1")),
+        ("balance".to_string(), n("1E+40")),
+    ]
+    .into();
+    let actual = Model::try_from(item);
+
+    assert_ok_eq!(
+        actual,
+        Model {
+            price: Decimal::new(1, 1),
+            balance: Some("1E+40".parse().unwrap()),
+        }
+    );
+}
+
+#[test]
+fn test_try_from_hashmap_unmatched_attribute_value() {
+    let item: HashMap<String, AttributeValue> =
+        [("price".to_string(), AttributeValue::S("0.1".into()))].into();
+    let actual = Model::try_from(item);
+
+    assert_attribute_unmatch!(actual, "N");
+}
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Counter {
+    count: u8,
+}
+
+#[test]
+fn test_try_from_hashmap_exponent_form() {
+    let item: HashMap<String, AttributeValue> = [("count".to_string(), n("1.2E+2"))].into();
+    assert_ok_eq!(Counter::try_from(item), Counter { count: 120 });
+
+    let item: HashMap<String, AttributeValue> = [("count".to_string(), n("1E+3"))].into();
+    let actual = Counter::try_from(item);
+    assert!(matches!(actual, Err(ConvertError::NumberOverflow(t, v)) if t == "u8" && v == "1E+3"));
+}
+
+#[test]
+fn test_non_finite_floats_are_errors_on_write() {
+    let value = f64::NAN.try_into_attribute_value();
+    assert!(matches!(value, Err(ConvertError::InvalidNumber(v)) if v == "NaN"));
+
+    let value = vec![1.0, f64::INFINITY].try_into_attribute_value();
+    assert!(matches!(value, Err(ConvertError::InvalidNumber(v)) if v == "inf"));
+
+    assert_eq!((-0.0f64).try_into_attribute_value().unwrap(), n("0"),);
+}
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Reading {
+    #[dynamodel(fallible)]
+    value: f64,
+    #[dynamodel(fallible)]
+    total: i128,
+}
+
+#[test]
+fn test_unstorable_numbers_are_errors_of_try_into_item() {
+    let result = Reading {
+        value: f64::NAN,
+        total: 1,
+    }
+    .try_into_item();
+    assert!(matches!(result, Err(ConvertError::InvalidNumber(v)) if v == "NaN"));
+
+    let result = Reading {
+        value: 1e-200,
+        total: 1,
+    }
+    .try_into_item();
+    assert!(matches!(result, Err(ConvertError::InvalidNumber(_))));
+
+    let result = Reading {
+        value: 0.5,
+        total: i128::MIN,
+    }
+    .try_into_item();
+    assert!(matches!(result, Err(ConvertError::InvalidNumber(_))));
+
+    let item = Reading {
+        value: 0.5,
+        total: 1,
+    }
+    .try_into_item()
+    .unwrap();
+    assert_eq!(item["value"], n("0.5"));
+    assert_eq!(item["total"], n("1"));
+}
@@ -1,5 +1,6 @@
 use super::*;
 
+mod exact;
 mod opt;
 mod simple;
 mod vec;
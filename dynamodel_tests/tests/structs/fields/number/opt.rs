@@ -1,18 +1,24 @@
 use super::*;
 
 macro_rules! int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Debug, Dynamodel, PartialEq)]
                 struct [<Model$ty>] {
-                    attr: Option<$ty>,
+                    $($attr)* attr: Option<$ty>,
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let m = [<Model$ty>] { attr: Some(10) };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.$($into)*;
 
                     let mut expected: HashMap<String, AttributeValue> = HashMap::new();
                     expected.insert("attr".into(), AttributeValue::N("10".into()));
@@ -23,7 +29,7 @@ macro_rules! int_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap_from_none>]() {
                     let m = [<Model$ty>] { attr: None };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.$($into)*;
 
                     let expected: HashMap<String, AttributeValue> = HashMap::new();
                     assert_eq!(actual, expected);
@@ -72,7 +78,8 @@ macro_rules! int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules! float_test {
     ($($ty:ty),*) => {
@@ -80,13 +87,13 @@ macro_rules! float_test {
             paste::item! {
                 #[derive(Debug, Dynamodel, PartialEq)]
                 struct [<Model$ty>] {
-                    attr: Option<$ty>,
+                    #[dynamodel(fallible)] attr: Option<$ty>,
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let m = [<Model$ty>] { attr: Some(1.2) };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.try_into_item().unwrap();
 
                     let mut expected: HashMap<String, AttributeValue> = HashMap::new();
                     expected.insert("attr".into(), AttributeValue::N("1.2".into()));
@@ -97,7 +104,7 @@ macro_rules! float_test {
                 #[test]
                 fn [<test_ $ty _into_hashmap_from_none>]() {
                     let m = [<Model$ty>] { attr: None };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.try_into_item().unwrap();
 
                     let expected: HashMap<String, AttributeValue> = HashMap::new();
                     assert_eq!(actual, expected);
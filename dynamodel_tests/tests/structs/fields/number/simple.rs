@@ -1,18 +1,24 @@
 use super::*;
 
 macro_rules! int_test {
-    ($($ty:ty),*) => {
+    ($($ty:ident),*) => {
+        int_test!($($ty [] [into()]),*);
+    };
+    (fallible: $($ty:ident),*) => {
+        int_test!($($ty [#[dynamodel(fallible)]] [try_into_item().unwrap()]),*);
+    };
+    ($($ty:ident [$($attr:tt)*] [$($into:tt)*]),*) => {
         $(
             paste::item! {
                 #[derive(Debug, Dynamodel, PartialEq)]
                 struct [<Model$ty>] {
-                    attr: $ty,
+                    $($attr)* attr: $ty,
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let m = [<Model$ty>] { attr: 10 };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.$($into)*;
 
                     let mut expected: HashMap<String, AttributeValue> = HashMap::new();
                     expected.insert("attr".into(), AttributeValue::N("10".into()));
@@ -61,7 +67,8 @@ macro_rules! int_test {
     }
 }
 
-int_test!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+int_test!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+int_test!(fallible: u128, i128);
 
 macro_rules! float_test {
     ($($ty:ty),*) => {
@@ -69,13 +76,13 @@ macro_rules! float_test {
             paste::item! {
                 #[derive(Debug, Dynamodel, PartialEq)]
                 struct [<Model$ty>] {
-                    attr: $ty,
+                    #[dynamodel(fallible)] attr: $ty,
                 }
 
                 #[test]
                 fn [<test_ $ty _into_hashmap>]() {
                     let m = [<Model$ty>] { attr: 1.2 };
-                    let actual: HashMap<String, AttributeValue> = m.into();
+                    let actual: HashMap<String, AttributeValue> = m.try_into_item().unwrap();
 
                     let mut expected: HashMap<String, AttributeValue> = HashMap::new();
                     expected.insert("attr".into(), AttributeValue::N("1.2".into()));
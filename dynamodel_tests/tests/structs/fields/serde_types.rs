@@ -0,0 +1,119 @@
+use super::*;
+use dynamodel::Serde;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Settings {
+    theme: Theme,
+    font_size: u8,
+    shortcuts: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Dynamodel, PartialEq)]
+struct Model {
+    id: String,
+    #[dynamodel(fallible)]
+    settings: Serde<Settings>,
+    #[dynamodel(fallible)]
+    history: Option<Serde<Vec<(u32, bool)>>>,
+}
+
+fn model() -> Model {
+    Model {
+        id: "7cf27a02".into(),
+        settings: Serde(Settings {
+            theme: Theme::Dark,
+            font_size: 14,
+            shortcuts: [("save".to_string(), "Ctrl+S".to_string())].into(),
+        }),
+        history: Some(Serde(vec![(1, true)])),
+    }
+}
+
+fn item() -> HashMap<String, AttributeValue> {
+    [
+        ("id".to_string(), AttributeValue::S("7cf27a02".into())),
+        (
+            "settings".to_string(),
+            AttributeValue::M(
+                [
+                    ("theme".to_string(), AttributeValue::S("dark".into())),
+                    ("font_size".to_string(), AttributeValue::N("14".into())),
+                    (
+                        "shortcuts".to_string(),
+                        AttributeValue::M(
+                            [("save".to_string(), AttributeValue::S("Ctrl+S".into()))].into(),
+                        ),
+                    ),
+                ]
+                .into(),
+            ),
+        ),
+        (
+            "history".to_string(),
+            AttributeValue::L(vec![AttributeValue::L(vec![
+                AttributeValue::N("1".into()),
+                AttributeValue::Bool(true),
+            ])]),
+        ),
+    ]
+    .into()
+}
+
+#[test]
+fn test_into_hashmap() {
+    assert_eq!(model().try_into_item().unwrap(), item());
+
+    let actual: HashMap<String, AttributeValue> = model().try_into().unwrap();
+    assert_eq!(actual, item());
+}
+
+#[derive(Debug, Dynamodel)]
+struct Counts {
+    #[dynamodel(fallible)]
+    by_pair: Serde<HashMap<(u8, u8), u32>>,
+    #[dynamodel(fallible)]
+    history: Vec<Serde<HashMap<(u8, u8), u32>>>,
+}
+
+#[test]
+fn test_try_into_hashmap_unserializable_value() {
+    let counts = Counts {
+        by_pair: Serde([((1, 2), 3)].into()),
+        history: vec![],
+    };
+    let actual = counts.try_into_item();
+    assert!(matches!(actual, Err(ConvertError::Other(_))));
+
+    let counts = Counts {
+        by_pair: Serde(HashMap::new()),
+        history: vec![Serde(HashMap::new()), Serde([((1, 2), 3)].into())],
+    };
+    let actual = counts.try_into_item();
+    assert!(matches!(actual, Err(ConvertError::Other(_))));
+}
+
+#[test]
+fn test_try_from_hashmap() {
+    assert_ok_eq!(Model::try_from(item()), model());
+}
+
+#[test]
+fn test_try_from_hashmap_invalid_value() {
+    let mut item = item();
+    item.insert(
+        "settings".into(),
+        AttributeValue::M([("theme".to_string(), AttributeValue::S("blue".into()))].into()),
+    );
+
+    let actual = Model::try_from(item);
+    assert!(matches!(actual, Err(ConvertError::Other(e)) if e.to_string().contains("blue")));
+}
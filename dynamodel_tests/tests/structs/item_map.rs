@@ -0,0 +1,97 @@
+use super::*;
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::BuildHasherDefault;
+
+type OtherHashMap = HashMap<String, AttributeValue, BuildHasherDefault<DefaultHasher>>;
+
+#[derive(Dynamodel, Debug, PartialEq, Clone)]
+#[dynamodel(by_ref, extra = "Order::key", tag = "Type", rename_all = "PascalCase")]
+struct Order {
+    #[dynamodel(rename = "PK")]
+    id: String,
+    quantity: u32,
+    note: Option<String>,
+    #[dynamodel(skip_into, try_from_item = "count_keys")]
+    keys: usize,
+}
+
+impl Order {
+    fn key(&self) -> HashMap<String, AttributeValue> {
+        [("SK".to_string(), AttributeValue::S("Order".into()))].into()
+    }
+}
+
+fn count_keys(item: &HashMap<String, AttributeValue>) -> Result<usize, ConvertError> {
+    Ok(item.len())
+}
+
+fn order() -> Order {
+    Order {
+        id: "0b3a5d41".into(),
+        quantity: 3,
+        note: None,
+        keys: 4,
+    }
+}
+
+fn item() -> BTreeMap<String, AttributeValue> {
+    [
+        ("PK".to_string(), AttributeValue::S("0b3a5d41".into())),
+        ("SK".to_string(), AttributeValue::S("Order".into())),
+        ("Type".to_string(), AttributeValue::S("Order".into())),
+        ("Quantity".to_string(), AttributeValue::N("3".into())),
+    ]
+    .into()
+}
+
+#[test]
+fn test_convert_btree_map() {
+    let converted: BTreeMap<String, AttributeValue> = order().into_item_map();
+    assert_eq!(converted, item());
+
+    let converted: BTreeMap<String, AttributeValue> = order().try_into_item_map().unwrap();
+    assert_eq!(converted, item());
+
+    let converted: BTreeMap<String, AttributeValue> = order().to_item_map();
+    assert_eq!(converted, item());
+
+    let converted = Order::try_from_item_map(item()).unwrap();
+    assert_eq!(converted, order());
+}
+
+#[test]
+fn test_convert_hash_map_with_other_hasher() {
+    let expected: OtherHashMap = item().into_iter().collect();
+
+    let converted: OtherHashMap = order().into_item_map();
+    assert_eq!(converted, expected);
+
+    let converted = Order::try_from_item_map(expected).unwrap();
+    assert_eq!(converted, order());
+}
+
+#[test]
+fn test_convert_hash_map_as_before() {
+    let expected: HashMap<String, AttributeValue> = item().into_iter().collect();
+
+    let converted: HashMap<String, AttributeValue> = order().into_item_map();
+    assert_eq!(converted, expected);
+
+    let converted: HashMap<String, AttributeValue> = order().into();
+    assert_eq!(converted, expected);
+
+    let converted: Order = expected.try_into().unwrap();
+    assert_eq!(converted, order());
+}
+
+#[test]
+fn test_try_from_item_map_without_required_fields() {
+    let mut item = item();
+    item.remove("Quantity");
+
+    assert!(matches!(
+        Order::try_from_item_map(item),
+        Err(ConvertError::FieldNotSet(name)) if name == "quantity"
+    ));
+}
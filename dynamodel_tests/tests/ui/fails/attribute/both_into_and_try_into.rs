@@ -0,0 +1,9 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+struct VideoComment {
+    #[dynamodel(into = "foo", try_into = "bar")]
+    timestamp: String
+}
+
+fn main() {}
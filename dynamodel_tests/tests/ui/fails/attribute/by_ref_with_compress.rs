@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+#[dynamodel(by_ref)]
+struct Note {
+    id: String,
+    #[dynamodel(compress = "zstd")]
+    body: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynamodel::{Dynamodel, Serde};
+
+#[derive(Dynamodel)]
+#[dynamodel(by_ref)]
+struct User {
+    id: String,
+    #[dynamodel(fallible)]
+    settings: Serde<Vec<String>>,
+}
+
+fn main() {}
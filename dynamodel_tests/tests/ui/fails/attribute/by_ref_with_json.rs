@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+#[dynamodel(by_ref)]
+struct Image {
+    id: String,
+    #[dynamodel(json)]
+    tags: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+#[dynamodel(by_ref)]
+struct VideoComment {
+    #[dynamodel(try_into = "foo")]
+    timestamp: String
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+struct Document {
+    id: String,
+    #[dynamodel(compress = "brotli")]
+    body: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+struct Customer {
+    id: String,
+    #[dynamodel(encrypt)]
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dynamodel::{Dynamodel, Serde};
+
+#[derive(Dynamodel)]
+struct User {
+    id: String,
+    #[dynamodel(fallible, try_into = "dynamodel::AttributeValueConvertible::try_into_attribute_value")]
+    settings: Serde<Vec<String>>,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Video {
+    id: String,
+    #[dynamodel(format = "%Y-%m-%d %Q")]
+    published_at: SystemTime,
+}
+
+fn main() {}
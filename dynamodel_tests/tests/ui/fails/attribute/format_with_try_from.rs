@@ -0,0 +1,16 @@
+use dynamodel::{ConvertError, Dynamodel};
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Event {
+    id: String,
+    #[dynamodel(format = "epoch_seconds", try_from = "started_at")]
+    started_at: SystemTime,
+}
+
+fn started_at(_: &AttributeValue) -> Result<SystemTime, ConvertError> {
+    Ok(SystemTime::now())
+}
+
+fn main() {}
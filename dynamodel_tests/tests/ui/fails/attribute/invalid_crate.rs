@@ -0,0 +1,9 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+#[dynamodel(crate = "not a path")]
+struct Video {
+    id: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Event {
+    id: String,
+    #[dynamodel(json, format = "epoch_seconds")]
+    started_at: SystemTime,
+}
+
+fn main() {}
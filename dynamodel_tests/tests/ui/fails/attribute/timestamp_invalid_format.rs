@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Video {
+    id: String,
+    #[dynamodel(created_at, format = "iso8601")]
+    created_at: SystemTime,
+}
+
+fn main() {}
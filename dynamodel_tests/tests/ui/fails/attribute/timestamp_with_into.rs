@@ -0,0 +1,16 @@
+use dynamodel::Dynamodel;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Video {
+    id: String,
+    #[dynamodel(updated_at, into = "to_value")]
+    updated_at: SystemTime,
+}
+
+fn to_value(_: SystemTime) -> AttributeValue {
+    AttributeValue::Null(true)
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Session {
+    id: String,
+    #[dynamodel(ttl, updated_at)]
+    expires_at: SystemTime,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dynamodel::Dynamodel;
+use std::time::SystemTime;
+
+#[derive(Dynamodel)]
+struct Session {
+    id: String,
+    #[dynamodel(ttl, format = "epoch_millis")]
+    expires_at: SystemTime,
+}
+
+fn main() {}
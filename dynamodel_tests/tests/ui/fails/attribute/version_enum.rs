@@ -0,0 +1,12 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+enum Video {
+    Draft {
+        id: String,
+        #[dynamodel(version)]
+        version: u64,
+    },
+}
+
+fn main() {}
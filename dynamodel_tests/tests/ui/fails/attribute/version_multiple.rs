@@ -0,0 +1,12 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+struct Video {
+    id: String,
+    #[dynamodel(version)]
+    version: u64,
+    #[dynamodel(version)]
+    revision: u64,
+}
+
+fn main() {}
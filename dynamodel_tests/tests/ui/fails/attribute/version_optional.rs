@@ -0,0 +1,10 @@
+use dynamodel::Dynamodel;
+
+#[derive(Dynamodel)]
+struct Video {
+    id: String,
+    #[dynamodel(version)]
+    version: Option<u64>,
+}
+
+fn main() {}